@@ -19,7 +19,7 @@ impl Coinglass {
 			// headless_chrome drops the WebSocket connection if no events are received for
 			// `idle_browser_timeout` (defaults to 30s). This scanner can be idle for minutes
 			// between alerts, so use a long timeout to keep Chrome alive.
-			.idle_browser_timeout(Duration::from_secs(60 * 60 * 24))
+			.idle_browser_timeout(Duration::from_hours(24))
 			.window_size(Some((1920, 1920)))
 			.args(vec![
 				OsStr::new("--headless=new"),
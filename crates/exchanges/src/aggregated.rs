@@ -0,0 +1,691 @@
+use std::{collections::HashSet, future::Future, sync::Arc};
+
+use futures_util::{StreamExt, future::join_all, stream::BoxStream};
+use tracing::warn;
+
+use crate::{
+	BinanceExchange, BybitExchange, CandleInfo, ContractKind, Exchange, ExchangeError, ExchangeKind, FundingRateInfo,
+	Instrument, Interval, LocalOrderBook, LongShortRatio, LongShortRatioKind, MarkPriceInfo, MarketLiquidationsInfo,
+	OkxExchange, OpenInterestInfo, OpenInterestPoint, OrderBookLevel, StreamEvent, SymbolInfo, TakerVolume, TickerInfo,
+	TradeInfo, instrument::identity_key, utils::open_interest_info_from_series,
+};
+
+/// Result of the same call on every venue, in venue order
+pub type VenueResults<T> = Vec<(ExchangeKind, Result<T, ExchangeError>)>;
+
+/// Several venues behind one `Exchange`
+///
+/// Streams are merged, open interest and taker volume are summed across venues, everything else is served by
+/// the first venue that answers. Venues that fail are logged and left out of merged results.
+/// Symbols are matched by `InstrumentId`, so `1000SHIBUSDT` is looked up as `SHIB1000USDT` on Bybit,
+/// and prices and quantities of a symbol are converted into its units, e.g. for `PEPEUSDT` served as `1000PEPEUSDT`. Venues not listing the instrument,
+/// e.g. Binance COIN-M for `BTCUSDT`, are skipped.
+#[derive(Clone)]
+pub struct AggregatedExchange {
+	venues: Vec<Venue>,
+}
+
+#[derive(Clone)]
+struct Venue {
+	kind: ExchangeKind,
+	exchange: Arc<dyn Exchange + Send + Sync>,
+}
+
+/// Listing of a requested symbol on one venue
+struct VenueSymbol {
+	symbol: String,
+	requested: String,
+	/// Converts base quantities of `symbol` into units of the requested symbol
+	scale: f64,
+}
+
+impl VenueSymbol {
+	/// `value` of the venue's listing in units of the requested symbol
+	fn convert<T: Rescale>(&self, value: T) -> T {
+		value.rescale(&self.requested, self.scale)
+	}
+}
+
+/// Values reported per unit of a venue's listing
+trait Rescale {
+	/// Quantities are multiplied and prices divided by `scale`, symbols are replaced by `symbol`
+	fn rescale(self, symbol: &str, scale: f64) -> Self;
+}
+
+impl<T: Rescale> Rescale for Vec<T> {
+	fn rescale(self, symbol: &str, scale: f64) -> Self {
+		self.into_iter().map(|value| value.rescale(symbol, scale)).collect()
+	}
+}
+
+impl Rescale for TradeInfo {
+	fn rescale(self, symbol: &str, scale: f64) -> Self {
+		Self { symbol: String::from(symbol), price: self.price / scale, quantity: self.quantity * scale, ..self }
+	}
+}
+
+impl Rescale for CandleInfo {
+	fn rescale(self, _symbol: &str, scale: f64) -> Self {
+		Self {
+			open: self.open / scale,
+			high: self.high / scale,
+			low: self.low / scale,
+			close: self.close / scale,
+			volume: self.volume * scale,
+			taker_buy_volume: self.taker_buy_volume.map(|volume| volume * scale),
+			..self
+		}
+	}
+}
+
+impl Rescale for LocalOrderBook {
+	fn rescale(self, symbol: &str, scale: f64) -> Self {
+		let level = |level: OrderBookLevel| OrderBookLevel { price: level.price / scale, quantity: level.quantity * scale };
+
+		Self::from_snapshot(symbol, self.last_update_id(), self.bids().map(level), self.asks().map(level), self.time())
+	}
+}
+
+impl Rescale for OpenInterestPoint {
+	fn rescale(self, _symbol: &str, scale: f64) -> Self {
+		Self { open_interest: self.open_interest * scale, ..self }
+	}
+}
+
+impl Rescale for TakerVolume {
+	fn rescale(self, _symbol: &str, scale: f64) -> Self {
+		Self { buy_volume: self.buy_volume * scale, sell_volume: self.sell_volume * scale, ..self }
+	}
+}
+
+impl Venue {
+	/// Perpetual of the same instrument as `symbol`, `None` when the venue doesn't list it.
+	/// `symbol` is kept as is when the venue's listing can't be fetched.
+	async fn resolve(&self, symbol: &str) -> Option<VenueSymbol> {
+		let (Some(wanted), Ok(symbols)) = (Instrument::parse(symbol), self.exchange.get_symbols().await) else {
+			return Some(VenueSymbol { symbol: String::from(symbol), requested: String::from(symbol), scale: 1.0 });
+		};
+
+		let listed: Vec<_> = symbols
+			.iter()
+			.filter(|info| info.contract_kind == ContractKind::Perpetual)
+			.map(Instrument::from_symbol_info)
+			.collect();
+
+		listed
+			.iter()
+			.find(|instrument| instrument.venue_symbol == symbol)
+			.or_else(|| listed.iter().find(|instrument| instrument.id() == wanted.id()))
+			.map(|instrument| VenueSymbol {
+				symbol: instrument.venue_symbol.clone(),
+				requested: String::from(symbol),
+				scale: instrument.multiplier as f64 / wanted.multiplier as f64,
+			})
+	}
+
+	/// Open interest in units of the requested symbol
+	async fn open_interest_history(
+		&self,
+		listing: VenueSymbol,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<OpenInterestPoint>, ExchangeError> {
+		let points = self.exchange.get_open_interest_history(&listing.symbol, period, limit).await?;
+		Ok(listing.convert(points))
+	}
+
+	/// Taker volume in units of the requested symbol
+	async fn taker_volume(
+		&self,
+		listing: VenueSymbol,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<TakerVolume>, ExchangeError> {
+		let volumes = self.exchange.get_taker_volume(&listing.symbol, period, limit).await?;
+		Ok(listing.convert(volumes))
+	}
+}
+
+impl AggregatedExchange {
+	#[must_use]
+	pub fn new(venues: Vec<(ExchangeKind, Arc<dyn Exchange + Send + Sync>)>) -> Self {
+		Self { venues: venues.into_iter().map(|(kind, exchange)| Venue { kind, exchange }).collect() }
+	}
+
+	/// Venues with default clients, duplicates are skipped
+	#[must_use]
+	pub fn from_kinds(kinds: &[ExchangeKind]) -> Self {
+		let mut seen = HashSet::new();
+
+		Self::new(
+			kinds
+				.iter()
+				.filter(|kind| seen.insert(**kind))
+				.map(|kind| {
+					let exchange: Arc<dyn Exchange + Send + Sync> = match kind {
+						ExchangeKind::Binance => Arc::new(BinanceExchange::new()),
+						ExchangeKind::Bybit => Arc::new(BybitExchange::new()),
+						ExchangeKind::Okx => Arc::new(OkxExchange::new()),
+					};
+					(*kind, exchange)
+				})
+				.collect(),
+		)
+	}
+
+	/// Add a venue with a custom client, e.g. the Binance COIN-M market next to USDⓈ-M
+	#[must_use]
+	pub fn with_venue(mut self, kind: ExchangeKind, exchange: Arc<dyn Exchange + Send + Sync>) -> Self {
+		self.venues.push(Venue { kind, exchange });
+		self
+	}
+
+	pub fn venues(&self) -> impl Iterator<Item = ExchangeKind> + '_ {
+		self.venues.iter().map(|venue| venue.kind)
+	}
+
+	/// Client of every venue, for results that must stay attributed to a venue such as new listings
+	pub fn venue_clients(&self) -> impl Iterator<Item = (ExchangeKind, Arc<dyn Exchange + Send + Sync>)> + '_ {
+		self.venues.iter().map(|venue| (venue.kind, Arc::clone(&venue.exchange)))
+	}
+
+	/// Klines of `symbol` from every venue in units of `symbol`
+	pub async fn get_klines_by_venue(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		limit: u32,
+	) -> VenueResults<Vec<CandleInfo>> {
+		self
+			.on_listing_venues(symbol, |venue, listing| async move {
+				Ok(listing.convert(venue.exchange.get_klines(&listing.symbol, interval, limit).await?))
+			})
+			.await
+	}
+
+	/// Funding of `symbol` from every venue
+	pub async fn get_funding_rate_info_by_venue(&self, symbol: &str) -> VenueResults<FundingRateInfo> {
+		self
+			.on_listing_venues(
+				symbol,
+				|venue, listing| async move { venue.exchange.get_funding_rate_info(&listing.symbol).await },
+			)
+			.await
+	}
+
+	/// Open interest of `symbol` from every venue in units of `symbol`, see `get_open_interest_history` for the sum
+	pub async fn get_open_interest_history_by_venue(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> VenueResults<Vec<OpenInterestPoint>> {
+		self.on_listing_venues(symbol, |venue, listing| venue.open_interest_history(listing, period, limit)).await
+	}
+
+	async fn on_every_venue<'a, T, F, Fut>(&'a self, call: F) -> VenueResults<T>
+	where
+		F: Fn(&'a Venue) -> Fut,
+		Fut: Future<Output = Result<T, ExchangeError>>,
+	{
+		join_all(self.venues.iter().map(|venue| {
+			let result = call(venue);
+			async move { (venue.kind, result.await) }
+		}))
+		.await
+	}
+
+	/// Like `on_every_venue` for the venues listing `symbol`, `call` gets the venue's listing of it
+	async fn on_listing_venues<'a, T, F, Fut>(&'a self, symbol: &str, call: F) -> VenueResults<T>
+	where
+		F: Fn(&'a Venue, VenueSymbol) -> Fut,
+		Fut: Future<Output = Result<T, ExchangeError>>,
+	{
+		let listings = join_all(self.venues.iter().map(|venue| async move { (venue, venue.resolve(symbol).await) })).await;
+
+		join_all(listings.into_iter().filter_map(|(venue, listing)| {
+			let result = call(venue, listing?);
+			Some(async move { (venue.kind, result.await) })
+		}))
+		.await
+	}
+
+	/// Results of the venues listing `symbol` that answered, `UnknownSymbol` when no venue lists it
+	async fn successful_for_symbol<'a, T, F, Fut>(&'a self, symbol: &str, call: F) -> Result<Vec<T>, ExchangeError>
+	where
+		F: Fn(&'a Venue, VenueSymbol) -> Fut,
+		Fut: Future<Output = Result<T, ExchangeError>>,
+	{
+		let results = self.on_listing_venues(symbol, call).await;
+		if results.is_empty() {
+			return Err(ExchangeError::UnknownSymbol(String::from(symbol)));
+		}
+
+		successful(results)
+	}
+
+	/// Venues listing `symbol` are tried in order, the last error is returned when none answers
+	async fn first_successful<'a, T, F, Fut>(&'a self, symbol: &str, call: F) -> Result<T, ExchangeError>
+	where
+		F: Fn(&'a Venue, VenueSymbol) -> Fut,
+		Fut: Future<Output = Result<T, ExchangeError>>,
+	{
+		let mut last_error = ExchangeError::UnknownSymbol(String::from(symbol));
+
+		for venue in &self.venues {
+			let Some(listing) = venue.resolve(symbol).await else {
+				continue;
+			};
+
+			match call(venue, listing).await {
+				Ok(value) => return Ok(value),
+				// Venues that never report the data are skipped without noise
+				Err(e @ ExchangeError::Unsupported(_)) => last_error = e,
+				Err(e) => {
+					warn!("{} failed, trying the next venue: {e}", venue.kind.name());
+					last_error = e;
+				},
+			}
+		}
+
+		Err(last_error)
+	}
+
+	fn merged<T: Send + 'static>(
+		&self,
+		watch: impl Fn(&(dyn Exchange + Send + Sync)) -> BoxStream<'static, StreamEvent<T>>,
+	) -> BoxStream<'static, StreamEvent<T>> {
+		futures_util::stream::select_all(self.venues.iter().map(|venue| watch(venue.exchange.as_ref()))).boxed()
+	}
+
+	/// Like `merged`, each venue's stream opens once `symbol` is resolved to its listing, venues not listing it are left out.
+	/// Items are converted into units of `symbol`.
+	fn merged_for_symbol<T: Rescale + Send + 'static>(
+		&self,
+		symbol: &str,
+		watch: fn(&(dyn Exchange + Send + Sync), &str) -> BoxStream<'static, StreamEvent<T>>,
+	) -> BoxStream<'static, StreamEvent<T>> {
+		futures_util::stream::select_all(self.venues.iter().cloned().map(|venue| {
+			let symbol = String::from(symbol);
+			futures_util::stream::once(async move {
+				venue.resolve(&symbol).await.map_or_else(
+					|| futures_util::stream::empty().boxed(),
+					|listing| {
+						watch(venue.exchange.as_ref(), &listing.symbol)
+							.map(move |event| match event {
+								StreamEvent::Data(value) => StreamEvent::Data(listing.convert(value)),
+								StreamEvent::Reconnected { disconnected_at, reconnected_at } => {
+									StreamEvent::Reconnected { disconnected_at, reconnected_at }
+								},
+							})
+							.boxed()
+					},
+				)
+			})
+			.flatten()
+			.boxed()
+		}))
+		.boxed()
+	}
+}
+
+#[async_trait::async_trait]
+impl Exchange for AggregatedExchange {
+	/// Pairs trading on any venue, spelled as the first venue listing them does
+	async fn get_all_usdt_pairs(&self) -> Result<Vec<String>, ExchangeError> {
+		let mut seen = HashSet::new();
+
+		let mut pairs: Vec<String> = successful(self.on_every_venue(|venue| venue.exchange.get_all_usdt_pairs()).await)?
+			.into_iter()
+			.flatten()
+			.filter(|pair| seen.insert(identity_key(pair)))
+			.collect();
+		pairs.sort();
+
+		Ok(pairs)
+	}
+
+	/// Symbols of every venue, an instrument listed on several venues is described by the first of them
+	async fn get_symbols(&self) -> Result<Vec<SymbolInfo>, ExchangeError> {
+		let mut seen = HashSet::new();
+
+		Ok(
+			successful(self.on_every_venue(|venue| venue.exchange.get_symbols()).await)?
+				.into_iter()
+				.flatten()
+				.filter(|info| {
+					// Delivery contracts of one instrument differ by expiry, only perpetuals are merged
+					let key = match info.contract_kind {
+						ContractKind::Perpetual => Instrument::from_symbol_info(info).id().to_string(),
+						_ => info.symbol.clone(),
+					};
+					seen.insert(key)
+				})
+				.collect(),
+		)
+	}
+
+	fn watch_market_liquidations(&self) -> BoxStream<'static, StreamEvent<MarketLiquidationsInfo>> {
+		self.merged(|exchange| exchange.watch_market_liquidations())
+	}
+
+	fn watch_tickers(&self) -> BoxStream<'static, StreamEvent<TickerInfo>> {
+		self.merged(|exchange| exchange.watch_tickers())
+	}
+
+	fn watch_mark_prices(&self) -> BoxStream<'static, StreamEvent<MarkPriceInfo>> {
+		self.merged(|exchange| exchange.watch_mark_prices())
+	}
+
+	fn watch_trades(&self, symbol: &str) -> BoxStream<'static, StreamEvent<TradeInfo>> {
+		self.merged_for_symbol(symbol, |exchange, symbol| exchange.watch_trades(symbol))
+	}
+
+	async fn get_klines(&self, symbol: &str, interval: Interval, limit: u32) -> Result<Vec<CandleInfo>, ExchangeError> {
+		self
+			.first_successful(symbol, |venue, listing| async move {
+				Ok(listing.convert(venue.exchange.get_klines(&listing.symbol, interval, limit).await?))
+			})
+			.await
+	}
+
+	async fn get_klines_range(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<CandleInfo>, ExchangeError> {
+		self
+			.first_successful(symbol, |venue, listing| async move {
+				Ok(listing.convert(venue.exchange.get_klines_range(&listing.symbol, interval, from, to).await?))
+			})
+			.await
+	}
+
+	/// Changes of the open interest summed across venues
+	async fn get_open_interest_info(&self, symbol: &str) -> Result<OpenInterestInfo, ExchangeError> {
+		// 48 x 5m to get 5m - 4h distance, 30 x 1d for 30 days
+		let (series_5m, series_1d) = tokio::try_join!(
+			self.get_open_interest_history(symbol, Interval::Minute5, 48),
+			self.get_open_interest_history(symbol, Interval::Day1, 30),
+		)?;
+
+		Ok(open_interest_info_from_series(&series_5m, &series_1d)?)
+	}
+
+	/// Summed over the timestamps every answering venue reports
+	async fn get_open_interest_history(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<OpenInterestPoint>, ExchangeError> {
+		let series =
+			self.successful_for_symbol(symbol, |venue, listing| venue.open_interest_history(listing, period, limit)).await?;
+
+		Ok(sum_common_timestamps(
+			&series,
+			|point| point.timestamp,
+			|total, point| {
+				total.open_interest += point.open_interest;
+				total.open_interest_usd = total.open_interest_usd.zip(point.open_interest_usd).map(|(a, b)| a + b);
+			},
+		))
+	}
+
+	async fn get_order_book(&self, symbol: &str, limit: u32) -> Result<LocalOrderBook, ExchangeError> {
+		self
+			.first_successful(symbol, |venue, listing| async move {
+				Ok(listing.convert(venue.exchange.get_order_book(&listing.symbol, limit).await?))
+			})
+			.await
+	}
+
+	async fn get_funding_rate_info(&self, symbol: &str) -> Result<FundingRateInfo, ExchangeError> {
+		self
+			.first_successful(
+				symbol,
+				|venue, listing| async move { venue.exchange.get_funding_rate_info(&listing.symbol).await },
+			)
+			.await
+	}
+
+	async fn get_long_short_ratio(
+		&self,
+		symbol: &str,
+		kind: LongShortRatioKind,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<LongShortRatio>, ExchangeError> {
+		self
+			.first_successful(symbol, |venue, listing| async move {
+				venue.exchange.get_long_short_ratio(&listing.symbol, kind, period, limit).await
+			})
+			.await
+	}
+
+	/// Summed over the timestamps every answering venue reports
+	async fn get_taker_volume(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<TakerVolume>, ExchangeError> {
+		let series =
+			self.successful_for_symbol(symbol, |venue, listing| venue.taker_volume(listing, period, limit)).await?;
+
+		Ok(sum_common_timestamps(
+			&series,
+			|volume| volume.timestamp,
+			|total, volume| {
+				total.buy_volume += volume.buy_volume;
+				total.sell_volume += volume.sell_volume;
+			},
+		))
+	}
+}
+
+/// Values of the venues that answered, the first error when none did
+fn successful<T>(results: VenueResults<T>) -> Result<Vec<T>, ExchangeError> {
+	let mut values = Vec::new();
+	let mut first_error = None;
+
+	for (kind, result) in results {
+		match result {
+			Ok(value) => values.push(value),
+			Err(e @ ExchangeError::Unsupported(_)) => {
+				first_error.get_or_insert(e);
+			},
+			Err(e) => {
+				warn!("{} left out of aggregated result: {e}", kind.name());
+				first_error.get_or_insert(e);
+			},
+		}
+	}
+
+	match first_error {
+		Some(e) if values.is_empty() => Err(e),
+		_ => Ok(values),
+	}
+}
+
+/// Add up the points of several series that share a timestamp, timestamps missing from any series are dropped.
+/// The order of the first series is kept.
+fn sum_common_timestamps<T: Clone>(series: &[Vec<T>], timestamp: fn(&T) -> u64, add: fn(&mut T, &T)) -> Vec<T> {
+	let Some((first, rest)) = series.split_first() else {
+		return Vec::new();
+	};
+
+	first
+		.iter()
+		.filter_map(|point| {
+			let mut total = point.clone();
+			for other in rest {
+				add(&mut total, other.iter().find(|other| timestamp(other) == timestamp(point))?);
+			}
+			Some(total)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use super::*;
+	use crate::{
+		BinanceExchange,
+		test_support::{MockResponse, MockServer, WsAction},
+	};
+
+	const KLINES: &str = include_str!("../fixtures/binance/klines.json");
+	const OPEN_INTEREST_HIST: &str = include_str!("../fixtures/binance/open_interest_hist.json");
+	const AGG_TRADE: &str = include_str!("../fixtures/binance/agg_trade.json");
+	const DEPTH: &str = include_str!("../fixtures/binance/depth.json");
+	const MULTIPLIED_EXCHANGE_INFO: &str = r#"{"symbols": [{
+		"symbol": "1000BTCUSDT", "pair": "1000BTCUSDT", "contractType": "PERPETUAL", "onboardDate": 1569398400000,
+		"status": "TRADING", "baseAsset": "1000BTC", "quoteAsset": "USDT",
+		"filters": [
+			{ "filterType": "PRICE_FILTER", "tickSize": "0.10" },
+			{ "filterType": "LOT_SIZE", "stepSize": "0.001" }
+		]
+	}]}"#;
+
+	fn point(open_interest: f64, timestamp: u64) -> OpenInterestPoint {
+		OpenInterestPoint { open_interest, open_interest_usd: Some(open_interest * 10.0), timestamp }
+	}
+
+	#[test]
+	fn sums_series_on_common_timestamps() {
+		let series = vec![vec![point(1.0, 0), point(2.0, 300), point(3.0, 600)], vec![point(10.0, 300), point(20.0, 600)]];
+
+		let summed = sum_common_timestamps(
+			&series,
+			|point| point.timestamp,
+			|total, point| {
+				total.open_interest += point.open_interest;
+				total.open_interest_usd = total.open_interest_usd.zip(point.open_interest_usd).map(|(a, b)| a + b);
+			},
+		);
+
+		assert_eq!(summed, vec![point(12.0, 300), point(23.0, 600)]);
+	}
+
+	#[tokio::test]
+	async fn falls_back_to_next_venue() {
+		let failing = MockServer::start().await.unwrap();
+		failing.route("/fapi/v1/klines", MockResponse::status(400));
+		let serving = MockServer::start().await.unwrap();
+		serving.route("/fapi/v1/klines", MockResponse::json(KLINES));
+
+		let venue = |server: &MockServer| -> Arc<dyn Exchange + Send + Sync> {
+			Arc::new(BinanceExchange::builder().rest_base_url(server.rest_url()).build())
+		};
+		let exchange =
+			AggregatedExchange::new(vec![(ExchangeKind::Binance, venue(&failing)), (ExchangeKind::Okx, venue(&serving))]);
+
+		let by_venue = exchange.get_klines_by_venue("BTCUSDT", Interval::Hour1, 2).await;
+
+		assert!(!exchange.get_klines("BTCUSDT", Interval::Hour1, 2).await.unwrap().is_empty());
+		assert!(by_venue[0].1.is_err());
+		assert_eq!(by_venue[1].0, ExchangeKind::Okx);
+	}
+
+	#[tokio::test]
+	async fn converts_multiplied_listings() {
+		let plain = MockServer::start().await.unwrap();
+		plain.route("/futures/data/openInterestHist", MockResponse::json(OPEN_INTEREST_HIST));
+		let multiplied = MockServer::start().await.unwrap();
+		multiplied.route("/fapi/v1/exchangeInfo", MockResponse::json(MULTIPLIED_EXCHANGE_INFO));
+		multiplied.route("/futures/data/openInterestHist", MockResponse::json(OPEN_INTEREST_HIST));
+
+		let venue = |server: &MockServer| -> Arc<dyn Exchange + Send + Sync> {
+			Arc::new(BinanceExchange::builder().rest_base_url(server.rest_url()).build())
+		};
+		let exchange =
+			AggregatedExchange::new(vec![(ExchangeKind::Binance, venue(&plain)), (ExchangeKind::Bybit, venue(&multiplied))]);
+
+		let summed = exchange.get_open_interest_history("BTCUSDT", Interval::Minute5, 2).await.unwrap();
+
+		assert!((summed[0].open_interest - 81_081_000.0).abs() < 1e-6);
+		assert!(multiplied.requests().iter().any(|request| request.contains("symbol=1000BTCUSDT")));
+	}
+
+	#[tokio::test]
+	async fn serves_multiplied_listing_in_requested_units() {
+		let multiplied = MockServer::start().await.unwrap();
+		multiplied.route("/fapi/v1/exchangeInfo", MockResponse::json(MULTIPLIED_EXCHANGE_INFO));
+		multiplied.route("/fapi/v1/klines", MockResponse::json(KLINES));
+		multiplied.route("/fapi/v1/depth", MockResponse::json(DEPTH));
+
+		let venue: Arc<dyn Exchange + Send + Sync> =
+			Arc::new(BinanceExchange::builder().rest_base_url(multiplied.rest_url()).build());
+		let exchange = AggregatedExchange::new(vec![(ExchangeKind::Binance, venue)]);
+
+		let candles = exchange.get_klines("BTCUSDT", Interval::Hour1, 2).await.unwrap();
+		let book = exchange.get_order_book("BTCUSDT", 5).await.unwrap();
+
+		// The venue quotes 1000 BTC per contract, so prices shrink and quantities grow by 1000
+		assert!((candles[1].close - 97.5005).abs() < 1e-9);
+		assert_eq!(book.symbol(), "BTCUSDT");
+		assert_eq!(book.best_bid(), Some(OrderBookLevel { price: 97.0, quantity: 1_500.0 }));
+	}
+
+	#[tokio::test]
+	async fn merges_trades_of_multiplied_listings_in_requested_units() {
+		let plain = MockServer::start().await.unwrap();
+		plain.session(vec![WsAction::Text(String::from(AGG_TRADE))]);
+		let multiplied = MockServer::start().await.unwrap();
+		multiplied.route("/fapi/v1/exchangeInfo", MockResponse::json(MULTIPLIED_EXCHANGE_INFO));
+		multiplied.session(vec![WsAction::Text(
+			AGG_TRADE
+				.replace(r#""BTCUSDT""#, r#""1000BTCUSDT""#)
+				.replace("97012.40", "97012400.00")
+				.replace("0.250", "0.001"),
+		)]);
+
+		let venue = |server: &MockServer| -> Arc<dyn Exchange + Send + Sync> {
+			Arc::new(BinanceExchange::builder().rest_base_url(server.rest_url()).ws_base_url(server.ws_url()).build())
+		};
+		let exchange =
+			AggregatedExchange::new(vec![(ExchangeKind::Binance, venue(&plain)), (ExchangeKind::Bybit, venue(&multiplied))]);
+
+		let trades = exchange.watch_trades("BTCUSDT").take(2).collect::<Vec<_>>();
+		let events = tokio::time::timeout(Duration::from_secs(5), trades).await.unwrap();
+		let mut trades: Vec<_> = events
+			.into_iter()
+			.filter_map(|event| match event {
+				StreamEvent::Data(trade) => Some(trade),
+				StreamEvent::Reconnected { .. } => None,
+			})
+			.collect();
+		trades.sort_by(|a, b| a.quantity.total_cmp(&b.quantity));
+
+		assert_eq!(trades.len(), 2);
+		assert!(trades.iter().all(|trade| trade.symbol == "BTCUSDT" && (trade.price - 97_012.4).abs() < 1e-6));
+		assert!((trades[0].quantity - 0.25).abs() < 1e-9);
+		assert!((trades[1].quantity - 1.0).abs() < 1e-9);
+	}
+
+	#[tokio::test]
+	async fn skips_venues_not_listing_symbol() {
+		let listing = MockServer::start().await.unwrap();
+		listing.route("/futures/data/openInterestHist", MockResponse::json(OPEN_INTEREST_HIST));
+		let other = MockServer::start().await.unwrap();
+		other.route("/fapi/v1/exchangeInfo", MockResponse::json(MULTIPLIED_EXCHANGE_INFO));
+
+		let venue = |server: &MockServer| -> Arc<dyn Exchange + Send + Sync> {
+			Arc::new(BinanceExchange::builder().rest_base_url(server.rest_url()).build())
+		};
+		let exchange =
+			AggregatedExchange::new(vec![(ExchangeKind::Binance, venue(&listing)), (ExchangeKind::Bybit, venue(&other))]);
+
+		let by_venue = exchange.get_open_interest_history_by_venue("ETHUSDT", Interval::Minute5, 2).await;
+
+		assert_eq!(by_venue.len(), 1);
+		assert_eq!(by_venue[0].0, ExchangeKind::Binance);
+		assert!(exchange.get_open_interest_history("ETHUSDT", Interval::Minute5, 2).await.is_ok());
+		assert!(other.requests().iter().all(|request| !request.contains("openInterestHist")));
+	}
+}
@@ -0,0 +1,153 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{CandleInfo, ContractKind, Instrument, SymbolInfo};
+
+/// Market whose volume drove a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketLead {
+	Spot,
+	Derivatives,
+}
+
+/// Perpetual and the spot pair of the same instrument, e.g. `1000PEPEUSDT` and `PEPEUSDT`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpotPerpPair {
+	pub perp: Instrument,
+	pub spot: Instrument,
+}
+
+impl SpotPerpPair {
+	/// Trading spot listing of `perp` among `spot_symbols`, matched by `InstrumentId`
+	#[must_use]
+	pub fn find(perp: &str, spot_symbols: &[SymbolInfo]) -> Option<Self> {
+		let perp = Instrument::parse(perp)?;
+		let spot = spot_symbols
+			.iter()
+			.filter(|info| info.contract_kind == ContractKind::Spot && info.is_trading())
+			.map(Instrument::from_symbol_info)
+			.find(|spot| spot.id() == perp.id())?;
+
+		Some(Self { perp, spot })
+	}
+
+	/// Spot price in the units the perpetual is quoted in, e.g. per 1000 PEPE for `1000PEPEUSDT`
+	#[must_use]
+	pub fn spot_in_perp_units(&self, spot_price: f64) -> f64 {
+		spot_price * self.perp.multiplier as f64 / self.spot.multiplier as f64
+	}
+
+	/// Perpetual premium over spot in percent, negative when the perpetual trades at a discount
+	#[must_use]
+	pub fn basis_percent(&self, perp_price: f64, spot_price: f64) -> f64 {
+		let spot_price = self.spot_in_perp_units(spot_price);
+		(perp_price - spot_price) / spot_price * 100.0
+	}
+
+	/// Basis at the close of the latest candle closed on both markets, so both prices are from the same instant
+	#[must_use]
+	pub fn closed_basis_percent(&self, perp: &[CandleInfo], spot: &[CandleInfo]) -> Option<f64> {
+		let spot_closes: HashMap<u64, f64> =
+			spot.iter().filter(|candle| candle.is_closed()).map(|candle| (candle.open_time, candle.close)).collect();
+
+		perp
+			.iter()
+			.rev()
+			.filter(|candle| candle.is_closed())
+			.find_map(|candle| Some(self.basis_percent(candle.close, *spot_closes.get(&candle.open_time)?)))
+	}
+}
+
+/// Market whose quote volume of the last `recent` candles grew more over the candles before them.
+///
+/// Only candles opened at the same time on both markets count, `None` when either market has no baseline volume.
+#[must_use]
+pub fn market_lead(perp: &[CandleInfo], spot: &[CandleInfo], recent: usize) -> Option<MarketLead> {
+	let perp_times: HashSet<u64> = perp.iter().map(|candle| candle.open_time).collect();
+	let spot_times: HashSet<u64> = spot.iter().map(|candle| candle.open_time).collect();
+	let quote_volumes = |candles: &[CandleInfo], other_times: &HashSet<u64>| -> Vec<f64> {
+		candles.iter().filter(|candle| other_times.contains(&candle.open_time)).map(|candle| candle.quote_volume).collect()
+	};
+
+	let perp_growth = volume_growth(&quote_volumes(perp, &spot_times), recent)?;
+	let spot_growth = volume_growth(&quote_volumes(spot, &perp_times), recent)?;
+
+	Some(if spot_growth > perp_growth { MarketLead::Spot } else { MarketLead::Derivatives })
+}
+
+/// Mean of the last `recent` volumes relative to the mean of the earlier ones
+fn volume_growth(volumes: &[f64], recent: usize) -> Option<f64> {
+	let (baseline, recent) = volumes.split_at(volumes.len().checked_sub(recent)?);
+	let mean = |volumes: &[f64]| volumes.iter().sum::<f64>() / volumes.len() as f64;
+
+	if baseline.is_empty() || recent.is_empty() {
+		return None;
+	}
+
+	let baseline = mean(baseline);
+	(baseline > 0.0).then(|| mean(recent) / baseline)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::SymbolStatus;
+
+	fn spot_symbol(symbol: &str, base_asset: &str, status: SymbolStatus) -> SymbolInfo {
+		SymbolInfo {
+			symbol: String::from(symbol),
+			base_asset: String::from(base_asset),
+			quote_asset: String::from("USDT"),
+			contract_kind: ContractKind::Spot,
+			onboard_date: 0,
+			tick_size: 0.000_000_01,
+			step_size: 1.0,
+			min_notional: Some(5.0),
+			contract_size: None,
+			status,
+		}
+	}
+
+	fn candles(quote_volumes: &[f64]) -> Vec<CandleInfo> {
+		(0..)
+			.zip(quote_volumes)
+			.map(|(open_time, quote_volume)| CandleInfo { open_time, quote_volume: *quote_volume, ..Default::default() })
+			.collect()
+	}
+
+	#[test]
+	fn pairs_multiplied_perpetual_with_spot() {
+		let spot_symbols =
+			[spot_symbol("PEPEUSDT", "PEPE", SymbolStatus::Trading), spot_symbol("LUNAUSDT", "LUNA", SymbolStatus::Closed)];
+
+		let pepe = SpotPerpPair::find("1000PEPEUSDT", &spot_symbols).unwrap();
+
+		assert_eq!(pepe.spot.venue_symbol, "PEPEUSDT");
+		assert!((pepe.spot_in_perp_units(0.000_01) - 0.01).abs() < 1e-12);
+		assert!((pepe.basis_percent(0.010_05, 0.000_01) - 0.5).abs() < 1e-9);
+		assert!(SpotPerpPair::find("LUNA2USDT", &spot_symbols).is_none());
+	}
+
+	#[test]
+	fn takes_basis_at_last_common_close() {
+		let pepe = SpotPerpPair::find("1000PEPEUSDT", &[spot_symbol("PEPEUSDT", "PEPE", SymbolStatus::Trading)]).unwrap();
+		let candle = |open_time, close_time, close| CandleInfo { open_time, close_time, close, ..Default::default() };
+		// The last perpetual candle is still open and the spot market hasn't reported the candle before it yet
+		let perp = [candle(0, 1, 0.010_05), candle(1, 2, 0.011), candle(2, u64::MAX, 0.012)];
+		let spot = [candle(0, 1, 0.000_01)];
+
+		assert!((pepe.closed_basis_percent(&perp, &spot).unwrap() - 0.5).abs() < 1e-9);
+		assert_eq!(pepe.closed_basis_percent(&perp, &[]), None);
+	}
+
+	#[test]
+	fn finds_market_leading_volume() {
+		let perp = candles(&[100.0, 100.0, 100.0, 150.0]);
+		let spot_led = candles(&[10.0, 10.0, 10.0, 40.0]);
+		let derivatives_led = candles(&[10.0, 10.0, 10.0, 12.0]);
+
+		assert_eq!(market_lead(&perp, &spot_led, 1), Some(MarketLead::Spot));
+		assert_eq!(market_lead(&perp, &derivatives_led, 1), Some(MarketLead::Derivatives));
+		assert_eq!(market_lead(&perp, &spot_led[3..], 1), None);
+	}
+}
@@ -1,6 +1,17 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{Interval, Side};
+
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/error-code
+#[derive(Debug, Deserialize)]
+pub struct ApiErrorResponse {
+	pub code: i64,
+	pub msg: String,
+}
+
 // https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Exchange-Information
+// https://developers.binance.com/docs/derivatives/coin-margined-futures/market-data/Exchange-Information
+// https://developers.binance.com/docs/binance-spot-api-docs/rest-api/general-endpoints#exchange-information
 #[derive(Debug, Deserialize)]
 pub struct ExchangeInfoResponse {
 	pub symbols: Vec<SymbolInfo>,
@@ -10,21 +21,65 @@ pub struct ExchangeInfoResponse {
 pub struct SymbolInfo {
 	pub symbol: String,
 	#[allow(dead_code)]
+	#[serde(default)]
 	pub pair: String,
+	/// Not reported for spot pairs
+	pub contract_type: Option<ContractType>,
+	/// Milliseconds since epoch, not reported for spot pairs
+	#[serde(default)]
+	pub onboard_date: u64,
+	pub base_asset: String,
 	pub quote_asset: String,
+	#[serde(alias = "contractStatus")]
 	pub status: SymbolInfoStatus,
+	pub filters: Vec<SymbolFilter>,
+	/// USD value of one contract, COIN-M only
+	pub contract_size: Option<f64>,
+}
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContractType {
+	Perpetual,
+	CurrentQuarter,
+	NextQuarter,
+	#[serde(other)]
+	Unknown,
 }
 // [...new Set($json.data.symbols.map(s => s.status))] in Firefox
 #[derive(Debug, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SymbolInfoStatus {
-	Trading,
-	Settling,
 	PendingTrading,
+	Trading,
+	PreDelivering,
+	Delivering,
+	Delivered,
 	PreSettle,
+	Settling,
+	Close,
+	/// Spot pair without trading, usually delisted
+	Break,
+	/// Spot trading halted
+	Halt,
 	#[serde(other)]
 	Unknown,
 }
+#[derive(Debug, Deserialize)]
+#[serde(tag = "filterType", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SymbolFilter {
+	#[serde(rename_all = "camelCase")]
+	PriceFilter { tick_size: String },
+	#[serde(rename_all = "camelCase")]
+	LotSize { step_size: String },
+	/// `NOTIONAL` with `minNotional` on spot
+	#[serde(alias = "NOTIONAL")]
+	MinNotional {
+		#[serde(alias = "minNotional")]
+		notional: String,
+	},
+	#[serde(other)]
+	Other,
+}
 
 // https://developers.binance.com/docs/derivatives/usds-margined-futures/websocket-market-streams/Liquidation-Order-Streams
 #[derive(Debug, Deserialize)]
@@ -43,7 +98,7 @@ pub struct ForceOrderInfo {
 	#[serde(rename = "s")]
 	pub symbol: String,
 	#[serde(rename = "S")]
-	pub side: String,
+	pub side: Side,
 	#[serde(rename = "o")]
 	pub order_type: String,
 	#[serde(rename = "f")]
@@ -69,41 +124,62 @@ pub struct ForceOrderInfo {
 #[allow(dead_code)]
 pub struct DailyTickerStream {
 	#[serde(rename = "e")]
-	event_type: String,
+	pub event_type: String,
 	#[serde(rename = "E")]
-	event_time: u64,
+	pub event_time: u64,
 	#[serde(rename = "s")]
-	symbol: String,
+	pub symbol: String,
 	#[serde(rename = "p")]
-	price_change: String,
+	pub price_change: String,
 	#[serde(rename = "P")]
-	price_change_percent: String,
+	pub price_change_percent: String,
 	#[serde(rename = "w")]
-	weighted_average_price: String,
+	pub weighted_average_price: String,
 	#[serde(rename = "c")]
-	last_price: String,
+	pub last_price: String,
 	#[serde(rename = "Q")]
-	last_quantity: String,
+	pub last_quantity: String,
 	#[serde(rename = "o")]
-	open_price: String,
+	pub open_price: String,
 	#[serde(rename = "h")]
-	high_price: String,
+	pub high_price: String,
 	#[serde(rename = "l")]
-	low_price: String,
+	pub low_price: String,
 	#[serde(rename = "v")]
-	total_traded_base_asset_volume: String,
+	pub total_traded_base_asset_volume: String,
 	#[serde(rename = "q")]
-	total_traded_quote_asset_volume: String,
+	pub total_traded_quote_asset_volume: String,
 	#[serde(rename = "O")]
-	statistics_open_time: u64,
+	pub statistics_open_time: u64,
 	#[serde(rename = "C")]
-	statistics_close_time: u64,
+	pub statistics_close_time: u64,
 	#[serde(rename = "F")]
-	first_trade_id: u64,
+	pub first_trade_id: u64,
 	#[serde(rename = "L")]
-	last_trade_id: u64,
+	pub last_trade_id: u64,
 	#[serde(rename = "n")]
-	total_number_of_trades: u64,
+	pub total_number_of_trades: u64,
+}
+
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/websocket-market-streams/Mark-Price-Stream-for-All-market
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct MarkPriceStream {
+	#[serde(rename = "E")]
+	pub event_time: u64,
+	#[serde(rename = "s")]
+	pub symbol: String,
+	#[serde(rename = "p")]
+	pub mark_price: String,
+	#[serde(rename = "i")]
+	pub index_price: String,
+	#[serde(rename = "P")]
+	pub estimated_settle_price: String,
+	/// Empty for delivery contracts
+	#[serde(rename = "r")]
+	pub funding_rate: String,
+	#[serde(rename = "T")]
+	pub next_funding_time: u64,
 }
 
 // https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Get-Funding-Rate-History
@@ -120,43 +196,72 @@ pub struct FundingRateHistoryRequestParams {
 #[serde(rename_all = "camelCase")]
 pub struct FundingRateHistoryResponse {
 	pub funding_rate: String,
+	pub funding_time: u64,
+}
+
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Mark-Price
+#[derive(Serialize)]
+pub struct PremiumIndexRequestParams {
+	pub symbol: String,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PremiumIndexResponse {
+	/// Rate of the current period
+	pub last_funding_rate: String,
+	pub next_funding_time: u64,
+}
+
+/// USDⓈ-M statistics are requested by symbol, COIN-M ones by pair and contract type
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StatisticsTarget {
+	pub symbol: Option<String>,
+	pub pair: Option<String>,
+	/// `PERPETUAL`, `CURRENT_QUARTER`, `NEXT_QUARTER` or `ALL`
+	pub contract_type: Option<&'static str>,
 }
 
 // https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Open-Interest-Statistics
+// https://developers.binance.com/docs/derivatives/coin-margined-futures/market-data/Open-Interest-Statistics
 #[derive(Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenInterestStatisticsRequestParams {
-	pub symbol: String,
+	#[serde(flatten)]
+	pub target: StatisticsTarget,
 	/// 5m, 15m, 30m, 1h, 2h, 4h, 6h, 12h, 1d
 	pub period: String,
 	/// default 30, max 500, array latest item is current
-	pub limit: Option<i64>,
-	pub start_time: Option<i64>,
-	pub end_time: Option<i64>,
+	pub limit: Option<u32>,
+	pub start_time: Option<u64>,
+	pub end_time: Option<u64>,
 }
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub struct OpenInterestStatisticsResponse {
+	/// `pair` on COIN-M
+	#[serde(alias = "pair")]
 	symbol: String,
+	/// Base asset on USDⓈ-M, contracts on COIN-M
 	pub sum_open_interest: String,
+	/// USD on USDⓈ-M, base asset on COIN-M
 	pub sum_open_interest_value: String,
 	#[serde(rename = "CMCCirculatingSupply")]
-	pub cmc_circulating_supply: String,
-	pub timestamp: i64,
+	pub cmc_circulating_supply: Option<String>,
+	pub timestamp: u64,
 }
 
 // https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Kline-Candlestick-Data
-#[derive(Serialize, Default)]
+#[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct KlineCandlestickRequestParams {
 	pub symbol: String,
-	/// 1m, 3m, 5m, 15m, 30m, 1h, 2h, 4h, 6h, 8h, 12h, 1d, 3d, 1w, 1M
-	pub interval: String,
+	pub interval: Interval,
 	/// default 500, max 1500
 	pub limit: Option<u32>,
-	pub start_time: Option<i64>,
-	pub end_time: Option<i64>,
+	pub start_time: Option<u64>,
+	pub end_time: Option<u64>,
 }
 pub type KlineCandlestickResponse = (
 	u64,    // Open time
@@ -174,25 +279,123 @@ pub type KlineCandlestickResponse = (
 );
 
 // https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Long-Short-Ratio
-#[derive(Serialize)]
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Top-Long-Short-Account-Ratio
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Top-Trader-Long-Short-Ratio
+#[derive(Serialize, Default)]
 #[serde(rename_all = "camelCase")]
-#[allow(dead_code)]
 pub struct LongShortRatioRequestParams {
-	pub symbol: String,
+	#[serde(flatten)]
+	pub target: StatisticsTarget,
 	/// 5m, 15m, 30m, 1h, 2h, 4h, 6h, 12h, 1d
 	pub period: String,
 	/// default 30, max 500
-	pub limit: Option<i64>,
-	pub start_time: Option<i64>,
-	pub end_time: Option<i64>,
+	pub limit: Option<u32>,
+	pub start_time: Option<u64>,
+	pub end_time: Option<u64>,
 }
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub struct LongShortRatioResponse {
+	#[serde(alias = "pair")]
 	pub symbol: String,
 	pub long_short_ratio: String,
+	/// Share of long accounts, or long positions for the top trader position ratio
+	#[serde(alias = "longPosition")]
 	pub long_account: String,
+	#[serde(alias = "shortPosition")]
 	pub short_account: String,
-	pub timestamp: String,
+	pub timestamp: u64,
+}
+
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Taker-BuySell-Volume
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TakerVolumeRequestParams {
+	#[serde(flatten)]
+	pub target: StatisticsTarget,
+	/// 5m, 15m, 30m, 1h, 2h, 4h, 6h, 12h, 1d
+	pub period: String,
+	/// default 30, max 500
+	pub limit: Option<u32>,
+	pub start_time: Option<u64>,
+	pub end_time: Option<u64>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TakerVolumeResponse {
+	/// Base asset bought by takers
+	pub buy_vol: String,
+	pub sell_vol: String,
+	pub timestamp: u64,
+}
+
+// https://developers.binance.com/docs/derivatives/coin-margined-futures/market-data/Taker-Buy-Sell-Volume
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinTakerVolumeResponse {
+	/// Base asset bought by takers, `taker_buy_vol` is in contracts
+	pub taker_buy_vol_value: String,
+	pub taker_sell_vol_value: String,
+	pub timestamp: u64,
+}
+
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Order-Book
+#[derive(Serialize)]
+pub struct OrderBookRequestParams {
+	pub symbol: String,
+	/// 5, 10, 20, 50, 100, 500, 1000
+	pub limit: u32,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBookResponse {
+	pub last_update_id: u64,
+	#[serde(rename = "E")]
+	pub message_output_time: u64,
+	/// `[price, quantity]`
+	pub bids: Vec<(String, String)>,
+	pub asks: Vec<(String, String)>,
+}
+
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/websocket-market-streams/Diff-Book-Depth-Streams
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct DepthUpdateStream {
+	#[serde(rename = "E")]
+	pub event_time: u64,
+	#[serde(rename = "s")]
+	pub symbol: String,
+	#[serde(rename = "U")]
+	pub first_update_id: u64,
+	#[serde(rename = "u")]
+	pub final_update_id: u64,
+	#[serde(rename = "pu")]
+	pub previous_update_id: u64,
+	/// `[price, quantity]`, zero quantity removes the level
+	#[serde(rename = "b")]
+	pub bids: Vec<(String, String)>,
+	#[serde(rename = "a")]
+	pub asks: Vec<(String, String)>,
+}
+
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/websocket-market-streams/Aggregate-Trade-Streams
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct AggTradeStream {
+	#[serde(rename = "E")]
+	pub event_time: u64,
+	#[serde(rename = "s")]
+	pub symbol: String,
+	#[serde(rename = "a")]
+	pub aggregate_trade_id: u64,
+	#[serde(rename = "p")]
+	pub price: String,
+	#[serde(rename = "q")]
+	pub quantity: String,
+	#[serde(rename = "T")]
+	pub trade_time: u64,
+	/// The buyer is the maker, i.e. the taker sold
+	#[serde(rename = "m")]
+	pub is_buyer_maker: bool,
 }
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use super::rate_limiter::klines_weight;
+use crate::{ExchangeError, SymbolInfo};
+
+/// Market a `BinanceExchange` trades on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BinanceMarket {
+	/// USDⓈ-margined contracts on `fapi`, quantities in base asset
+	#[default]
+	UsdM,
+	/// Coin-margined inverse contracts on `dapi`, quantities in contracts worth `SymbolInfo::contract_size` USD
+	CoinM,
+	/// Spot pairs on `api`, without liquidations, funding or open interest
+	Spot,
+}
+
+impl BinanceMarket {
+	pub(super) const fn rest_base_url(self) -> &'static str {
+		match self {
+			Self::UsdM => "https://fapi.binance.com",
+			Self::CoinM => "https://dapi.binance.com",
+			Self::Spot => "https://api.binance.com",
+		}
+	}
+
+	pub(super) const fn ws_base_url(self) -> &'static str {
+		match self {
+			Self::UsdM => "wss://fstream.binance.com/ws",
+			Self::CoinM => "wss://dstream.binance.com/ws",
+			Self::Spot => "wss://stream.binance.com:9443/ws",
+		}
+	}
+
+	/// Versioned REST path, e.g. `/fapi/v1/klines`
+	pub(super) fn path(self, endpoint: &str) -> String {
+		match self {
+			Self::UsdM => format!("/fapi/v1/{endpoint}"),
+			Self::CoinM => format!("/dapi/v1/{endpoint}"),
+			Self::Spot => format!("/api/v3/{endpoint}"),
+		}
+	}
+
+	/// Max candles per klines request
+	pub(super) const fn kline_limit(self) -> u32 {
+		match self {
+			Self::UsdM | Self::CoinM => 1500,
+			Self::Spot => 1000,
+		}
+	}
+
+	/// Weight of a klines request, spot klines cost the same regardless of `limit`
+	pub(super) const fn klines_weight(self, limit: u32) -> u32 {
+		match self {
+			Self::UsdM | Self::CoinM => klines_weight(limit),
+			Self::Spot => 2,
+		}
+	}
+
+	/// Quote asset of the contracts kept from exchange info
+	pub(super) const fn quote_asset(self) -> &'static str {
+		match self {
+			Self::UsdM | Self::Spot => "USDT",
+			Self::CoinM => "USD",
+		}
+	}
+}
+
+/// How quantities of a market are denominated
+pub enum Contracts {
+	/// USDⓈ-M and spot, quantities are already in base asset
+	Base,
+	/// COIN-M, USD value of one contract per symbol
+	Inverse(HashMap<String, f64>),
+}
+
+impl Contracts {
+	pub fn inverse(symbols: &[SymbolInfo]) -> Self {
+		Self::Inverse(symbols.iter().filter_map(|info| Some((info.symbol.clone(), info.contract_size?))).collect())
+	}
+
+	/// Contract size of `symbol`, `None` when quantities are in base asset
+	pub fn size(&self, symbol: &str) -> anyhow::Result<Option<f64>> {
+		match self {
+			Self::Base => Ok(None),
+			Self::Inverse(sizes) => {
+				let size = sizes.get(symbol).ok_or_else(|| ExchangeError::UnknownSymbol(String::from(symbol)))?;
+				Ok(Some(*size))
+			},
+		}
+	}
+
+	/// Base asset amount of `quantity` at `price`
+	pub fn base_quantity(&self, symbol: &str, quantity: f64, price: f64) -> anyhow::Result<f64> {
+		Ok(self.size(symbol)?.map_or(quantity, |size| quantity * size / price))
+	}
+
+	/// Stream items of other markets sharing the endpoint are dropped, only USDT pairs are kept on USDⓈ-M
+	pub fn lists(&self, symbol: &str) -> bool {
+		match self {
+			Self::Base => symbol.ends_with("USDT"),
+			Self::Inverse(sizes) => sizes.contains_key(symbol),
+		}
+	}
+}
@@ -1,277 +1,785 @@
-use futures_util::{SinkExt, StreamExt};
+use std::sync::Arc;
+
+use futures_util::{
+	StreamExt,
+	stream::{self, BoxStream},
+};
 use rayon::prelude::*;
-use tokio::time::{Duration, Instant};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use tracing::error;
+use reqwest::StatusCode;
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::time::Duration;
+use tracing::warn;
 
 use crate::{
-	CandleInfo, Exchange, MarketLiquidationsInfo,
+	CandleInfo, ContractKind, DepthUpdate, Exchange, ExchangeError, ExchangeKind, FUNDING_HISTORY_LIMIT, FundingRateInfo,
+	Interval, LocalOrderBook, LongShortRatio, LongShortRatioKind, MarkPriceInfo, MarketLiquidationsInfo,
+	OpenInterestPoint, OrderBookLevel, Side, StreamEvent, SymbolInfo, SymbolStatus, TakerVolume, TickerInfo, TradeInfo,
 	binance::api_schemes::{
+		AggTradeStream, ApiErrorResponse, CoinTakerVolumeResponse, ContractType, DailyTickerStream, DepthUpdateStream,
 		ExchangeInfoResponse, ForceOrderStream, FundingRateHistoryRequestParams, KlineCandlestickRequestParams,
-		KlineCandlestickResponse, OpenInterestStatisticsRequestParams, SymbolInfoStatus,
+		KlineCandlestickResponse, LongShortRatioRequestParams, LongShortRatioResponse, MarkPriceStream,
+		OpenInterestStatisticsRequestParams, OrderBookRequestParams, OrderBookResponse, PremiumIndexRequestParams,
+		PremiumIndexResponse, StatisticsTarget, SymbolFilter, SymbolInfoStatus, TakerVolumeRequestParams,
+		TakerVolumeResponse,
 	},
+	capture,
+	order_book::synced_order_book,
+	replay::Replay,
+	stream::{Heartbeat, RECONNECT_DELAY, Subscription, reconnecting_stream},
+	symbol_info::SymbolCache,
+	utils::{fetch_candles_range, funding_rate_info_from_history, open_interest_info_from_series, parse_levels},
 };
-use anyhow::{Context, bail};
+use anyhow::{Context, anyhow};
 use api_schemes::{FundingRateHistoryResponse, OpenInterestStatisticsResponse};
+pub use market::BinanceMarket;
+use market::Contracts;
+pub use rate_limiter::WeightUsage;
+use rate_limiter::{RateLimiter, order_book_weight};
 mod api_schemes;
+mod market;
+mod rate_limiter;
+
+/// Max items per futures statistics request (open interest, long/short ratio)
+const STATISTICS_MAX_LIMIT: u32 = 500;
+/// Depths served by the order book endpoint
+const ORDER_BOOK_LIMITS: [u32; 7] = [5, 10, 20, 50, 100, 500, 1000];
+/// Snapshot depth used to bootstrap `watch_order_book`
+const ORDER_BOOK_SYNC_LIMIT: u32 = 1000;
 
-const BINANCE_FUTURES_API_BASE: &str = "https://fapi.binance.com";
+const PING_EVERY: Duration = Duration::from_mins(1);
+const PONG_TIMEOUT: Duration = Duration::from_mins(10);
 
-const WS_URL: &str = "wss://fstream.binance.com/ws/!forceOrder@arr";
-const HOURS_24: Duration = Duration::from_secs(24 * 60 * 60);
-const PING_EVERY: Duration = Duration::from_secs(60);
-const PONG_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Retries after 429 and 5xx responses
+const MAX_RETRIES: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Used when a 429/418 response has no `Retry-After` header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_mins(1);
+/// Error code for a symbol the exchange doesn't list
+const INVALID_SYMBOL_CODE: i64 = -1121;
 
 #[derive(Clone)]
 pub struct BinanceExchange {
 	client: reqwest::Client,
+	rate_limiter: RateLimiter,
+	symbols: SymbolCache,
+	config: Arc<Config>,
+}
+
+struct Config {
+	market: BinanceMarket,
+	rest_base_url: String,
+	ws_base_url: String,
+	ping_every: Duration,
+	pong_timeout: Duration,
+	reconnect_delay: Duration,
+}
+
+/// Builds a `BinanceExchange`, every setting defaults to the production endpoints
+pub struct BinanceExchangeBuilder {
+	client: Option<reqwest::Client>,
+	config: Config,
+}
+
+impl BinanceExchangeBuilder {
+	/// USDⓈ-M by default. Also switches the endpoints to the market's production URLs unless they were overridden.
+	#[must_use]
+	pub fn market(mut self, market: BinanceMarket) -> Self {
+		if self.config.rest_base_url == self.config.market.rest_base_url() {
+			self.config.rest_base_url = String::from(market.rest_base_url());
+		}
+		if self.config.ws_base_url == self.config.market.ws_base_url() {
+			self.config.ws_base_url = String::from(market.ws_base_url());
+		}
+		self.config.market = market;
+		self
+	}
+
+	/// e.g. `https://fapi.binance.com`
+	#[must_use]
+	pub fn rest_base_url(mut self, url: impl Into<String>) -> Self {
+		self.config.rest_base_url = url.into();
+		self
+	}
+
+	/// Raw stream endpoint, e.g. `wss://fstream.binance.com/ws`
+	#[must_use]
+	pub fn ws_base_url(mut self, url: impl Into<String>) -> Self {
+		self.config.ws_base_url = url.into();
+		self
+	}
+
+	/// How often websocket pings are sent and how long to wait for a pong before reconnecting
+	#[must_use]
+	pub const fn heartbeat(mut self, ping_every: Duration, pong_timeout: Duration) -> Self {
+		self.config.ping_every = ping_every;
+		self.config.pong_timeout = pong_timeout;
+		self
+	}
+
+	/// Delay before reconnecting a failed websocket session
+	#[must_use]
+	pub const fn reconnect_delay(mut self, delay: Duration) -> Self {
+		self.config.reconnect_delay = delay;
+		self
+	}
+
+	#[must_use]
+	pub fn client(mut self, client: reqwest::Client) -> Self {
+		self.client = Some(client);
+		self
+	}
+
+	#[must_use]
+	pub fn build(self) -> BinanceExchange {
+		BinanceExchange {
+			client: self.client.unwrap_or_default(),
+			rate_limiter: RateLimiter::default(),
+			symbols: SymbolCache::default(),
+			config: Arc::new(self.config),
+		}
+	}
 }
 
 impl BinanceExchange {
 	#[must_use]
 	pub fn new() -> Self {
-		Self { client: reqwest::Client::new() }
+		Self::builder().build()
 	}
-}
 
-impl Default for BinanceExchange {
-	fn default() -> Self {
-		Self::new()
+	#[must_use]
+	pub fn builder() -> BinanceExchangeBuilder {
+		BinanceExchangeBuilder {
+			client: None,
+			config: Config {
+				market: BinanceMarket::UsdM,
+				rest_base_url: String::from(BinanceMarket::UsdM.rest_base_url()),
+				ws_base_url: String::from(BinanceMarket::UsdM.ws_base_url()),
+				ping_every: PING_EVERY,
+				pong_timeout: PONG_TIMEOUT,
+				reconnect_delay: RECONNECT_DELAY,
+			},
+		}
 	}
-}
 
-#[async_trait::async_trait]
-impl Exchange for BinanceExchange {
-	async fn get_all_usdt_pairs(&self) -> anyhow::Result<Vec<String>> {
-		let url = format!("{BINANCE_FUTURES_API_BASE}/fapi/v1/exchangeInfo");
-		let response: ExchangeInfoResponse =
-			self.client.get(&url).send().await?.error_for_status()?.json().await.context("Failed to fetch exchange info")?;
+	/// Request weight used within the current minute, shared by clones of this exchange
+	#[must_use]
+	pub fn weight_usage(&self) -> WeightUsage {
+		self.rate_limiter.usage()
+	}
 
-		let pairs = response
-			.symbols
-			.into_par_iter()
-			.filter(|symbol| symbol.quote_asset == "USDT" && symbol.status == SymbolInfoStatus::Trading)
-			.map(|symbol| symbol.symbol)
-			.collect();
+	#[must_use]
+	pub fn market(&self) -> BinanceMarket {
+		self.config.market
+	}
+
+	/// Contract sizes of the COIN-M market, nothing to fetch on USDⓈ-M and spot
+	async fn contracts(&self) -> Result<Contracts, ExchangeError> {
+		match self.config.market {
+			BinanceMarket::UsdM | BinanceMarket::Spot => Ok(Contracts::Base),
+			BinanceMarket::CoinM => Ok(Contracts::inverse(&self.get_symbols().await?)),
+		}
+	}
 
-		Ok(pairs)
+	/// COIN-M statistics cover a pair and contract type, only perpetuals (`BTCUSD_PERP`) map to a single contract
+	fn statistics_target(&self, symbol: &str) -> anyhow::Result<StatisticsTarget> {
+		match self.config.market {
+			BinanceMarket::UsdM => Ok(StatisticsTarget { symbol: Some(String::from(symbol)), ..Default::default() }),
+			BinanceMarket::CoinM => {
+				let Some(pair) = symbol.strip_suffix("_PERP") else {
+					return Err(ExchangeError::Unsupported(format!("Binance COIN-M statistics of {symbol}")).into());
+				};
+				Ok(StatisticsTarget { pair: Some(String::from(pair)), contract_type: Some("PERPETUAL"), ..Default::default() })
+			},
+			BinanceMarket::Spot => Err(ExchangeError::Unsupported(String::from("Binance spot statistics")).into()),
+		}
+	}
+
+	/// Fails with `ExchangeError::Unsupported` for `data` only futures markets have
+	fn futures_only(&self, data: &str) -> Result<(), ExchangeError> {
+		match self.config.market {
+			BinanceMarket::UsdM | BinanceMarket::CoinM => Ok(()),
+			BinanceMarket::Spot => Err(ExchangeError::Unsupported(format!("Binance spot {data}"))),
+		}
 	}
 
-	async fn watch_market_liquidations<F>(&self, mut callback: F) -> anyhow::Result<()>
+	/// GET request that stays within the request weight limit.
+	/// Waits out `Retry-After` on 429/418 and retries 5xx responses with jittered backoff.
+	async fn get<T, Q>(&self, path: &str, query: &Q, weight: u32) -> anyhow::Result<T>
 	where
-		F: FnMut(MarketLiquidationsInfo) + Send,
+		T: DeserializeOwned,
+		Q: Serialize + Sync,
 	{
+		let url = format!("{}{path}", self.config.rest_base_url);
+		if let Some(replay) = Replay::current() {
+			let request = self.client.get(&url).query(query).build()?;
+			return Ok(serde_json::from_str(&replay.response(request.url().as_str())?)?);
+		}
+		let mut attempt = 0;
+
 		loop {
-			if let Err(e) = run_stream(&mut callback).await {
-				error!("Stream error: {}, reconnecting in 5s...", e);
-				tokio::time::sleep(Duration::from_secs(5)).await;
+			self.rate_limiter.acquire(weight).await;
+			let response = self.client.get(&url).query(query).send().await?;
+
+			if let Some(used) = header_value(&response, "X-MBX-USED-WEIGHT-1M") {
+				self.rate_limiter.observe(used);
 			}
+
+			let status = response.status();
+			if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::IM_A_TEAPOT {
+				let retry_after = header_value(&response, "Retry-After").map_or(DEFAULT_RETRY_AFTER, Duration::from_secs);
+				self.rate_limiter.ban_for(retry_after);
+				warn!("Binance rate limit hit ({status}), holding requests for {}s", retry_after.as_secs());
+
+				// 418 means the IP is already banned for repeated 429s, retrying only extends the ban
+				if status == StatusCode::TOO_MANY_REQUESTS && attempt < MAX_RETRIES {
+					attempt += 1;
+					continue;
+				}
+
+				return Err(ExchangeError::RateLimited { retry_after: Some(retry_after) }.into());
+			} else if status.is_server_error() && attempt < MAX_RETRIES {
+				let delay = retry_delay(attempt);
+				warn!("Binance responded {status} for {path}, retrying in {}ms", delay.as_millis());
+				tokio::time::sleep(delay).await;
+				attempt += 1;
+				continue;
+			}
+
+			if status.is_client_error() {
+				return Err(client_error(response).await);
+			}
+
+			let response = response.error_for_status()?;
+			let request_url = response.url().to_string();
+			let body = response.text().await?;
+			capture::record(&request_url, &body);
+
+			return Ok(serde_json::from_str(&body)?);
 		}
 	}
 
-	async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> anyhow::Result<Vec<CandleInfo>> {
-		let url = format!("{BINANCE_FUTURES_API_BASE}/fapi/v1/klines");
+	/// Order book of `symbol` maintained from the `@depth@100ms` diff stream on top of REST snapshots.
+	/// Yields the book after every applied update, i.e. up to 10 times a second. Futures markets only.
+	#[must_use]
+	pub fn watch_order_book(&self, symbol: &str) -> BoxStream<'static, StreamEvent<LocalOrderBook>> {
+		let updates = self.watch_stream(format!("{}@depth@100ms", symbol.to_lowercase()), |text, contracts| {
+			parse_depth_update(text, contracts).map(|update| vec![update])
+		});
+		let exchange = self.clone();
+		let symbol = String::from(symbol);
+
+		synced_order_book(updates, move || {
+			let exchange = exchange.clone();
+			let symbol = symbol.clone();
+			async move { Ok(exchange.get_order_book(&symbol, ORDER_BOOK_SYNC_LIMIT).await?) }
+		})
+	}
+
+	/// Subscribe to a raw stream, e.g. `!forceOrder@arr`.
+	/// Contract sizes are refetched on every reconnect on COIN-M to pick up new listings.
+	fn watch_stream<T: Send + 'static>(
+		&self,
+		stream: impl Into<String>,
+		parse: fn(&str, &Contracts) -> anyhow::Result<Vec<T>>,
+	) -> BoxStream<'static, StreamEvent<T>> {
+		let stream = stream.into();
+		self.subscribe(move |_| (stream.clone(), Vec::new()), parse)
+	}
+
+	/// Connect to the stream endpoint returned by `target` and send its messages, e.g. `SUBSCRIBE` requests
+	fn subscribe<T: Send + 'static>(
+		&self,
+		target: impl Fn(&Contracts) -> (String, Vec<String>) + Send + Sync + 'static,
+		parse: fn(&str, &Contracts) -> anyhow::Result<Vec<T>>,
+	) -> BoxStream<'static, StreamEvent<T>> {
+		let exchange = self.clone();
+		let target = Arc::new(target);
+
+		reconnecting_stream(self.config.reconnect_delay, move || {
+			let exchange = exchange.clone();
+			let target = Arc::clone(&target);
+			async move {
+				let contracts = exchange.contracts().await?;
+				let (stream, messages) = target(&contracts);
+				let config = &exchange.config;
+
+				Ok(Subscription {
+					url: if stream.is_empty() { config.ws_base_url.clone() } else { format!("{}/{stream}", config.ws_base_url) },
+					messages,
+					heartbeat: Heartbeat::PingFrame,
+					ping_every: config.ping_every,
+					pong_timeout: config.pong_timeout,
+					parse: move |text: &str| parse(text, &contracts),
+				})
+			}
+		})
+	}
+
+	async fn fetch_klines(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		limit: u32,
+		start_time: Option<u64>,
+		end_time: Option<u64>,
+	) -> anyhow::Result<Vec<CandleInfo>> {
+		let contracts = self.contracts().await?;
 		let response: Vec<KlineCandlestickResponse> = self
-			.client
-			.get(&url)
-			.query(&KlineCandlestickRequestParams {
-				symbol: String::from(symbol),
-				limit: Some(limit),
-				interval: String::from(interval),
-				..Default::default()
-			})
-			.send()
-			.await?
-			.error_for_status()?
-			.json()
+			.get(
+				&self.config.market.path("klines"),
+				&KlineCandlestickRequestParams {
+					symbol: String::from(symbol),
+					limit: Some(limit),
+					interval,
+					start_time,
+					end_time,
+				},
+				self.config.market.klines_weight(limit),
+			)
 			.await
 			.context(format!("Failed to fetch klines info for {symbol}"))?;
 
-		let candles: Vec<CandleInfo> = response
-			.iter()
-			.filter_map(|v| {
-				Some(CandleInfo {
-					open: v.1.parse().ok()?,
-					high: v.2.parse().ok()?,
-					low: v.3.parse().ok()?,
-					close: v.4.parse().ok()?,
-					volume: v.5.parse().ok()?,
-				})
-			})
-			.collect();
+		Ok(parse_candles(&response, contracts.size(symbol)?))
+	}
+}
+
+impl Default for BinanceExchange {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[async_trait::async_trait]
+impl Exchange for BinanceExchange {
+	async fn get_all_usdt_pairs(&self) -> Result<Vec<String>, ExchangeError> {
+		let symbols = self.get_symbols().await?;
 
-		Ok(candles)
+		Ok(symbols.into_iter().filter(SymbolInfo::is_trading).map(|info| info.symbol).collect())
 	}
 
-	async fn get_funding_rate_info(&self, symbol: &str) -> anyhow::Result<crate::FundingRateInfo> {
-		let url = format!("{BINANCE_FUTURES_API_BASE}/fapi/v1/fundingRate");
-		let response: Vec<FundingRateHistoryResponse> = self
-			.client
-			.get(&url)
-			.query(&FundingRateHistoryRequestParams { symbol: String::from(symbol), limit: Some(100), ..Default::default() })
-			.send()
-			.await?
-			.error_for_status()?
-			.json()
+	async fn get_symbols(&self) -> Result<Vec<SymbolInfo>, ExchangeError> {
+		self
+			.symbols
+			.get_or_fetch(|| async {
+				let response: ExchangeInfoResponse =
+					self.get(&self.config.market.path("exchangeInfo"), &(), 1).await.context("Failed to fetch exchange info")?;
+
+				Ok(parse_symbols(response, self.config.market.quote_asset()))
+			})
 			.await
-			.context(format!("Failed to fetch funding rate info for {symbol}"))?;
+	}
 
-		let current_funding_rate = response.first().map(|item| item.funding_rate.clone()).unwrap_or_default();
+	/// Ends right away on spot
+	fn watch_market_liquidations(&self) -> BoxStream<'static, StreamEvent<MarketLiquidationsInfo>> {
+		if self.config.market == BinanceMarket::Spot {
+			return stream::empty().boxed();
+		}
 
-		let rates: Vec<f64> = response.iter().filter_map(|item| item.funding_rate.parse::<f64>().ok()).collect();
+		self.watch_stream("!forceOrder@arr", |text, contracts| parse_liquidation(text, contracts).map(|info| vec![info]))
+	}
 
-		let average_funding_rate = if rates.is_empty() {
-			String::from("0.0000")
-		} else {
-			let sum: f64 = rates.iter().sum();
-			let average = sum / rates.len() as f64;
-			average.to_string()
-		};
+	fn watch_tickers(&self) -> BoxStream<'static, StreamEvent<TickerInfo>> {
+		self.watch_stream("!ticker@arr", parse_tickers)
+	}
 
-		Ok(crate::FundingRateInfo { funding_rate: current_funding_rate, average_funding_rate })
-	}
-
-	async fn get_open_interest_info(&self, symbol: &str) -> anyhow::Result<crate::OpenInterestInfo> {
-		let url = format!("{BINANCE_FUTURES_API_BASE}/futures/data/openInterestHist");
-
-		let (response_5m, response_1d) = tokio::join!(
-			async {
-				let limit = Some(48); // to get 5m - 4h distance
-				self
-					.client
-					.get(&url)
-					.query(&OpenInterestStatisticsRequestParams {
-						symbol: String::from(symbol),
-						period: String::from("5m"),
-						limit,
-						..Default::default()
-					})
-					.send()
-					.await?
-					.error_for_status()?
-					.json::<Vec<OpenInterestStatisticsResponse>>()
-					.await
-					.context(format!("Failed to fetch open interest info for {symbol} (5m)"))
+	/// COIN-M has no all-market mark price stream, every pair is subscribed instead. Ends right away on spot.
+	fn watch_mark_prices(&self) -> BoxStream<'static, StreamEvent<MarkPriceInfo>> {
+		match self.config.market {
+			BinanceMarket::Spot => stream::empty().boxed(),
+			BinanceMarket::UsdM => self.watch_stream("!markPrice@arr", parse_mark_prices),
+			BinanceMarket::CoinM => {
+				self.subscribe(|contracts| (String::new(), vec![mark_price_subscription(contracts)]), parse_mark_prices)
 			},
-			async {
-				let limit = Some(30); // 30 days
-				self
-					.client
-					.get(&url)
-					.query(&OpenInterestStatisticsRequestParams {
-						symbol: String::from(symbol),
-						period: String::from("1d"),
-						limit,
-						..Default::default()
-					})
-					.send()
-					.await?
-					.error_for_status()?
-					.json::<Vec<OpenInterestStatisticsResponse>>()
-					.await
-					.context(format!("Failed to fetch open interest info for {symbol} (1d)"))
-			}
-		);
+		}
+	}
 
-		let response_5m = response_5m?;
-		let response_1d = response_1d?;
+	fn watch_trades(&self, symbol: &str) -> BoxStream<'static, StreamEvent<TradeInfo>> {
+		self.watch_stream(format!("{}@aggTrade", symbol.to_lowercase()), |text, contracts| {
+			parse_agg_trade(text, contracts).map(|trade| vec![trade])
+		})
+	}
 
-		// Calculate percent changes for 5m period data
-		// Latest item is at the end of the array (highest index), oldest at index 0
-		let percent_change_5_minutes = calculate_percent_change(&response_5m, 1)?;
-		let percent_change_15_minutes = calculate_percent_change(&response_5m, 3)?;
-		let percent_change_1_hour = calculate_percent_change(&response_5m, 12)?;
-		let percent_change_4_hours = calculate_percent_change(&response_5m, 47)?;
-
-		// Calculate percent changes for 1d period data
-		let percent_change_1_day = calculate_percent_change(&response_1d, 1)?;
-		let percent_change_7_days = calculate_percent_change(&response_1d, 7)?;
-		let percent_change_30_days = calculate_percent_change(&response_1d, 29)?;
-
-		Ok(crate::OpenInterestInfo {
-			percent_change_5_minutes,
-			percent_change_15_minutes,
-			percent_change_1_hour,
-			percent_change_4_hours,
-			percent_change_1_day,
-			percent_change_7_days,
-			percent_change_30_days,
+	async fn get_klines(&self, symbol: &str, interval: Interval, limit: u32) -> Result<Vec<CandleInfo>, ExchangeError> {
+		Ok(self.fetch_klines(symbol, interval, limit, None, None).await?)
+	}
+
+	async fn get_klines_range(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<CandleInfo>, ExchangeError> {
+		let page_limit = self.config.market.kline_limit();
+		fetch_candles_range(interval, from, to, page_limit, |start, end| {
+			self.fetch_klines(symbol, interval, page_limit, Some(start), Some(end))
 		})
+		.await
 	}
-}
 
-/// Calculate percent change between the most recent value (last index) and a value at a given offset back in time
-fn calculate_percent_change(data: &[OpenInterestStatisticsResponse], offset: usize) -> anyhow::Result<f64> {
-	if data.len() <= offset {
-		bail!("Insufficient data: need at least {} items, got {}", offset + 1, data.len());
+	async fn get_funding_rate_info(&self, symbol: &str) -> Result<FundingRateInfo, ExchangeError> {
+		self.futures_only("funding rate")?;
+		let premium_index_params = PremiumIndexRequestParams { symbol: String::from(symbol) };
+		let history_params = FundingRateHistoryRequestParams {
+			symbol: String::from(symbol),
+			limit: Some(FUNDING_HISTORY_LIMIT),
+			..Default::default()
+		};
+
+		let premium_index_path = self.config.market.path("premiumIndex");
+		let premium_index = async {
+			match self.config.market {
+				BinanceMarket::UsdM => self.get::<PremiumIndexResponse, _>(&premium_index_path, &premium_index_params, 1).await,
+				// Listed per contract of the pair even when a symbol is given
+				BinanceMarket::CoinM => self
+					.get::<Vec<PremiumIndexResponse>, _>(&premium_index_path, &premium_index_params, 1)
+					.await?
+					.into_iter()
+					.next()
+					.context("Empty premium index"),
+				BinanceMarket::Spot => Err(ExchangeError::Unsupported(String::from("Binance spot funding rate")).into()),
+			}
+		};
+		let history_path = self.config.market.path("fundingRate");
+		let (premium_index, history) = tokio::try_join!(
+			premium_index,
+			self.get::<Vec<FundingRateHistoryResponse>, _>(&history_path, &history_params, 1),
+		)
+		.context(format!("Failed to fetch funding rate info for {symbol}"))?;
+
+		Ok(parse_funding_rate_info(&premium_index, &history)?)
 	}
 
-	let last_idx = data.len() - 1;
-	let previous_idx = last_idx - offset;
+	async fn get_open_interest_info(&self, symbol: &str) -> Result<crate::OpenInterestInfo, ExchangeError> {
+		// 48 x 5m to get 5m - 4h distance, 30 x 1d for 30 days
+		let (series_5m, series_1d) = tokio::try_join!(
+			self.get_open_interest_history(symbol, Interval::Minute5, 48),
+			self.get_open_interest_history(symbol, Interval::Day1, 30),
+		)?;
 
-	let current = data[last_idx]
-		.sum_open_interest
-		.parse::<f64>()
-		.context(format!("Failed to parse current open interest: {}", data[last_idx].sum_open_interest))?;
+		Ok(open_interest_info_from_series(&series_5m, &series_1d)?)
+	}
 
-	let previous = data[previous_idx]
-		.sum_open_interest
-		.parse::<f64>()
-		.context(format!("Failed to parse previous open interest: {}", data[previous_idx].sum_open_interest))?;
+	async fn get_open_interest_history(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<OpenInterestPoint>, ExchangeError> {
+		let contracts = self.contracts().await?;
+		let response: Vec<OpenInterestStatisticsResponse> = self
+			.get(
+				"/futures/data/openInterestHist",
+				&OpenInterestStatisticsRequestParams {
+					target: self.statistics_target(symbol)?,
+					period: String::from(to_statistics_period(period)?),
+					limit: Some(limit.min(STATISTICS_MAX_LIMIT)),
+					..Default::default()
+				},
+				1,
+			)
+			.await
+			.context(format!("Failed to fetch open interest history for {symbol} ({period})"))?;
 
-	if previous == 0.0 {
-		return Ok(0.0);
+		// Latest item is at the end of the array (highest index), oldest at index 0
+		Ok(parse_open_interest_series(&response, contracts.size(symbol)?)?)
 	}
 
-	let percent_change = ((current - previous) / previous) * 100.0;
-	Ok(percent_change)
-}
+	async fn get_order_book(&self, symbol: &str, limit: u32) -> Result<LocalOrderBook, ExchangeError> {
+		self.futures_only("order book")?;
+		let limit = ORDER_BOOK_LIMITS.into_iter().find(|&depth| depth >= limit).unwrap_or(ORDER_BOOK_SYNC_LIMIT);
+		let contracts = self.contracts().await?;
+		let response: OrderBookResponse = self
+			.get(
+				&self.config.market.path("depth"),
+				&OrderBookRequestParams { symbol: String::from(symbol), limit },
+				order_book_weight(limit),
+			)
+			.await
+			.context(format!("Failed to fetch order book for {symbol}"))?;
 
-async fn run_stream<F>(callback: &mut F) -> anyhow::Result<()>
-where
-	F: FnMut(MarketLiquidationsInfo),
-{
-	let (ws, _) = connect_async(WS_URL).await.context("Failed to connect")?;
-	let (mut tx, mut rx) = ws.split();
-
-	let start = Instant::now();
-	let mut last_ping = Instant::now();
-	let mut last_pong = Instant::now();
-
-	loop {
-		if start.elapsed() >= HOURS_24 {
-			let _ = tx.send(Message::Close(None)).await;
-			return Ok(());
-		}
+		Ok(LocalOrderBook::from_snapshot(
+			symbol,
+			response.last_update_id,
+			parse_book_levels(symbol, &response.bids, &contracts)?,
+			parse_book_levels(symbol, &response.asks, &contracts)?,
+			response.message_output_time,
+		))
+	}
 
-		if last_ping.elapsed() >= PING_EVERY {
-			tx.send(Message::Ping(vec![].into())).await?;
-			last_ping = Instant::now();
-		}
+	async fn get_long_short_ratio(
+		&self,
+		symbol: &str,
+		kind: LongShortRatioKind,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<LongShortRatio>, ExchangeError> {
+		let path = match kind {
+			LongShortRatioKind::GlobalAccount => "globalLongShortAccountRatio",
+			LongShortRatioKind::TopTraderAccount => "topLongShortAccountRatio",
+			LongShortRatioKind::TopTraderPosition => "topLongShortPositionRatio",
+		};
+		let response: Vec<LongShortRatioResponse> = self
+			.get(
+				&format!("/futures/data/{path}"),
+				&LongShortRatioRequestParams {
+					target: self.statistics_target(symbol)?,
+					period: String::from(to_statistics_period(period)?),
+					limit: Some(limit.min(STATISTICS_MAX_LIMIT)),
+					..Default::default()
+				},
+				1,
+			)
+			.await
+			.context(format!("Failed to fetch long/short ratio for {symbol}"))?;
 
-		if last_pong.elapsed() > PONG_TIMEOUT {
-			return Err(anyhow::anyhow!("Server timeout"));
-		}
+		Ok(parse_long_short_ratios(&response)?)
+	}
 
-		match tokio::time::timeout(Duration::from_secs(30), rx.next()).await {
-			Ok(Some(Ok(Message::Text(text)))) => {
-				if let Ok(info) = parse_liquidation(&text) {
-					callback(info);
-				}
+	async fn get_taker_volume(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<TakerVolume>, ExchangeError> {
+		let params = TakerVolumeRequestParams {
+			target: self.statistics_target(symbol)?,
+			period: String::from(to_statistics_period(period)?),
+			limit: Some(limit.min(STATISTICS_MAX_LIMIT)),
+			..Default::default()
+		};
+
+		match self.config.market {
+			BinanceMarket::UsdM => {
+				let response: Vec<TakerVolumeResponse> = self
+					.get("/futures/data/takerlongshortRatio", &params, 1)
+					.await
+					.context(format!("Failed to fetch taker volume for {symbol}"))?;
+
+				Ok(parse_taker_volumes(&response)?)
 			},
-			Ok(Some(Ok(Message::Pong(_)))) => last_pong = Instant::now(),
-			Ok(Some(Ok(Message::Ping(p)))) => {
-				tx.send(Message::Pong(p)).await?;
-				last_pong = Instant::now();
+			BinanceMarket::CoinM => {
+				let response: Vec<CoinTakerVolumeResponse> = self
+					.get("/futures/data/takerBuySellVol", &params, 1)
+					.await
+					.context(format!("Failed to fetch taker volume for {symbol}"))?;
+
+				Ok(parse_coin_taker_volumes(&response)?)
 			},
-			Ok(Some(Ok(Message::Close(_))) | None) => return Ok(()),
-			Ok(Some(Err(e))) => return Err(e.into()),
-			_ => {},
+			BinanceMarket::Spot => Err(ExchangeError::Unsupported(String::from("Binance spot taker volume"))),
 		}
 	}
 }
 
-fn parse_liquidation(text: &str) -> anyhow::Result<MarketLiquidationsInfo> {
+fn header_value<T: std::str::FromStr>(response: &reqwest::Response, name: &str) -> Option<T> {
+	response.headers().get(name)?.to_str().ok()?.parse().ok()
+}
+
+/// Exponential backoff with jitter: `base * 2^attempt`, scaled by a random factor in `0.5..1.0`
+fn retry_delay(attempt: u32) -> Duration {
+	RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt)).mul_f64(fastrand::f64().mul_add(0.5, 0.5))
+}
+
+/// Periods supported by the futures data endpoints
+fn to_statistics_period(interval: Interval) -> anyhow::Result<&'static str> {
+	match interval {
+		Interval::Minute5
+		| Interval::Minute15
+		| Interval::Minute30
+		| Interval::Hour1
+		| Interval::Hour2
+		| Interval::Hour4
+		| Interval::Hour6
+		| Interval::Hour12
+		| Interval::Day1 => Ok(interval.as_str()),
+		_ => Err(ExchangeError::Unsupported(format!("Binance statistics period {interval}")).into()),
+	}
+}
+
+/// Classify a 4xx response by the `{code, msg}` body Binance sends with it
+async fn client_error(response: reqwest::Response) -> anyhow::Error {
+	let status = response.status();
+	let symbol = response.url().query_pairs().find(|(key, _)| key == "symbol").map(|(_, value)| value.into_owned());
+
+	match response.json::<ApiErrorResponse>().await {
+		Ok(error) if error.code == INVALID_SYMBOL_CODE => ExchangeError::UnknownSymbol(symbol.unwrap_or_default()).into(),
+		Ok(error) => anyhow!("Binance API error {}: {} ({status})", error.code, error.msg),
+		Err(_) => anyhow!("Binance responded {status}"),
+	}
+}
+
+/// Only pairs quoted in `quote_asset` are kept, symbols with malformed filters are skipped
+fn parse_symbols(response: ExchangeInfoResponse, quote_asset: &str) -> Vec<SymbolInfo> {
+	response
+		.symbols
+		.into_par_iter()
+		.filter(|symbol| symbol.quote_asset == quote_asset)
+		.filter_map(|symbol| {
+			let mut tick_size = None;
+			let mut step_size = None;
+			let mut min_notional = None;
+
+			for filter in &symbol.filters {
+				match filter {
+					SymbolFilter::PriceFilter { tick_size: value } => tick_size = value.parse().ok(),
+					SymbolFilter::LotSize { step_size: value } => step_size = value.parse().ok(),
+					SymbolFilter::MinNotional { notional } => min_notional = notional.parse().ok(),
+					SymbolFilter::Other => {},
+				}
+			}
+
+			Some(SymbolInfo {
+				contract_kind: match symbol.contract_type {
+					Some(ContractType::Perpetual) => ContractKind::Perpetual,
+					Some(ContractType::CurrentQuarter | ContractType::NextQuarter) => ContractKind::Delivery,
+					Some(ContractType::Unknown) => ContractKind::Unknown,
+					None => ContractKind::Spot,
+				},
+				status: match symbol.status {
+					SymbolInfoStatus::PendingTrading => SymbolStatus::PreTrading,
+					SymbolInfoStatus::Trading => SymbolStatus::Trading,
+					SymbolInfoStatus::Halt => SymbolStatus::Suspended,
+					SymbolInfoStatus::PreDelivering
+					| SymbolInfoStatus::Delivering
+					| SymbolInfoStatus::PreSettle
+					| SymbolInfoStatus::Settling => SymbolStatus::Settling,
+					SymbolInfoStatus::Delivered | SymbolInfoStatus::Close | SymbolInfoStatus::Break => SymbolStatus::Closed,
+					SymbolInfoStatus::Unknown => SymbolStatus::Unknown,
+				},
+				symbol: symbol.symbol,
+				base_asset: symbol.base_asset,
+				quote_asset: symbol.quote_asset,
+				onboard_date: symbol.onboard_date,
+				tick_size: tick_size?,
+				step_size: step_size?,
+				min_notional,
+				contract_size: symbol.contract_size,
+			})
+		})
+		.collect()
+}
+
+/// Volumes of inverse candles are in contracts of `contract_size` USD, with the base asset volume in place of the
+/// quote volume
+fn parse_candles(response: &[KlineCandlestickResponse], contract_size: Option<f64>) -> Vec<CandleInfo> {
+	response
+		.iter()
+		.filter_map(|v| {
+			let (volume, quote_volume, taker_buy_volume, taker_buy_quote_volume) = match contract_size {
+				None => (v.5.parse().ok()?, v.7.parse().ok()?, v.9.parse().ok()?, v.10.parse().ok()?),
+				Some(size) => {
+					(v.7.parse().ok()?, v.5.parse::<f64>().ok()? * size, v.10.parse().ok()?, v.9.parse::<f64>().ok()? * size)
+				},
+			};
+
+			Some(CandleInfo {
+				open_time: v.0,
+				close_time: v.6,
+				open: v.1.parse().ok()?,
+				high: v.2.parse().ok()?,
+				low: v.3.parse().ok()?,
+				close: v.4.parse().ok()?,
+				volume,
+				quote_volume,
+				trades: Some(v.8),
+				taker_buy_volume: Some(taker_buy_volume),
+				taker_buy_quote_volume: Some(taker_buy_quote_volume),
+			})
+		})
+		.collect()
+}
+
+/// Binance returns funding history oldest first
+fn parse_funding_rate_info(
+	premium_index: &PremiumIndexResponse,
+	history: &[FundingRateHistoryResponse],
+) -> anyhow::Result<FundingRateInfo> {
+	let funding_rate = premium_index
+		.last_funding_rate
+		.parse()
+		.context(format!("Failed to parse funding rate: {}", premium_index.last_funding_rate))?;
+	let history = history
+		.iter()
+		.map(|item| {
+			let rate = item.funding_rate.parse().context(format!("Failed to parse funding rate: {}", item.funding_rate))?;
+			Ok((item.funding_time, rate))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	Ok(funding_rate_info_from_history(funding_rate, premium_index.next_funding_time, &history))
+}
+
+fn parse_long_short_ratios(data: &[LongShortRatioResponse]) -> anyhow::Result<Vec<LongShortRatio>> {
+	data
+		.iter()
+		.map(|item| {
+			let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse long/short ratio: {value}"));
+
+			Ok(LongShortRatio {
+				long_short_ratio: parse(&item.long_short_ratio)?,
+				long_share: parse(&item.long_account)?,
+				short_share: parse(&item.short_account)?,
+				timestamp: item.timestamp,
+			})
+		})
+		.collect()
+}
+
+fn parse_taker_volumes(data: &[TakerVolumeResponse]) -> anyhow::Result<Vec<TakerVolume>> {
+	data
+		.iter()
+		.map(|item| {
+			let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse taker volume: {value}"));
+
+			Ok(TakerVolume {
+				buy_volume: parse(&item.buy_vol)?,
+				sell_volume: parse(&item.sell_vol)?,
+				timestamp: item.timestamp,
+			})
+		})
+		.collect()
+}
+
+/// Inverse open interest is reported in contracts of `contract_size` USD, with its base asset value alongside
+fn parse_open_interest_series(
+	data: &[OpenInterestStatisticsResponse],
+	contract_size: Option<f64>,
+) -> anyhow::Result<Vec<OpenInterestPoint>> {
+	data
+		.iter()
+		.map(|item| {
+			let open_interest: f64 =
+				item.sum_open_interest.parse().context(format!("Failed to parse open interest: {}", item.sum_open_interest))?;
+			let value: f64 = item
+				.sum_open_interest_value
+				.parse()
+				.context(format!("Failed to parse open interest value: {}", item.sum_open_interest_value))?;
+
+			let (open_interest, open_interest_usd) =
+				contract_size.map_or((open_interest, value), |size| (value, open_interest * size));
+
+			Ok(OpenInterestPoint { open_interest, open_interest_usd: Some(open_interest_usd), timestamp: item.timestamp })
+		})
+		.collect()
+}
+
+fn parse_coin_taker_volumes(data: &[CoinTakerVolumeResponse]) -> anyhow::Result<Vec<TakerVolume>> {
+	data
+		.iter()
+		.map(|item| {
+			let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse taker volume: {value}"));
+
+			Ok(TakerVolume {
+				buy_volume: parse(&item.taker_buy_vol_value)?,
+				sell_volume: parse(&item.taker_sell_vol_value)?,
+				timestamp: item.timestamp,
+			})
+		})
+		.collect()
+}
+
+/// Inverse liquidations are valued by their contract size, not by price
+fn parse_liquidation(text: &str, contracts: &Contracts) -> anyhow::Result<MarketLiquidationsInfo> {
 	let data: ForceOrderStream = serde_json::from_str(text)?;
 	let symbol_price = data.order.price.parse::<f64>().context(format!("Failed to parse price: {}", data.order.price))?;
 	let quantity = data
@@ -279,14 +787,487 @@ fn parse_liquidation(text: &str) -> anyhow::Result<MarketLiquidationsInfo> {
 		.original_quantity
 		.parse::<f64>()
 		.context(format!("Failed to parse quantity: {}", data.order.original_quantity))?;
-	let usd_price = symbol_price * quantity;
+	let average_price = data
+		.order
+		.average_price
+		.parse::<f64>()
+		.context(format!("Failed to parse average price: {}", data.order.average_price))?;
+	let filled_quantity = data
+		.order
+		.order_filled_accumulated_quantity
+		.parse::<f64>()
+		.context(format!("Failed to parse filled quantity: {}", data.order.order_filled_accumulated_quantity))?;
+	let symbol = data.order.symbol;
+	let usd_price = contracts.size(&symbol)?.map_or(symbol_price * quantity, |size| quantity * size);
+	let fill_price = if average_price > 0.0 { average_price } else { symbol_price };
 
 	Ok(MarketLiquidationsInfo {
-		symbol: data.order.symbol,
+		quantity: contracts.base_quantity(&symbol, quantity, symbol_price)?,
+		filled_quantity: contracts.base_quantity(&symbol, filled_quantity, fill_price)?,
+		symbol,
+		exchange: ExchangeKind::Binance,
 		side: data.order.side,
+		order_type: Some(data.order.order_type),
 		symbol_price,
+		average_price,
 		usd_price,
-		quantity,
 		time: data.event_time,
+		trade_time: data.order.order_trade_time,
 	})
 }
+
+/// Only pairs of the market are kept, inverse tickers report contracts as volume and base asset as quote volume
+fn parse_tickers(text: &str, contracts: &Contracts) -> anyhow::Result<Vec<TickerInfo>> {
+	let tickers: Vec<DailyTickerStream> = serde_json::from_str(text)?;
+
+	tickers
+		.into_iter()
+		.filter(|ticker| contracts.lists(&ticker.symbol))
+		.map(|ticker| {
+			let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse ticker value: {value}"));
+			let traded = parse(&ticker.total_traded_base_asset_volume)?;
+			let traded_quote = parse(&ticker.total_traded_quote_asset_volume)?;
+			let (volume, quote_volume) =
+				contracts.size(&ticker.symbol)?.map_or((traded, traded_quote), |size| (traded_quote, traded * size));
+
+			Ok(TickerInfo {
+				last_price: parse(&ticker.last_price)?,
+				open_price: parse(&ticker.open_price)?,
+				high_price: parse(&ticker.high_price)?,
+				low_price: parse(&ticker.low_price)?,
+				price_change_percent: parse(&ticker.price_change_percent)?,
+				volume,
+				quote_volume,
+				trades: Some(ticker.total_number_of_trades),
+				time: ticker.event_time,
+				symbol: ticker.symbol,
+			})
+		})
+		.collect()
+}
+
+/// Only perpetuals of the market are kept, delivery contracts have no funding rate
+fn parse_mark_prices(text: &str, contracts: &Contracts) -> anyhow::Result<Vec<MarkPriceInfo>> {
+	let mark_prices: Vec<MarkPriceStream> = serde_json::from_str(text)?;
+
+	mark_prices
+		.into_iter()
+		.filter(|item| contracts.lists(&item.symbol) && !item.funding_rate.is_empty())
+		.map(|item| {
+			let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse mark price value: {value}"));
+
+			Ok(MarkPriceInfo {
+				mark_price: parse(&item.mark_price)?,
+				index_price: parse(&item.index_price)?,
+				funding_rate: parse(&item.funding_rate)?,
+				next_funding_time: item.next_funding_time,
+				time: item.event_time,
+				symbol: item.symbol,
+			})
+		})
+		.collect()
+}
+
+fn parse_agg_trade(text: &str, contracts: &Contracts) -> anyhow::Result<TradeInfo> {
+	let data: AggTradeStream = serde_json::from_str(text)?;
+	let price = data.price.parse().context(format!("Failed to parse price: {}", data.price))?;
+	let quantity = data.quantity.parse().context(format!("Failed to parse quantity: {}", data.quantity))?;
+
+	Ok(TradeInfo {
+		price,
+		quantity: contracts.base_quantity(&data.symbol, quantity, price)?,
+		taker_side: if data.is_buyer_maker { Side::Sell } else { Side::Buy },
+		time: data.trade_time,
+		symbol: data.symbol,
+	})
+}
+
+fn parse_depth_update(text: &str, contracts: &Contracts) -> anyhow::Result<DepthUpdate> {
+	let data: DepthUpdateStream = serde_json::from_str(text)?;
+
+	Ok(DepthUpdate {
+		first_update_id: data.first_update_id,
+		final_update_id: data.final_update_id,
+		previous_update_id: data.previous_update_id,
+		bids: parse_book_levels(&data.symbol, &data.bids, contracts)?,
+		asks: parse_book_levels(&data.symbol, &data.asks, contracts)?,
+		time: data.event_time,
+	})
+}
+
+/// Inverse levels are converted from contracts into base asset
+fn parse_book_levels(
+	symbol: &str,
+	levels: &[(String, String)],
+	contracts: &Contracts,
+) -> anyhow::Result<Vec<OrderBookLevel>> {
+	parse_levels(levels)?
+		.into_iter()
+		.map(|level| {
+			Ok(OrderBookLevel { quantity: contracts.base_quantity(symbol, level.quantity, level.price)?, ..level })
+		})
+		.collect()
+}
+
+/// `SUBSCRIBE` request for the mark prices of every pair listed on the market
+fn mark_price_subscription(contracts: &Contracts) -> String {
+	let mut streams: Vec<String> = match contracts {
+		Contracts::Base => Vec::new(),
+		Contracts::Inverse(sizes) => sizes
+			.keys()
+			.map(|symbol| format!("{}@markPrice", symbol.split('_').next().unwrap_or(symbol).to_lowercase()))
+			.collect(),
+	};
+	streams.sort();
+	streams.dedup();
+
+	serde_json::json!({ "method": "SUBSCRIBE", "params": streams, "id": 1 }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use futures_util::StreamExt;
+
+	use super::*;
+	use crate::{
+		LiquidatedPosition,
+		test_support::{MockResponse, MockServer, WsAction},
+	};
+
+	const KLINES: &str = include_str!("../../fixtures/binance/klines.json");
+	const FORCE_ORDER: &str = include_str!("../../fixtures/binance/force_order.json");
+	const DEPTH_UPDATE: &str = include_str!("../../fixtures/binance/depth_update.json");
+
+	fn mock_exchange(server: &MockServer) -> BinanceExchange {
+		BinanceExchange::builder()
+			.rest_base_url(server.rest_url())
+			.ws_base_url(server.ws_url())
+			.heartbeat(Duration::from_millis(100), Duration::from_millis(300))
+			.reconnect_delay(Duration::from_millis(50))
+			.build()
+	}
+
+	#[tokio::test]
+	async fn fetches_klines_from_mock_server() {
+		let server = MockServer::start().await.unwrap();
+		server.route("/fapi/v1/klines", MockResponse::json(KLINES).header("X-MBX-USED-WEIGHT-1M", "42"));
+
+		let candles = mock_exchange(&server).get_klines("BTCUSDT", Interval::Hour1, 2).await.unwrap();
+
+		assert_eq!(candles.len(), 2);
+		assert_eq!(candles[1].close_time, 1_760_007_199_999);
+		assert!((candles[1].close - 97_500.5).abs() < f64::EPSILON);
+		assert_eq!(candles[0].trades, Some(250_000));
+		assert_eq!(server.requests(), vec!["/fapi/v1/klines?symbol=BTCUSDT&interval=1h&limit=2"]);
+	}
+
+	#[tokio::test]
+	async fn retries_rate_limited_and_failed_requests() {
+		let server = MockServer::start().await.unwrap();
+		server.route("/fapi/v1/klines", MockResponse::status(429).header("Retry-After", "0"));
+		server.route("/fapi/v1/klines", MockResponse::status(503));
+		server.route("/fapi/v1/klines", MockResponse::json(KLINES));
+
+		let candles = mock_exchange(&server).get_klines("BTCUSDT", Interval::Hour1, 2).await.unwrap();
+
+		assert_eq!(candles.len(), 2);
+		assert_eq!(server.requests().len(), 3);
+	}
+
+	#[tokio::test]
+	async fn caches_symbol_metadata() {
+		let server = MockServer::start().await.unwrap();
+		server
+			.route("/fapi/v1/exchangeInfo", MockResponse::json(include_str!("../../fixtures/binance/exchange_info.json")));
+		let exchange = mock_exchange(&server);
+
+		let pairs = exchange.get_all_usdt_pairs().await.unwrap();
+		let info = exchange.get_symbol_info("OLDUSDT").await.unwrap();
+		let symbols = exchange.clone().get_symbols().await.unwrap();
+
+		assert_eq!(pairs, vec!["BTCUSDT", "BTCUSDT_251226"]);
+		assert_eq!(symbols.len(), 3);
+		assert_eq!(symbols[1].contract_kind, ContractKind::Delivery);
+		assert_eq!(info.status, SymbolStatus::Settling);
+		assert_eq!(info.onboard_date, 1_650_000_000_000);
+		assert_eq!(info.format_price(0.123_456), "0.12346");
+		assert_eq!(info.min_notional, Some(5.0));
+		assert!(exchange.get_symbol_info("BTCUSDC").await.is_err());
+		assert_eq!(server.requests().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn converts_coin_margined_contracts() {
+		let server = MockServer::start().await.unwrap();
+		server.route(
+			"/dapi/v1/exchangeInfo",
+			MockResponse::json(include_str!("../../fixtures/binance/coin_exchange_info.json")),
+		);
+		server.route(
+			"/futures/data/openInterestHist",
+			MockResponse::json(include_str!("../../fixtures/binance/coin_open_interest_hist.json")),
+		);
+		let exchange = BinanceExchange::builder().market(BinanceMarket::CoinM).rest_base_url(server.rest_url()).build();
+
+		let pairs = exchange.get_all_usdt_pairs().await.unwrap();
+		let series = exchange.get_open_interest_history("BTCUSD_PERP", Interval::Minute5, 1).await.unwrap();
+		let delivery = exchange.get_open_interest_history("BTCUSD_251226", Interval::Minute5, 1).await.unwrap_err();
+
+		assert_eq!(pairs, vec!["BTCUSD_PERP", "ETHUSD_PERP"]);
+		assert!((series[0].open_interest - 835.051_546_39).abs() < 1e-9);
+		assert_eq!(series[0].open_interest_usd, Some(81_000_000.0));
+		assert!(matches!(delivery, ExchangeError::Unsupported(_)));
+		assert!(
+			server.requests()[1].starts_with("/futures/data/openInterestHist?pair=BTCUSD&contractType=PERPETUAL&period=5m")
+		);
+	}
+
+	#[tokio::test]
+	async fn serves_spot_market() {
+		let server = MockServer::start().await.unwrap();
+		server.route(
+			"/api/v3/exchangeInfo",
+			MockResponse::json(include_str!("../../fixtures/binance/spot_exchange_info.json")),
+		);
+		server.route("/api/v3/klines", MockResponse::json(KLINES));
+		let exchange = BinanceExchange::builder().market(BinanceMarket::Spot).rest_base_url(server.rest_url()).build();
+
+		let symbols = exchange.get_symbols().await.unwrap();
+		let candles = exchange.get_klines("PEPEUSDT", Interval::Hour1, 2).await.unwrap();
+		exchange.get_klines_range("PEPEUSDT", Interval::Hour1, 0, 3_600_000).await.unwrap();
+		let open_interest = exchange.get_open_interest_history("PEPEUSDT", Interval::Minute5, 1).await.unwrap_err();
+
+		assert_eq!(symbols.len(), 2);
+		assert_eq!(symbols[0].contract_kind, ContractKind::Spot);
+		assert_eq!(symbols[0].min_notional, Some(1.0));
+		assert_eq!(symbols[1].status, SymbolStatus::Closed);
+		assert_eq!(candles.len(), 2);
+		assert!(server.requests().iter().any(|request| request.contains("limit=1000")));
+		assert!(matches!(open_interest, ExchangeError::Unsupported(_)));
+	}
+
+	#[test]
+	fn parses_coin_margined_force_order() {
+		let contracts = Contracts::Inverse(HashMap::from([(String::from("BTCUSDT"), 100.0)]));
+		let liquidation = parse_liquidation(FORCE_ORDER, &contracts).unwrap();
+
+		assert!((liquidation.usd_price - 100.0).abs() < f64::EPSILON);
+		assert!((liquidation.quantity - 100.0 / 9_910.0).abs() < 1e-12);
+		assert!(parse_liquidation(FORCE_ORDER, &Contracts::Inverse(HashMap::new())).is_err());
+	}
+
+	#[tokio::test]
+	async fn does_not_retry_ip_ban() {
+		let server = MockServer::start().await.unwrap();
+		server.route("/fapi/v1/klines", MockResponse::status(418).header("Retry-After", "0"));
+
+		let error = mock_exchange(&server).get_klines("BTCUSDT", Interval::Hour1, 2).await.unwrap_err();
+
+		assert!(matches!(error, ExchangeError::RateLimited { retry_after: Some(_) }));
+		assert_eq!(server.requests().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn reports_unknown_symbol() {
+		let server = MockServer::start().await.unwrap();
+		let invalid_symbol = MockResponse::json(r#"{"code":-1121,"msg":"Invalid symbol."}"#);
+		server.route("/fapi/v1/klines", MockResponse { status: 400, ..invalid_symbol });
+
+		let error = mock_exchange(&server).get_klines("FOOUSDT", Interval::Hour1, 2).await.unwrap_err();
+
+		assert!(matches!(error, ExchangeError::UnknownSymbol(symbol) if symbol == "FOOUSDT"));
+		assert_eq!(server.requests().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn reconnects_after_pong_timeout() {
+		let server = MockServer::start().await.unwrap();
+		server.session(vec![WsAction::Text(String::from(FORCE_ORDER)), WsAction::Stall]);
+		server.session(vec![WsAction::Text(String::from(FORCE_ORDER))]);
+
+		let liquidations = mock_exchange(&server).watch_market_liquidations().take(3).collect::<Vec<_>>();
+		let events = tokio::time::timeout(Duration::from_secs(5), liquidations).await.unwrap();
+
+		assert!(matches!(&events[0], StreamEvent::Data(liquidation) if liquidation.symbol == "BTCUSDT"));
+		assert!(matches!(events[1], StreamEvent::Reconnected { .. }));
+		assert!(matches!(events[2], StreamEvent::Data(_)));
+	}
+
+	#[tokio::test]
+	async fn syncs_order_book_from_snapshot_and_diffs() {
+		let server = MockServer::start().await.unwrap();
+		server.route("/fapi/v1/depth", MockResponse::json(include_str!("../../fixtures/binance/depth.json")));
+		server.session(vec![
+			WsAction::Text(String::from(DEPTH_UPDATE)),
+			WsAction::Text(String::from(
+				r#"{"e":"depthUpdate","E":1760000000200,"T":0,"s":"BTCUSDT","U":1006,"u":1010,"pu":1005,"b":[],"a":[["97010.00","0"]]}"#,
+			)),
+		]);
+
+		let books = mock_exchange(&server).watch_order_book("BTCUSDT").take(2).collect::<Vec<_>>();
+		let events = tokio::time::timeout(Duration::from_secs(5), books).await.unwrap();
+
+		assert!(matches!(&events[0], StreamEvent::Data(book) if book.last_update_id() == 1005));
+		let StreamEvent::Data(book) = &events[1] else { unreachable!("Reconnected without a dropped connection") };
+		assert_eq!(book.last_update_id(), 1010);
+		assert_eq!(book.best_bid(), Some(OrderBookLevel { price: 97_005.0, quantity: 0.4 }));
+		assert_eq!(book.best_ask(), Some(OrderBookLevel { price: 97_020.0, quantity: 2.5 }));
+		assert_eq!(server.requests(), vec!["/fapi/v1/depth?symbol=BTCUSDT&limit=1000"]);
+	}
+
+	#[test]
+	fn parses_funding_rate_info() {
+		let premium_index: PremiumIndexResponse =
+			serde_json::from_str(include_str!("../../fixtures/binance/premium_index.json")).unwrap();
+		let history: Vec<FundingRateHistoryResponse> =
+			serde_json::from_str(include_str!("../../fixtures/binance/funding_rate.json")).unwrap();
+
+		let info = parse_funding_rate_info(&premium_index, &history).unwrap();
+
+		assert!((info.funding_rate - 0.0003).abs() < f64::EPSILON);
+		assert_eq!(info.funding_interval_hours, 4);
+		assert_eq!(info.next_funding_time, 1_760_025_600_000);
+		assert_eq!(info.history_len, 3);
+		assert!((info.percentile - 1.0).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn parses_open_interest_history() {
+		let response: Vec<OpenInterestStatisticsResponse> =
+			serde_json::from_str(include_str!("../../fixtures/binance/open_interest_hist.json")).unwrap();
+		let series = parse_open_interest_series(&response, None).unwrap();
+
+		assert_eq!(series.len(), 2);
+		assert!((series[1].open_interest - 81_250.5).abs() < f64::EPSILON);
+		assert_eq!(series[1].open_interest_usd, Some(7_881_298_500.0));
+		assert_eq!(series[1].timestamp, 1_760_000_300_000);
+	}
+
+	#[test]
+	fn parses_agg_trade_stream() {
+		let trade = parse_agg_trade(include_str!("../../fixtures/binance/agg_trade.json"), &Contracts::Base).unwrap();
+
+		assert_eq!(trade.symbol, "BTCUSDT");
+		assert_eq!(trade.taker_side, Side::Sell);
+		assert!((trade.quantity - 0.25).abs() < f64::EPSILON);
+		assert_eq!(trade.time, 1_760_000_000_050);
+	}
+
+	#[test]
+	fn parses_spot_agg_trade_stream() {
+		let trade = parse_agg_trade(include_str!("../../fixtures/binance/spot_agg_trade.json"), &Contracts::Base).unwrap();
+
+		assert_eq!(trade.symbol, "PEPEUSDT");
+		assert_eq!(trade.taker_side, Side::Buy);
+		assert!((trade.quantity - 25_000_000.0).abs() < f64::EPSILON);
+		assert_eq!(trade.time, 1_760_000_000_075);
+	}
+
+	#[test]
+	fn parses_depth_update_stream() {
+		let update = parse_depth_update(DEPTH_UPDATE, &Contracts::Base).unwrap();
+
+		assert_eq!((update.first_update_id, update.final_update_id, update.previous_update_id), (995, 1005, 994));
+		assert_eq!(update.bids[0], OrderBookLevel { price: 97_000.0, quantity: 0.0 });
+		assert_eq!(update.asks.len(), 1);
+	}
+
+	#[test]
+	fn parses_force_order_stream() {
+		let liquidation = parse_liquidation(FORCE_ORDER, &Contracts::Base).unwrap();
+
+		assert_eq!(liquidation.symbol, "BTCUSDT");
+		assert_eq!(liquidation.side, Side::Sell);
+		assert_eq!(liquidation.liquidated_position(), LiquidatedPosition::Long);
+		assert_eq!(liquidation.order_type.as_deref(), Some("LIMIT"));
+		assert!((liquidation.usd_price - 9_910.0).abs() < 1e-9);
+		assert!((liquidation.average_price - 9_910.0).abs() < f64::EPSILON);
+		assert!((liquidation.filled_quantity - 1.0).abs() < f64::EPSILON);
+		assert_eq!(liquidation.time, 1_568_014_460_893);
+		assert_eq!(liquidation.trade_time, 1_568_014_460_893);
+	}
+
+	#[test]
+	fn parses_long_short_ratio() {
+		let response: Vec<LongShortRatioResponse> =
+			serde_json::from_str(include_str!("../../fixtures/binance/long_short_ratio.json")).unwrap();
+		let ratios = parse_long_short_ratios(&response).unwrap();
+
+		assert_eq!(ratios.len(), 2);
+		assert!((ratios[1].long_short_ratio - 1.8105).abs() < f64::EPSILON);
+		assert!((ratios[1].long_share - 0.6442).abs() < f64::EPSILON);
+		assert!((ratios[1].short_share - 0.3558).abs() < f64::EPSILON);
+		assert_eq!(ratios[1].timestamp, 1_760_000_300_000);
+	}
+
+	#[test]
+	fn parses_taker_volume() {
+		let response: Vec<TakerVolumeResponse> =
+			serde_json::from_str(include_str!("../../fixtures/binance/taker_volume.json")).unwrap();
+		let volumes = parse_taker_volumes(&response).unwrap();
+
+		assert_eq!(volumes.len(), 2);
+		assert!((volumes[0].buy_volume - 387.33).abs() < f64::EPSILON);
+		assert!((volumes[1].buy_sell_ratio().unwrap() - 0.75).abs() < f64::EPSILON);
+		assert_eq!(volumes[1].timestamp, 1_760_000_300_000);
+
+		let total = TakerVolume::combined(&volumes).unwrap();
+		assert!((total.sell_volume - 448.503).abs() < 1e-9);
+		assert_eq!(total.timestamp, 1_760_000_000_000);
+		assert!(TakerVolume::combined(&[]).is_none());
+	}
+
+	#[test]
+	fn retry_delay_grows_with_jitter() {
+		for attempt in 0..3 {
+			let delay = retry_delay(attempt);
+			let max = RETRY_BASE_DELAY * 2u32.pow(attempt);
+			assert!(delay >= max / 2 && delay <= max);
+		}
+	}
+
+	#[test]
+	fn maps_statistics_periods() {
+		assert_eq!(to_statistics_period(Interval::Hour4).unwrap(), "4h");
+		assert!(to_statistics_period(Interval::Minute1).is_err());
+	}
+
+	#[test]
+	fn parses_mark_price_stream() {
+		let mark_prices =
+			parse_mark_prices(include_str!("../../fixtures/binance/mark_price_arr.json"), &Contracts::Base).unwrap();
+
+		assert_eq!(mark_prices.len(), 1);
+		assert_eq!(mark_prices[0].symbol, "BTCUSDT");
+		assert!((mark_prices[0].mark_price - 97_251.3).abs() < f64::EPSILON);
+		assert!((mark_prices[0].index_price - 97_260.12).abs() < f64::EPSILON);
+		assert!((mark_prices[0].funding_rate - 0.000_1).abs() < f64::EPSILON);
+		assert_eq!(mark_prices[0].next_funding_time, 1_760_025_600_000);
+	}
+
+	#[test]
+	fn parses_ticker_stream() {
+		let tickers = parse_tickers(include_str!("../../fixtures/binance/ticker_arr.json"), &Contracts::Base).unwrap();
+
+		assert_eq!(tickers.len(), 1);
+		assert_eq!(tickers[0].symbol, "BTCUSDT");
+		assert!((tickers[0].last_price - 97_250.5).abs() < f64::EPSILON);
+		assert!((tickers[0].price_change_percent - 1.305).abs() < f64::EPSILON);
+		assert!((tickers[0].quote_volume - 12_345_678_901.25).abs() < f64::EPSILON);
+		assert_eq!(tickers[0].trades, Some(4_567_890));
+		assert_eq!(tickers[0].time, 1_760_000_000_123);
+	}
+
+	#[test]
+	fn parses_spot_ticker_stream() {
+		let tickers = parse_tickers(include_str!("../../fixtures/binance/spot_ticker_arr.json"), &Contracts::Base).unwrap();
+
+		assert_eq!(tickers.len(), 1);
+		assert_eq!(tickers[0].symbol, "PEPEUSDT");
+		assert!((tickers[0].last_price - 0.000_010_01).abs() < f64::EPSILON);
+		assert!((tickers[0].volume - 9_876_543_210_000.0).abs() < f64::EPSILON);
+		assert_eq!(tickers[0].trades, Some(345_679));
+	}
+}
@@ -0,0 +1,176 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tokio::time::{Duration, Instant};
+
+use crate::utils::now_millis;
+
+// https://developers.binance.com/docs/derivatives/usds-margined-futures/general-info#limits
+pub const WEIGHT_LIMIT_1M: u32 = 2400;
+/// Requests are held back once this much weight is used within the current minute
+const THROTTLE_AT: u32 = WEIGHT_LIMIT_1M * 9 / 10;
+const MINUTE_MILLIS: u64 = 60_000;
+
+/// Request weight used within the current minute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeightUsage {
+	pub used: u32,
+	pub limit: u32,
+}
+
+/// Shared between clones of `BinanceExchange`, so every request from a process counts towards one budget
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+	state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+	/// Minutes since epoch the weight below belongs to, Binance resets the weight every calendar minute
+	minute: u64,
+	used: u32,
+	banned_until: Option<Instant>,
+}
+
+impl RateLimiter {
+	pub fn usage(&self) -> WeightUsage {
+		let mut state = self.lock();
+		state.roll_over(now_millis());
+		WeightUsage { used: state.used, limit: WEIGHT_LIMIT_1M }
+	}
+
+	/// Wait until `weight` fits into the current minute and no ban is active, then reserve it
+	pub async fn acquire(&self, weight: u32) {
+		loop {
+			let wait = self.lock().try_reserve(weight, now_millis(), Instant::now());
+			match wait {
+				Some(wait) => tokio::time::sleep(wait).await,
+				None => return,
+			}
+		}
+	}
+
+	/// Sync with the weight reported by `X-MBX-USED-WEIGHT-1M`
+	pub fn observe(&self, used: u32) {
+		self.lock().observe(used, now_millis());
+	}
+
+	/// Hold back every request for `delay`, as asked by `Retry-After`
+	pub fn ban_for(&self, delay: Duration) {
+		let until = Instant::now() + delay;
+		let mut state = self.lock();
+		state.banned_until = Some(state.banned_until.map_or(until, |banned_until| banned_until.max(until)));
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+impl State {
+	const fn roll_over(&mut self, now_millis: u64) {
+		let minute = now_millis / MINUTE_MILLIS;
+		if minute != self.minute {
+			self.minute = minute;
+			self.used = 0;
+		}
+	}
+
+	fn observe(&mut self, used: u32, now_millis: u64) {
+		self.roll_over(now_millis);
+		// Reservations of requests still in flight are not part of the reported weight yet
+		self.used = self.used.max(used);
+	}
+
+	/// Reserve `weight` or tell how long to wait before trying again
+	fn try_reserve(&mut self, weight: u32, now_millis: u64, now: Instant) -> Option<Duration> {
+		if let Some(banned_until) = self.banned_until {
+			if banned_until > now {
+				return Some(banned_until - now);
+			}
+			self.banned_until = None;
+		}
+
+		self.roll_over(now_millis);
+
+		// A single request heavier than the threshold still goes through on a fresh minute
+		if self.used > 0 && self.used + weight > THROTTLE_AT {
+			return Some(Duration::from_millis(MINUTE_MILLIS - now_millis % MINUTE_MILLIS));
+		}
+
+		self.used += weight;
+		None
+	}
+}
+
+/// Weight of a klines request
+pub const fn klines_weight(limit: u32) -> u32 {
+	match limit {
+		0..100 => 1,
+		100..500 => 2,
+		500..=1000 => 5,
+		_ => 10,
+	}
+}
+
+/// Weight of an order book request
+pub const fn order_book_weight(limit: u32) -> u32 {
+	match limit {
+		0..100 => 2,
+		100..500 => 5,
+		500..1000 => 10,
+		_ => 20,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reserves_until_threshold() {
+		let now = Instant::now();
+		let mut state = State::default();
+
+		assert_eq!(state.try_reserve(THROTTLE_AT, 60_000, now), None);
+		assert_eq!(state.try_reserve(1, 60_500, now), Some(Duration::from_millis(59_500)));
+		// Next minute starts from scratch
+		assert_eq!(state.try_reserve(1, 120_000, now), None);
+		assert_eq!(state.used, 1);
+	}
+
+	#[test]
+	fn waits_for_ban() {
+		let now = Instant::now();
+		let mut state = State { banned_until: Some(now + Duration::from_secs(30)), ..Default::default() };
+
+		assert_eq!(state.try_reserve(1, 0, now), Some(Duration::from_secs(30)));
+		assert_eq!(state.try_reserve(1, 0, now + Duration::from_secs(30)), None);
+		assert!(state.banned_until.is_none());
+	}
+
+	#[test]
+	fn observes_reported_weight() {
+		let mut state = State::default();
+
+		state.observe(1200, 60_000);
+		state.observe(10, 61_000);
+		assert_eq!(state.used, 1200);
+
+		state.observe(10, 120_000);
+		assert_eq!(state.used, 10);
+	}
+
+	#[test]
+	fn weighs_klines_by_limit() {
+		assert_eq!(klines_weight(99), 1);
+		assert_eq!(klines_weight(500), 5);
+		assert_eq!(klines_weight(1500), 10);
+	}
+
+	#[test]
+	fn weighs_order_book_by_limit() {
+		assert_eq!(order_book_weight(50), 2);
+		assert_eq!(order_book_weight(100), 5);
+		assert_eq!(order_book_weight(1000), 20);
+	}
+}
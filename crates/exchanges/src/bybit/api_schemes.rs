@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Side;
+
+// https://bybit-exchange.github.io/docs/v5/intro#common-response-parameters
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResponse<T> {
+	pub ret_code: i64,
+	pub ret_msg: String,
+	pub result: T,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+	#[default]
+	Linear,
+}
+
+// https://bybit-exchange.github.io/docs/v5/market/instrument
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentsInfoRequestParams {
+	pub category: Category,
+	/// default 500, max 1000
+	pub limit: Option<u32>,
+	pub cursor: Option<String>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentsInfoResponse {
+	pub list: Vec<InstrumentInfo>,
+	#[serde(default)]
+	pub next_page_cursor: String,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentInfo {
+	pub symbol: String,
+	pub contract_type: ContractType,
+	pub status: InstrumentStatus,
+	pub base_coin: String,
+	pub quote_coin: String,
+	/// Milliseconds since epoch
+	pub launch_time: String,
+	pub price_filter: PriceFilter,
+	pub lot_size_filter: LotSizeFilter,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceFilter {
+	pub tick_size: String,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LotSizeFilter {
+	pub qty_step: String,
+	#[serde(default)]
+	pub min_notional_value: String,
+}
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub enum ContractType {
+	LinearPerpetual,
+	LinearFutures,
+	#[serde(other)]
+	Unknown,
+}
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub enum InstrumentStatus {
+	PreLaunch,
+	Trading,
+	Delivering,
+	Closed,
+	#[serde(other)]
+	Unknown,
+}
+
+// https://bybit-exchange.github.io/docs/v5/market/kline
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct KlineRequestParams {
+	pub category: Category,
+	pub symbol: String,
+	/// 1, 3, 5, 15, 30, 60, 120, 240, 360, 720, D, W, M
+	pub interval: String,
+	/// default 200, max 1000
+	pub limit: Option<u32>,
+	pub start: Option<u64>,
+	pub end: Option<u64>,
+}
+#[derive(Debug, Deserialize)]
+pub struct KlineResponse {
+	/// Sorted in reverse order by start time
+	pub list: Vec<KlineItem>,
+}
+pub type KlineItem = (
+	String, // Start time
+	String, // Open
+	String, // High
+	String, // Low
+	String, // Close
+	String, // Volume (base coin)
+	String, // Turnover (quote coin)
+);
+
+// https://bybit-exchange.github.io/docs/v5/market/open-interest
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterestRequestParams {
+	pub category: Category,
+	pub symbol: String,
+	/// 5min, 15min, 30min, 1h, 4h, 1d
+	pub interval_time: String,
+	/// default 50, max 200
+	pub limit: Option<u32>,
+}
+#[derive(Debug, Deserialize)]
+pub struct OpenInterestResponse {
+	/// Sorted in reverse order by timestamp
+	pub list: Vec<OpenInterestItem>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterestItem {
+	pub open_interest: String,
+	pub timestamp: String,
+}
+
+// https://bybit-exchange.github.io/docs/v5/market/long-short-ratio
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRatioRequestParams {
+	pub category: Category,
+	pub symbol: String,
+	/// 5min, 15min, 30min, 1h, 4h, 1d
+	pub period: String,
+	/// default 50, max 500
+	pub limit: Option<u32>,
+}
+#[derive(Debug, Deserialize)]
+pub struct AccountRatioResponse {
+	/// Sorted in reverse order by timestamp
+	pub list: Vec<AccountRatioItem>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRatioItem {
+	/// Share of accounts holding longs
+	pub buy_ratio: String,
+	pub sell_ratio: String,
+	pub timestamp: String,
+}
+
+// https://bybit-exchange.github.io/docs/v5/market/orderbook
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBookRequestParams {
+	pub category: Category,
+	pub symbol: String,
+	/// default 25, max 500 for linear
+	pub limit: Option<u32>,
+}
+#[derive(Debug, Deserialize)]
+pub struct OrderBookResponse {
+	/// `[price, size]`, best first
+	#[serde(rename = "b")]
+	pub bids: Vec<(String, String)>,
+	#[serde(rename = "a")]
+	pub asks: Vec<(String, String)>,
+	/// Milliseconds since epoch
+	pub ts: u64,
+	/// Update id
+	pub u: u64,
+}
+
+// https://bybit-exchange.github.io/docs/v5/market/history-fund-rate
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateHistoryRequestParams {
+	pub category: Category,
+	pub symbol: String,
+	/// default 200, max 200
+	pub limit: Option<u32>,
+}
+#[derive(Debug, Deserialize)]
+pub struct FundingRateHistoryResponse {
+	/// Sorted in reverse order by funding time
+	pub list: Vec<FundingRateHistoryItem>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateHistoryItem {
+	pub funding_rate: String,
+	pub funding_rate_timestamp: String,
+}
+
+// https://bybit-exchange.github.io/docs/v5/market/tickers
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TickersRequestParams {
+	pub category: Category,
+	pub symbol: String,
+}
+#[derive(Debug, Deserialize)]
+pub struct TickersResponse {
+	pub list: Vec<TickerData>,
+}
+
+// https://bybit-exchange.github.io/docs/v5/ws/connect
+#[derive(Serialize)]
+pub struct WsRequest {
+	pub op: &'static str,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub args: Vec<String>,
+}
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct WsOperationResponse {
+	pub op: String,
+	#[serde(default)]
+	pub success: Option<bool>,
+	#[serde(default)]
+	pub ret_msg: String,
+}
+
+// https://bybit-exchange.github.io/docs/v5/websocket/public/all-liquidation
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct AllLiquidationStream {
+	pub topic: String,
+	#[serde(rename = "type")]
+	pub message_type: String,
+	pub ts: u64,
+	pub data: Vec<LiquidationInfo>,
+}
+#[derive(Debug, Deserialize)]
+pub struct LiquidationInfo {
+	#[serde(rename = "T")]
+	pub updated_time: u64,
+	#[serde(rename = "s")]
+	pub symbol: String,
+	/// Position side: `Buy` means a long position was liquidated, `Sell` a short one
+	#[serde(rename = "S")]
+	pub side: Side,
+	#[serde(rename = "v")]
+	pub size: String,
+	#[serde(rename = "p")]
+	pub price: String,
+}
+
+// https://bybit-exchange.github.io/docs/v5/websocket/public/trade
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct PublicTradeStream {
+	pub topic: String,
+	pub ts: u64,
+	pub data: Vec<PublicTradeInfo>,
+}
+#[derive(Debug, Deserialize)]
+pub struct PublicTradeInfo {
+	#[serde(rename = "T")]
+	pub trade_time: u64,
+	#[serde(rename = "s")]
+	pub symbol: String,
+	/// Taker side
+	#[serde(rename = "S")]
+	pub side: Side,
+	#[serde(rename = "v")]
+	pub size: String,
+	#[serde(rename = "p")]
+	pub price: String,
+}
+
+// https://bybit-exchange.github.io/docs/v5/websocket/public/ticker
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct TickerStream {
+	pub topic: String,
+	#[serde(rename = "type")]
+	pub message_type: String,
+	pub ts: u64,
+	pub data: TickerData,
+}
+/// `snapshot` messages carry every field, `delta` messages only the changed ones
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickerData {
+	pub symbol: String,
+	pub last_price: Option<String>,
+	pub prev_price_24h: Option<String>,
+	/// Fraction, e.g. `0.0123` for +1.23%
+	pub price_24h_pcnt: Option<String>,
+	pub high_price_24h: Option<String>,
+	pub low_price_24h: Option<String>,
+	pub volume_24h: Option<String>,
+	pub turnover_24h: Option<String>,
+	pub mark_price: Option<String>,
+	pub index_price: Option<String>,
+	pub funding_rate: Option<String>,
+	/// Milliseconds since epoch
+	pub next_funding_time: Option<String>,
+}
@@ -0,0 +1,881 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, bail};
+use futures_util::stream::BoxStream;
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::time::Duration;
+use tracing::error;
+
+use crate::{
+	CandleInfo, ContractKind, Exchange, ExchangeError, ExchangeKind, FUNDING_HISTORY_LIMIT, FundingRateInfo, Interval,
+	LiquidatedPosition, LocalOrderBook, LongShortRatio, LongShortRatioKind, MarkPriceInfo, MarketLiquidationsInfo,
+	OpenInterestInfo, OpenInterestPoint, Side, StreamEvent, SymbolInfo, SymbolStatus, TakerVolume, TickerInfo, TradeInfo,
+	bybit::api_schemes::{
+		AccountRatioRequestParams, AccountRatioResponse, AllLiquidationStream, ApiResponse, ContractType,
+		FundingRateHistoryRequestParams, FundingRateHistoryResponse, InstrumentInfo, InstrumentStatus,
+		InstrumentsInfoRequestParams, InstrumentsInfoResponse, KlineItem, KlineRequestParams, KlineResponse,
+		OpenInterestRequestParams, OpenInterestResponse, OrderBookRequestParams, OrderBookResponse, PublicTradeStream,
+		TickerData, TickerStream, TickersRequestParams, TickersResponse, WsOperationResponse, WsRequest,
+	},
+	capture,
+	replay::Replay,
+	stream::{Heartbeat, RECONNECT_DELAY, Subscription, reconnecting_stream},
+	symbol_info::SymbolCache,
+	utils::{fetch_candles_range, funding_rate_info_from_history, open_interest_info_from_series, parse_levels},
+};
+mod api_schemes;
+
+const BYBIT_API_BASE: &str = "https://api.bybit.com";
+
+const WS_URL: &str = "wss://stream.bybit.com/v5/public/linear";
+/// Bybit drops connections without a heartbeat, recommended interval is 20 seconds
+const PING_EVERY: Duration = Duration::from_secs(20);
+const PONG_TIMEOUT: Duration = Duration::from_mins(1);
+/// Max topics per subscribe request
+const SUBSCRIBE_BATCH_SIZE: usize = 10;
+const KLINE_MAX_LIMIT: u32 = 1000;
+const OPEN_INTEREST_MAX_LIMIT: u32 = 200;
+const ORDER_BOOK_MAX_LIMIT: u32 = 500;
+const LONG_SHORT_RATIO_MAX_LIMIT: u32 = 500;
+/// `retCode` when the request rate is exceeded
+const RATE_LIMIT_CODE: i64 = 10006;
+/// `retCode` for invalid request parameters, including unknown symbols
+const INVALID_PARAMETER_CODE: i64 = 10001;
+
+#[derive(Clone)]
+pub struct BybitExchange {
+	client: reqwest::Client,
+	symbols: SymbolCache,
+}
+
+impl BybitExchange {
+	#[must_use]
+	pub fn new() -> Self {
+		Self { client: reqwest::Client::new(), symbols: SymbolCache::default() }
+	}
+
+	async fn get<T, Q>(&self, path: &str, query: &Q) -> anyhow::Result<T>
+	where
+		T: DeserializeOwned,
+		Q: Serialize + Sync,
+	{
+		let request = self.client.get(format!("{BYBIT_API_BASE}{path}")).query(query).build()?;
+		let url = request.url().to_string();
+		let body = if let Some(replay) = Replay::current() {
+			replay.response(&url)?
+		} else {
+			let body = self.client.execute(request).await?.error_for_status()?.text().await?;
+			capture::record(&url, &body);
+			body
+		};
+
+		// Error responses carry an empty `result` object, so decode it only after checking `retCode`
+		let response: ApiResponse<serde_json::Value> = serde_json::from_str(&body)?;
+
+		match response.ret_code {
+			0 => {},
+			RATE_LIMIT_CODE => return Err(ExchangeError::RateLimited { retry_after: None }.into()),
+			INVALID_PARAMETER_CODE if response.ret_msg.to_lowercase().contains("symbol") => {
+				let symbol = serde_json::to_value(query)?.get("symbol").and_then(|symbol| symbol.as_str()).map(String::from);
+				return Err(ExchangeError::UnknownSymbol(symbol.unwrap_or_default()).into());
+			},
+			code => bail!("Bybit API error {code}: {}", response.ret_msg),
+		}
+
+		serde_json::from_value(response.result).context("Failed to decode Bybit response")
+	}
+
+	/// Subscribe to `tickers.{symbol}` of all USDT pairs, converting the merged ticker state on every update
+	fn watch_ticker_topic<T: Send + 'static>(
+		&self,
+		convert: fn(&TickerData, u64) -> Option<T>,
+	) -> BoxStream<'static, StreamEvent<T>> {
+		let exchange = self.clone();
+
+		reconnecting_stream(RECONNECT_DELAY, move || {
+			let exchange = exchange.clone();
+			async move {
+				let symbols = exchange.get_all_usdt_pairs().await?;
+				// Deltas are applied on top of the snapshot received right after subscribing
+				let mut tickers = HashMap::new();
+
+				Ok(Subscription {
+					url: String::from(WS_URL),
+					messages: subscription_messages("tickers", &symbols)?,
+					heartbeat: Heartbeat::Text {
+						ping: serde_json::to_string(&WsRequest { op: "ping", args: Vec::new() })?,
+						is_pong,
+					},
+					ping_every: PING_EVERY,
+					pong_timeout: PONG_TIMEOUT,
+					parse: move |text: &str| parse_frame(text, |text| parse_ticker(text, &mut tickers, convert)),
+				})
+			}
+		})
+	}
+
+	async fn fetch_klines(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		limit: u32,
+		start: Option<u64>,
+		end: Option<u64>,
+	) -> anyhow::Result<Vec<CandleInfo>> {
+		let response: KlineResponse = self
+			.get(
+				"/v5/market/kline",
+				&KlineRequestParams {
+					symbol: String::from(symbol),
+					interval: String::from(to_bybit_interval(interval)?),
+					limit: Some(limit.min(KLINE_MAX_LIMIT)),
+					start,
+					end,
+					..Default::default()
+				},
+			)
+			.await
+			.context(format!("Failed to fetch klines info for {symbol}"))?;
+
+		Ok(parse_klines(&response.list, interval))
+	}
+}
+
+impl Default for BybitExchange {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[async_trait::async_trait]
+impl Exchange for BybitExchange {
+	async fn get_all_usdt_pairs(&self) -> Result<Vec<String>, ExchangeError> {
+		let symbols = self.get_symbols().await?;
+
+		Ok(
+			symbols
+				.into_iter()
+				.filter(|info| info.contract_kind == ContractKind::Perpetual && info.is_trading())
+				.map(|info| info.symbol)
+				.collect(),
+		)
+	}
+
+	async fn get_symbols(&self) -> Result<Vec<SymbolInfo>, ExchangeError> {
+		self
+			.symbols
+			.get_or_fetch(|| async {
+				let mut symbols = Vec::new();
+				let mut cursor = None;
+
+				loop {
+					let response: InstrumentsInfoResponse = self
+						.get(
+							"/v5/market/instruments-info",
+							&InstrumentsInfoRequestParams { limit: Some(1000), cursor, ..Default::default() },
+						)
+						.await
+						.context("Failed to fetch instruments info")?;
+
+					symbols.extend(response.list.iter().filter_map(parse_instrument));
+
+					if response.next_page_cursor.is_empty() {
+						break;
+					}
+					cursor = Some(response.next_page_cursor);
+				}
+
+				Ok(symbols)
+			})
+			.await
+	}
+
+	fn watch_market_liquidations(&self) -> BoxStream<'static, StreamEvent<MarketLiquidationsInfo>> {
+		let exchange = self.clone();
+
+		// Bybit has no all-market liquidation topic, so pairs are refetched on every reconnect
+		reconnecting_stream(RECONNECT_DELAY, move || {
+			let exchange = exchange.clone();
+			async move {
+				let symbols = exchange.get_all_usdt_pairs().await?;
+
+				Ok(Subscription {
+					url: String::from(WS_URL),
+					messages: subscription_messages("allLiquidation", &symbols)?,
+					heartbeat: Heartbeat::Text {
+						ping: serde_json::to_string(&WsRequest { op: "ping", args: Vec::new() })?,
+						is_pong,
+					},
+					ping_every: PING_EVERY,
+					pong_timeout: PONG_TIMEOUT,
+					parse: |text: &str| parse_frame(text, parse_liquidations),
+				})
+			}
+		})
+	}
+
+	fn watch_tickers(&self) -> BoxStream<'static, StreamEvent<TickerInfo>> {
+		self.watch_ticker_topic(ticker_info)
+	}
+
+	fn watch_mark_prices(&self) -> BoxStream<'static, StreamEvent<MarkPriceInfo>> {
+		self.watch_ticker_topic(mark_price_info)
+	}
+
+	fn watch_trades(&self, symbol: &str) -> BoxStream<'static, StreamEvent<TradeInfo>> {
+		let symbol = String::from(symbol);
+
+		reconnecting_stream(RECONNECT_DELAY, move || {
+			let messages = subscription_messages("publicTrade", std::slice::from_ref(&symbol));
+			let topic = format!("publicTrade.{symbol}");
+			async move {
+				Ok(Subscription {
+					url: String::from(WS_URL),
+					messages: messages?,
+					heartbeat: Heartbeat::Text {
+						ping: serde_json::to_string(&WsRequest { op: "ping", args: Vec::new() })?,
+						is_pong,
+					},
+					ping_every: PING_EVERY,
+					pong_timeout: PONG_TIMEOUT,
+					parse: move |text: &str| parse_frame(text, |text| parse_trades(text, &topic)),
+				})
+			}
+		})
+	}
+
+	async fn get_klines(&self, symbol: &str, interval: Interval, limit: u32) -> Result<Vec<CandleInfo>, ExchangeError> {
+		Ok(self.fetch_klines(symbol, interval, limit, None, None).await?)
+	}
+
+	async fn get_klines_range(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<CandleInfo>, ExchangeError> {
+		fetch_candles_range(interval, from, to, KLINE_MAX_LIMIT, |start, end| {
+			self.fetch_klines(symbol, interval, KLINE_MAX_LIMIT, Some(start), Some(end))
+		})
+		.await
+	}
+
+	async fn get_funding_rate_info(&self, symbol: &str) -> Result<FundingRateInfo, ExchangeError> {
+		let tickers_params = TickersRequestParams { symbol: String::from(symbol), ..Default::default() };
+		let history_params = FundingRateHistoryRequestParams {
+			symbol: String::from(symbol),
+			limit: Some(FUNDING_HISTORY_LIMIT),
+			..Default::default()
+		};
+
+		let (tickers, history) = tokio::try_join!(
+			self.get::<TickersResponse, _>("/v5/market/tickers", &tickers_params),
+			self.get::<FundingRateHistoryResponse, _>("/v5/market/funding/history", &history_params),
+		)
+		.context(format!("Failed to fetch funding rate info for {symbol}"))?;
+
+		let ticker = tickers.list.first().context(format!("No ticker for {symbol}"))?;
+		Ok(parse_funding_rate_info(ticker, &history)?)
+	}
+
+	async fn get_open_interest_info(&self, symbol: &str) -> Result<OpenInterestInfo, ExchangeError> {
+		// 48 x 5m to get 5m - 4h distance, 30 x 1d for 30 days
+		let (series_5m, series_1d) = tokio::try_join!(
+			self.get_open_interest_history(symbol, Interval::Minute5, 48),
+			self.get_open_interest_history(symbol, Interval::Day1, 30),
+		)?;
+
+		Ok(open_interest_info_from_series(&series_5m, &series_1d)?)
+	}
+
+	async fn get_open_interest_history(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<OpenInterestPoint>, ExchangeError> {
+		let response: OpenInterestResponse = self
+			.get(
+				"/v5/market/open-interest",
+				&OpenInterestRequestParams {
+					symbol: String::from(symbol),
+					interval_time: String::from(to_bybit_period(period)?),
+					limit: Some(limit.min(OPEN_INTEREST_MAX_LIMIT)),
+					..Default::default()
+				},
+			)
+			.await
+			.context(format!("Failed to fetch open interest history for {symbol} ({period})"))?;
+
+		Ok(parse_open_interest_series(&response)?)
+	}
+
+	async fn get_order_book(&self, symbol: &str, limit: u32) -> Result<LocalOrderBook, ExchangeError> {
+		let response: OrderBookResponse = self
+			.get(
+				"/v5/market/orderbook",
+				&OrderBookRequestParams {
+					symbol: String::from(symbol),
+					limit: Some(limit.min(ORDER_BOOK_MAX_LIMIT)),
+					..Default::default()
+				},
+			)
+			.await
+			.context(format!("Failed to fetch order book for {symbol}"))?;
+
+		Ok(parse_order_book(symbol, &response)?)
+	}
+
+	async fn get_long_short_ratio(
+		&self,
+		symbol: &str,
+		kind: LongShortRatioKind,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<LongShortRatio>, ExchangeError> {
+		if kind != LongShortRatioKind::GlobalAccount {
+			return Err(ExchangeError::Unsupported(format!("Bybit {kind:?} long/short ratio")));
+		}
+
+		let response: AccountRatioResponse = self
+			.get(
+				"/v5/market/account-ratio",
+				&AccountRatioRequestParams {
+					symbol: String::from(symbol),
+					period: String::from(to_bybit_period(period)?),
+					limit: Some(limit.min(LONG_SHORT_RATIO_MAX_LIMIT)),
+					..Default::default()
+				},
+			)
+			.await
+			.context(format!("Failed to fetch long/short ratio for {symbol}"))?;
+
+		Ok(parse_account_ratios(&response)?)
+	}
+
+	async fn get_taker_volume(
+		&self,
+		_symbol: &str,
+		_period: Interval,
+		_limit: u32,
+	) -> Result<Vec<TakerVolume>, ExchangeError> {
+		Err(ExchangeError::Unsupported(String::from("Bybit taker buy/sell volume")))
+	}
+}
+
+/// Periods supported by the open interest and long/short ratio endpoints
+fn to_bybit_period(interval: Interval) -> anyhow::Result<&'static str> {
+	let period = match interval {
+		Interval::Minute5 => "5min",
+		Interval::Minute15 => "15min",
+		Interval::Minute30 => "30min",
+		Interval::Hour1 => "1h",
+		Interval::Hour4 => "4h",
+		Interval::Day1 => "1d",
+		_ => return Err(ExchangeError::Unsupported(format!("Bybit statistics period {interval}")).into()),
+	};
+
+	Ok(period)
+}
+
+fn to_bybit_interval(interval: Interval) -> anyhow::Result<&'static str> {
+	let bybit_interval = match interval {
+		Interval::Minute1 => "1",
+		Interval::Minute3 => "3",
+		Interval::Minute5 => "5",
+		Interval::Minute15 => "15",
+		Interval::Minute30 => "30",
+		Interval::Hour1 => "60",
+		Interval::Hour2 => "120",
+		Interval::Hour4 => "240",
+		Interval::Hour6 => "360",
+		Interval::Hour12 => "720",
+		Interval::Day1 => "D",
+		Interval::Week1 => "W",
+		Interval::Month1 => "M",
+		Interval::Hour8 | Interval::Day3 => {
+			return Err(ExchangeError::Unsupported(format!("Bybit kline interval {interval}")).into());
+		},
+	};
+
+	Ok(bybit_interval)
+}
+
+/// Only USDT contracts are kept, instruments with malformed filters are skipped
+fn parse_instrument(instrument: &InstrumentInfo) -> Option<SymbolInfo> {
+	if instrument.quote_coin != "USDT" {
+		return None;
+	}
+
+	Some(SymbolInfo {
+		symbol: instrument.symbol.clone(),
+		base_asset: instrument.base_coin.clone(),
+		quote_asset: instrument.quote_coin.clone(),
+		contract_kind: match instrument.contract_type {
+			ContractType::LinearPerpetual => ContractKind::Perpetual,
+			ContractType::LinearFutures => ContractKind::Delivery,
+			ContractType::Unknown => ContractKind::Unknown,
+		},
+		onboard_date: instrument.launch_time.parse().ok()?,
+		tick_size: instrument.price_filter.tick_size.parse().ok()?,
+		step_size: instrument.lot_size_filter.qty_step.parse().ok()?,
+		min_notional: instrument.lot_size_filter.min_notional_value.parse().ok(),
+		contract_size: None,
+		status: match instrument.status {
+			InstrumentStatus::PreLaunch => SymbolStatus::PreTrading,
+			InstrumentStatus::Trading => SymbolStatus::Trading,
+			InstrumentStatus::Delivering => SymbolStatus::Settling,
+			InstrumentStatus::Closed => SymbolStatus::Closed,
+			InstrumentStatus::Unknown => SymbolStatus::Unknown,
+		},
+	})
+}
+
+/// Bybit returns newest candles first, `CandleInfo` list is ordered oldest → newest
+fn parse_klines(list: &[KlineItem], interval: Interval) -> Vec<CandleInfo> {
+	list
+		.iter()
+		.rev()
+		.filter_map(|v| {
+			let open_time: u64 = v.0.parse().ok()?;
+
+			Some(CandleInfo {
+				open_time,
+				close_time: open_time + interval.as_millis() - 1,
+				open: v.1.parse().ok()?,
+				high: v.2.parse().ok()?,
+				low: v.3.parse().ok()?,
+				close: v.4.parse().ok()?,
+				volume: v.5.parse().ok()?,
+				quote_volume: v.6.parse().ok()?,
+				trades: None,
+				taker_buy_volume: None,
+				taker_buy_quote_volume: None,
+			})
+		})
+		.collect()
+}
+
+/// Bybit returns newest entries first, the series is ordered oldest → newest
+fn parse_open_interest_series(response: &OpenInterestResponse) -> anyhow::Result<Vec<OpenInterestPoint>> {
+	response
+		.list
+		.iter()
+		.rev()
+		.map(|item| {
+			Ok(OpenInterestPoint {
+				open_interest: item
+					.open_interest
+					.parse()
+					.context(format!("Failed to parse open interest: {}", item.open_interest))?,
+				open_interest_usd: None,
+				timestamp: item.timestamp.parse().context(format!("Failed to parse timestamp: {}", item.timestamp))?,
+			})
+		})
+		.collect()
+}
+
+fn subscription_messages(topic: &str, symbols: &[String]) -> anyhow::Result<Vec<String>> {
+	symbols
+		.chunks(SUBSCRIBE_BATCH_SIZE)
+		.map(|batch| {
+			let request =
+				WsRequest { op: "subscribe", args: batch.iter().map(|symbol| format!("{topic}.{symbol}")).collect() };
+			Ok(serde_json::to_string(&request)?)
+		})
+		.collect()
+}
+
+fn is_pong(text: &str) -> bool {
+	serde_json::from_str::<WsOperationResponse>(text)
+		.is_ok_and(|response| matches!(response.op.as_str(), "ping" | "pong"))
+}
+
+/// Decode a data frame with `parse`, failed subscriptions are logged
+fn parse_frame<T>(text: &str, parse: impl FnOnce(&str) -> anyhow::Result<Vec<T>>) -> anyhow::Result<Vec<T>> {
+	if let Ok(response) = serde_json::from_str::<WsOperationResponse>(text) {
+		if response.op == "subscribe" && response.success == Some(false) {
+			error!("Subscribe failed: {}", response.ret_msg);
+		}
+		return Ok(Vec::new());
+	}
+
+	parse(text)
+}
+
+/// Frames of other topics are skipped, trade frames decode into the same shape
+fn parse_liquidations(text: &str) -> anyhow::Result<Vec<MarketLiquidationsInfo>> {
+	let stream: AllLiquidationStream = serde_json::from_str(text)?;
+	if !stream.topic.starts_with("allLiquidation.") {
+		return Ok(Vec::new());
+	}
+
+	stream
+		.data
+		.into_iter()
+		.map(|item| {
+			let symbol_price = item.price.parse::<f64>().context(format!("Failed to parse price: {}", item.price))?;
+			let quantity = item.size.parse::<f64>().context(format!("Failed to parse quantity: {}", item.size))?;
+
+			// Bybit reports the liquidated position side, convert it to the liquidation order side
+			let position = match item.side {
+				Side::Buy => LiquidatedPosition::Long,
+				Side::Sell => LiquidatedPosition::Short,
+			};
+
+			Ok(MarketLiquidationsInfo {
+				symbol: item.symbol,
+				exchange: ExchangeKind::Bybit,
+				side: position.closing_side(),
+				order_type: None,
+				symbol_price,
+				average_price: symbol_price,
+				usd_price: symbol_price * quantity,
+				quantity,
+				filled_quantity: quantity,
+				time: item.updated_time,
+				trade_time: item.updated_time,
+			})
+		})
+		.collect()
+}
+
+/// Merge a ticker message into `tickers` and convert the updated ticker, nothing is returned until every field is known
+fn parse_ticker<T>(
+	text: &str,
+	tickers: &mut HashMap<String, TickerData>,
+	convert: fn(&TickerData, u64) -> Option<T>,
+) -> anyhow::Result<Vec<T>> {
+	let stream: TickerStream = serde_json::from_str(text)?;
+	if !stream.topic.starts_with("tickers.") {
+		return Ok(Vec::new());
+	}
+	let update = stream.data;
+
+	let ticker = tickers.entry(update.symbol.clone()).or_default();
+	if stream.message_type == "snapshot" {
+		*ticker = update;
+	} else {
+		let merge = |field: &mut Option<String>, value: Option<String>| {
+			if value.is_some() {
+				*field = value;
+			}
+		};
+		merge(&mut ticker.last_price, update.last_price);
+		merge(&mut ticker.prev_price_24h, update.prev_price_24h);
+		merge(&mut ticker.price_24h_pcnt, update.price_24h_pcnt);
+		merge(&mut ticker.high_price_24h, update.high_price_24h);
+		merge(&mut ticker.low_price_24h, update.low_price_24h);
+		merge(&mut ticker.volume_24h, update.volume_24h);
+		merge(&mut ticker.turnover_24h, update.turnover_24h);
+		merge(&mut ticker.mark_price, update.mark_price);
+		merge(&mut ticker.index_price, update.index_price);
+		merge(&mut ticker.funding_rate, update.funding_rate);
+		merge(&mut ticker.next_funding_time, update.next_funding_time);
+	}
+
+	Ok(convert(ticker, stream.ts).into_iter().collect())
+}
+
+fn ticker_info(ticker: &TickerData, time: u64) -> Option<TickerInfo> {
+	let parse = |value: Option<&String>| value.and_then(|value| value.parse::<f64>().ok());
+
+	Some(TickerInfo {
+		symbol: ticker.symbol.clone(),
+		last_price: parse(ticker.last_price.as_ref())?,
+		open_price: parse(ticker.prev_price_24h.as_ref())?,
+		high_price: parse(ticker.high_price_24h.as_ref())?,
+		low_price: parse(ticker.low_price_24h.as_ref())?,
+		price_change_percent: parse(ticker.price_24h_pcnt.as_ref())? * 100.0,
+		volume: parse(ticker.volume_24h.as_ref())?,
+		quote_volume: parse(ticker.turnover_24h.as_ref())?,
+		trades: None,
+		time,
+	})
+}
+
+/// Bybit returns newest entries first, the series is ordered oldest → newest
+fn parse_account_ratios(response: &AccountRatioResponse) -> anyhow::Result<Vec<LongShortRatio>> {
+	response
+		.list
+		.iter()
+		.rev()
+		.map(|item| {
+			let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse account ratio: {value}"));
+			let long_share = parse(&item.buy_ratio)?;
+			let short_share = parse(&item.sell_ratio)?;
+
+			Ok(LongShortRatio {
+				long_short_ratio: if short_share == 0.0 { 0.0 } else { long_share / short_share },
+				long_share,
+				short_share,
+				timestamp: item.timestamp.parse().context(format!("Failed to parse timestamp: {}", item.timestamp))?,
+			})
+		})
+		.collect()
+}
+
+fn mark_price_info(ticker: &TickerData, time: u64) -> Option<MarkPriceInfo> {
+	let parse = |value: Option<&String>| value.and_then(|value| value.parse::<f64>().ok());
+
+	Some(MarkPriceInfo {
+		symbol: ticker.symbol.clone(),
+		mark_price: parse(ticker.mark_price.as_ref())?,
+		index_price: parse(ticker.index_price.as_ref())?,
+		funding_rate: parse(ticker.funding_rate.as_ref())?,
+		next_funding_time: ticker.next_funding_time.as_ref()?.parse().ok()?,
+		time,
+	})
+}
+
+/// Frames of topics other than `topic` are skipped, liquidation frames decode into the same shape
+fn parse_trades(text: &str, topic: &str) -> anyhow::Result<Vec<TradeInfo>> {
+	let stream: PublicTradeStream = serde_json::from_str(text)?;
+	if stream.topic != topic {
+		return Ok(Vec::new());
+	}
+
+	stream
+		.data
+		.into_iter()
+		.map(|item| {
+			Ok(TradeInfo {
+				price: item.price.parse().context(format!("Failed to parse price: {}", item.price))?,
+				quantity: item.size.parse().context(format!("Failed to parse quantity: {}", item.size))?,
+				taker_side: item.side,
+				time: item.trade_time,
+				symbol: item.symbol,
+			})
+		})
+		.collect()
+}
+
+/// Bybit returns funding history newest first
+fn parse_funding_rate_info(
+	ticker: &TickerData,
+	history: &FundingRateHistoryResponse,
+) -> anyhow::Result<FundingRateInfo> {
+	let (Some(funding_rate), Some(next_funding_time)) = (&ticker.funding_rate, &ticker.next_funding_time) else {
+		bail!("No funding in ticker of {}", ticker.symbol);
+	};
+	let funding_rate = funding_rate.parse().context(format!("Failed to parse funding rate: {funding_rate}"))?;
+	let next_funding_time =
+		next_funding_time.parse().context(format!("Failed to parse next funding time: {next_funding_time}"))?;
+
+	let history = history
+		.list
+		.iter()
+		.rev()
+		.map(|item| {
+			let rate = item.funding_rate.parse().context(format!("Failed to parse funding rate: {}", item.funding_rate))?;
+			let time = item
+				.funding_rate_timestamp
+				.parse()
+				.context(format!("Failed to parse funding time: {}", item.funding_rate_timestamp))?;
+			Ok((time, rate))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	Ok(funding_rate_info_from_history(funding_rate, next_funding_time, &history))
+}
+
+fn parse_order_book(symbol: &str, response: &OrderBookResponse) -> anyhow::Result<LocalOrderBook> {
+	Ok(LocalOrderBook::from_snapshot(
+		symbol,
+		response.u,
+		parse_levels(&response.bids)?,
+		parse_levels(&response.asks)?,
+		response.ts,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bybit::api_schemes::FundingRateHistoryResponse;
+
+	fn parse_result<T: DeserializeOwned>(fixture: &str) -> T {
+		let response: ApiResponse<T> = serde_json::from_str(fixture).unwrap();
+		assert_eq!(response.ret_code, 0);
+		response.result
+	}
+
+	#[test]
+	fn parses_instruments_info() {
+		let response: InstrumentsInfoResponse = parse_result(include_str!("../../fixtures/bybit/instruments_info.json"));
+
+		assert_eq!(response.list.len(), 3);
+		assert_eq!(response.list[0].symbol, "BTCUSDT");
+		assert_eq!(response.list[0].contract_type, ContractType::LinearPerpetual);
+		assert_eq!(response.list[0].status, InstrumentStatus::Trading);
+		assert_eq!(response.list[1].contract_type, ContractType::LinearFutures);
+		assert_eq!(response.list[2].status, InstrumentStatus::PreLaunch);
+		assert_eq!(response.next_page_cursor, "next");
+
+		let symbols: Vec<_> = response.list.iter().filter_map(parse_instrument).collect();
+		assert_eq!(symbols.len(), 3);
+		assert_eq!(symbols[0].base_asset, "BTC");
+		assert_eq!(symbols[0].onboard_date, 1_585_526_400_000);
+		assert_eq!(symbols[0].min_notional, Some(5.0));
+		assert_eq!(symbols[0].price_precision(), 1);
+		assert_eq!(symbols[1].contract_kind, ContractKind::Delivery);
+		assert_eq!(symbols[2].status, SymbolStatus::PreTrading);
+	}
+
+	#[test]
+	fn parses_klines_oldest_first() {
+		let response: KlineResponse = parse_result(include_str!("../../fixtures/bybit/kline.json"));
+		let candles = parse_klines(&response.list, Interval::Hour1);
+
+		assert_eq!(candles.len(), 2);
+		assert_eq!(candles[0].open_time, 1_760_000_000_000);
+		assert_eq!(candles[0].close_time, 1_760_003_599_999);
+		assert!((candles[1].quote_volume - 120_000_000.0).abs() < f64::EPSILON);
+		assert!((candles[0].open - 96_000.0).abs() < f64::EPSILON);
+		assert!((candles[1].close - 97_500.5).abs() < f64::EPSILON);
+		assert!((candles[1].volume - 1_234.5).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn parses_open_interest_oldest_first() {
+		let response: OpenInterestResponse = parse_result(include_str!("../../fixtures/bybit/open_interest.json"));
+		let series = parse_open_interest_series(&response).unwrap();
+
+		assert_eq!(series.iter().map(|point| point.open_interest).collect::<Vec<_>>(), vec![50_000.0, 51_000.0, 52_500.0]);
+		assert_eq!(series[0].timestamp, 1_760_000_000_000);
+	}
+
+	#[test]
+	fn parses_funding_rate_history() {
+		let response: FundingRateHistoryResponse = parse_result(include_str!("../../fixtures/bybit/funding_history.json"));
+
+		let ticker: TickerStream = serde_json::from_str(include_str!("../../fixtures/bybit/ticker_snapshot.json")).unwrap();
+
+		let info = parse_funding_rate_info(&ticker.data, &response).unwrap();
+
+		assert_eq!(response.list.len(), 2);
+		assert!((info.funding_rate - 0.0001).abs() < f64::EPSILON);
+		assert!((info.average_funding_rate - 0.000_025).abs() < f64::EPSILON);
+		assert_eq!(info.funding_interval_hours, 8);
+		assert_eq!(info.next_funding_time, 1_760_025_600_000);
+	}
+
+	#[test]
+	fn parses_public_trades() {
+		let trade_frame = include_str!("../../fixtures/bybit/public_trade.json");
+		let trades = parse_frame(trade_frame, |text| parse_trades(text, "publicTrade.BTCUSDT")).unwrap();
+
+		assert!(parse_trades(trade_frame, "publicTrade.ETHUSDT").unwrap().is_empty());
+		assert!(parse_liquidations(trade_frame).unwrap().is_empty());
+		assert_eq!(trades.len(), 2);
+		assert_eq!(trades[0].taker_side, Side::Buy);
+		assert_eq!(trades[1].taker_side, Side::Sell);
+		assert!((trades[1].quantity - 0.5).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn parses_order_book() {
+		let response: OrderBookResponse = parse_result(include_str!("../../fixtures/bybit/orderbook.json"));
+		let book = parse_order_book("BTCUSDT", &response).unwrap();
+
+		assert_eq!(book.last_update_id(), 1_864_523);
+		assert_eq!(book.best_bid().map(|level| level.price), Some(97_000.1));
+		assert_eq!(book.best_ask().map(|level| level.price), Some(97_000.2));
+		assert_eq!(book.asks().count(), 2);
+	}
+
+	#[test]
+	fn parses_all_liquidation_stream() {
+		let liquidations = parse_liquidations(include_str!("../../fixtures/bybit/all_liquidation.json")).unwrap();
+
+		assert_eq!(liquidations.len(), 2);
+		assert_eq!(liquidations[0].symbol, "ROSEUSDT");
+		assert_eq!(liquidations[0].side, Side::Sell);
+		assert_eq!(liquidations[0].liquidated_position(), LiquidatedPosition::Long);
+		assert!((liquidations[0].usd_price - 899.8).abs() < 1e-9);
+		assert_eq!(liquidations[0].time, 1_739_502_302_929);
+		assert_eq!(liquidations[1].liquidated_position(), LiquidatedPosition::Short);
+	}
+
+	#[test]
+	fn merges_ticker_deltas() {
+		let mut tickers = HashMap::new();
+
+		let snapshot =
+			parse_ticker(include_str!("../../fixtures/bybit/ticker_snapshot.json"), &mut tickers, ticker_info).unwrap();
+		assert_eq!(snapshot.len(), 1);
+		assert_eq!(snapshot[0].symbol, "BTCUSDT");
+		assert!((snapshot[0].last_price - 97_000.0).abs() < f64::EPSILON);
+		assert!((snapshot[0].price_change_percent - 1.5).abs() < 1e-9);
+
+		let delta =
+			parse_ticker(include_str!("../../fixtures/bybit/ticker_delta.json"), &mut tickers, ticker_info).unwrap();
+		assert!((delta[0].last_price - 97_100.5).abs() < f64::EPSILON);
+		assert!((delta[0].open_price - 95_566.5).abs() < f64::EPSILON);
+		assert!((delta[0].quote_volume - 8_000_000_000.0).abs() < f64::EPSILON);
+		assert_eq!(delta[0].time, 1_760_000_001_000);
+	}
+
+	#[test]
+	fn parses_mark_price_from_tickers() {
+		let mut tickers = HashMap::new();
+
+		let snapshot =
+			parse_ticker(include_str!("../../fixtures/bybit/ticker_snapshot.json"), &mut tickers, mark_price_info).unwrap();
+		assert!((snapshot[0].mark_price - 97_001.2).abs() < f64::EPSILON);
+		assert!((snapshot[0].funding_rate - 0.000_1).abs() < f64::EPSILON);
+		assert_eq!(snapshot[0].next_funding_time, 1_760_025_600_000);
+
+		let delta =
+			parse_ticker(include_str!("../../fixtures/bybit/ticker_delta.json"), &mut tickers, mark_price_info).unwrap();
+		assert!((delta[0].mark_price - 97_101.0).abs() < f64::EPSILON);
+		assert!((delta[0].index_price - 97_010.0).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn skips_incomplete_ticker_delta() {
+		let mut tickers = HashMap::new();
+
+		let delta =
+			parse_ticker(include_str!("../../fixtures/bybit/ticker_delta.json"), &mut tickers, ticker_info).unwrap();
+		assert!(delta.is_empty());
+	}
+
+	#[test]
+	fn parses_account_ratio_oldest_first() {
+		let response: AccountRatioResponse = parse_result(include_str!("../../fixtures/bybit/account_ratio.json"));
+		let ratios = parse_account_ratios(&response).unwrap();
+
+		assert_eq!(ratios.len(), 2);
+		assert_eq!(ratios[0].timestamp, 1_760_000_000_000);
+		assert!((ratios[1].long_share - 0.6).abs() < f64::EPSILON);
+		assert!((ratios[1].long_short_ratio - 1.5).abs() < 1e-9);
+	}
+
+	#[test]
+	fn skips_operation_responses() {
+		let pong = r#"{"success":true,"ret_msg":"pong","conn_id":"id","op":"ping"}"#;
+		let subscribed = r#"{"success":true,"ret_msg":"","conn_id":"id","op":"subscribe"}"#;
+
+		assert!(is_pong(pong));
+		assert!(!is_pong(subscribed));
+		assert!(parse_frame(subscribed, parse_liquidations).unwrap().is_empty());
+	}
+
+	#[test]
+	fn parses_api_error_with_empty_result() {
+		let response: ApiResponse<serde_json::Value> =
+			serde_json::from_str(r#"{"retCode":10001,"retMsg":"params error","result":{}}"#).unwrap();
+
+		assert_eq!(response.ret_code, 10001);
+		assert_eq!(response.ret_msg, "params error");
+	}
+
+	#[test]
+	fn maps_intervals() {
+		assert_eq!(to_bybit_interval(Interval::Hour1).unwrap(), "60");
+		assert_eq!(to_bybit_interval(Interval::Week1).unwrap(), "W");
+		assert!(to_bybit_interval(Interval::Hour8).is_err());
+	}
+}
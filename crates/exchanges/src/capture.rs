@@ -0,0 +1,191 @@
+use std::{
+	path::{Path, PathBuf},
+	sync::{
+		Arc, OnceLock,
+		atomic::{AtomicU64, Ordering},
+	},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tokio::{
+	fs::File,
+	io::{AsyncWriteExt, BufWriter},
+	sync::mpsc,
+};
+use tracing::{error, warn};
+
+use crate::utils::now_millis;
+
+/// Capture files are rotated once they grow past this size
+pub const DEFAULT_CAPTURE_FILE_BYTES: u64 = 256 * 1024 * 1024;
+const CHANNEL_CAPACITY: usize = 4096;
+
+static RECORDER: OnceLock<FrameRecorder> = OnceLock::new();
+
+/// Raw websocket frame or REST response body, stored one per line in capture files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedFrame {
+	/// Milliseconds since epoch
+	pub received_at: u64,
+	/// Websocket endpoint the frame arrived on, or the full URL of the GET request answered by it
+	pub url: String,
+	pub frame: String,
+}
+
+/// Writes frames to `capture-<millis>-<n>.jsonl` files in a directory from a background task
+#[derive(Clone)]
+pub struct FrameRecorder {
+	frames: mpsc::Sender<CapturedFrame>,
+	/// Frames dropped since the writer last caught up
+	dropped: Arc<AtomicU64>,
+}
+
+impl FrameRecorder {
+	/// A new file is started once the current one would exceed `max_file_bytes`
+	pub async fn start(dir: impl Into<PathBuf>, max_file_bytes: u64) -> anyhow::Result<Self> {
+		let dir = dir.into();
+		tokio::fs::create_dir_all(&dir).await.context(format!("Failed to create capture directory {}", dir.display()))?;
+
+		let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+		let mut writer = CaptureWriter::new(dir, max_file_bytes);
+		let dropped = Arc::new(AtomicU64::new(0));
+		let writer_dropped = Arc::clone(&dropped);
+
+		tokio::spawn(async move {
+			while let Some(frame) = rx.recv().await {
+				// Flush whenever the backlog drains, so captures survive a crash up to the last quiet moment
+				let caught_up = rx.is_empty();
+				if let Err(e) = writer.write(&frame, caught_up).await {
+					error!("Failed to write captured frame: {e:#}");
+				}
+
+				let dropped = if caught_up { writer_dropped.swap(0, Ordering::Relaxed) } else { 0 };
+				if dropped > 0 {
+					warn!("Capture writer fell behind, {dropped} frames were dropped");
+				}
+			}
+		});
+
+		Ok(Self { frames: tx, dropped })
+	}
+
+	/// Drops the frame while the writer is behind, so the websocket read loop is never held up
+	fn record(&self, url: &str, frame: &str) {
+		let frame = CapturedFrame { received_at: now_millis(), url: String::from(url), frame: String::from(frame) };
+		if self.frames.try_send(frame).is_err() {
+			self.dropped.fetch_add(1, Ordering::Relaxed);
+		}
+	}
+}
+
+/// Tee websocket frames and REST responses into `recorder`, including streams opened before this call.
+/// Only the first recorder is kept, returns `false` if one was already installed.
+pub fn record_frames(recorder: FrameRecorder) -> bool {
+	RECORDER.set(recorder).is_ok()
+}
+
+/// Hand a received text frame or response body to the installed recorder, if any
+pub fn record(url: &str, frame: &str) {
+	if let Some(recorder) = RECORDER.get() {
+		recorder.record(url, frame);
+	}
+}
+
+/// Capture files in `dir` in recording order
+pub fn capture_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+	let mut files = Vec::new();
+
+	for entry in std::fs::read_dir(dir).context(format!("Failed to read capture directory {}", dir.display()))? {
+		let path = entry?.path();
+		let is_capture = path.extension().is_some_and(|extension| extension == "jsonl")
+			&& path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("capture-"));
+		if is_capture {
+			files.push(path);
+		}
+	}
+
+	// Names start with the zero-padded time of their first frame
+	files.sort();
+	Ok(files)
+}
+
+struct CaptureWriter {
+	dir: PathBuf,
+	max_file_bytes: u64,
+	file: Option<BufWriter<File>>,
+	file_bytes: u64,
+	files_created: u32,
+}
+
+impl CaptureWriter {
+	const fn new(dir: PathBuf, max_file_bytes: u64) -> Self {
+		Self { dir, max_file_bytes, file: None, file_bytes: 0, files_created: 0 }
+	}
+
+	async fn write(&mut self, frame: &CapturedFrame, flush: bool) -> anyhow::Result<()> {
+		let mut line = serde_json::to_string(frame)?;
+		line.push('\n');
+		let line_bytes = line.len() as u64;
+
+		let file = match self.file.take() {
+			Some(file) if self.file_bytes + line_bytes <= self.max_file_bytes => file,
+			previous => {
+				if let Some(mut previous) = previous {
+					previous.flush().await?;
+				}
+				self.open(frame.received_at).await?
+			},
+		};
+		let file = self.file.insert(file);
+
+		file.write_all(line.as_bytes()).await?;
+		self.file_bytes += line_bytes;
+
+		if flush {
+			file.flush().await?;
+		}
+
+		Ok(())
+	}
+
+	async fn open(&mut self, received_at: u64) -> anyhow::Result<BufWriter<File>> {
+		let path = self.dir.join(format!("capture-{received_at:013}-{:04}.jsonl", self.files_created));
+		let file = File::create(&path).await.context(format!("Failed to create capture file {}", path.display()))?;
+
+		self.files_created += 1;
+		self.file_bytes = 0;
+
+		Ok(BufWriter::new(file))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn frame(received_at: u64, frame: &str) -> CapturedFrame {
+		CapturedFrame { received_at, url: String::from("wss://example.com/ws"), frame: String::from(frame) }
+	}
+
+	#[tokio::test]
+	async fn rotates_capture_files() {
+		let dir = std::env::temp_dir().join(format!("capture-test-{}", fastrand::u64(..)));
+		tokio::fs::create_dir_all(&dir).await.unwrap();
+		let line_bytes = serde_json::to_string(&frame(1, "a")).unwrap().len() as u64 + 1;
+
+		let mut writer = CaptureWriter::new(dir.clone(), line_bytes * 2);
+		for (received_at, text) in [(1, "a"), (2, "b"), (3, "c")] {
+			writer.write(&frame(received_at, text), true).await.unwrap();
+		}
+
+		let files = capture_files(&dir).unwrap();
+		let first = std::fs::read_to_string(&files[0]).unwrap();
+		let second = std::fs::read_to_string(&files[1]).unwrap();
+		std::fs::remove_dir_all(&dir).unwrap();
+
+		assert_eq!(files.len(), 2);
+		assert_eq!(first.lines().count(), 2);
+		assert_eq!(serde_json::from_str::<CapturedFrame>(second.trim()).unwrap(), frame(3, "c"));
+	}
+}
@@ -0,0 +1,85 @@
+use std::num::{ParseFloatError, ParseIntError};
+
+use reqwest::StatusCode;
+use tokio::time::Duration;
+
+/// Failure of an `Exchange` call, classified so callers can decide whether to retry
+#[derive(Debug, thiserror::Error)]
+pub enum ExchangeError {
+	/// Request rate exceeded, `retry_after` is set when the exchange says how long to back off
+	#[error("Rate limited by the exchange")]
+	RateLimited { retry_after: Option<Duration> },
+	#[error("Unknown symbol: {0}")]
+	UnknownSymbol(String),
+	/// Response didn't match the expected schema
+	#[error("Failed to decode response: {0:#}")]
+	Decode(anyhow::Error),
+	/// Connection failure, timeout or server error, usually transient
+	#[error("Network error: {0:#}")]
+	Network(anyhow::Error),
+	/// Not enough history for the requested statistic, e.g. a freshly listed symbol
+	#[error("Insufficient data: {0}")]
+	InsufficientData(String),
+	/// Interval or statistic the exchange doesn't offer
+	#[error("Unsupported: {0}")]
+	Unsupported(String),
+	#[error("{0:#}")]
+	Other(anyhow::Error),
+}
+
+impl ExchangeError {
+	/// The same call may succeed later
+	#[must_use]
+	pub const fn is_transient(&self) -> bool {
+		matches!(self, Self::RateLimited { .. } | Self::Network(_))
+	}
+}
+
+/// `ExchangeError`s raised inside `anyhow` contexts are unwrapped, anything else is classified by its causes
+impl From<anyhow::Error> for ExchangeError {
+	fn from(error: anyhow::Error) -> Self {
+		let error = match error.downcast::<Self>() {
+			Ok(error) => return error,
+			Err(error) => error,
+		};
+
+		for cause in error.chain() {
+			if let Some(request_error) = cause.downcast_ref::<reqwest::Error>() {
+				if request_error.status() == Some(StatusCode::TOO_MANY_REQUESTS) {
+					return Self::RateLimited { retry_after: None };
+				}
+				if request_error.is_decode() {
+					return Self::Decode(error);
+				}
+				return Self::Network(error);
+			}
+
+			if cause.is::<serde_json::Error>() || cause.is::<ParseFloatError>() || cause.is::<ParseIntError>() {
+				return Self::Decode(error);
+			}
+		}
+
+		Self::Other(error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use anyhow::Context;
+
+	use super::*;
+
+	#[test]
+	fn classifies_wrapped_errors() {
+		let typed: anyhow::Result<()> =
+			Err(ExchangeError::UnknownSymbol(String::from("FOOUSDT"))).context("Failed to fetch klines");
+		let decode = "1.5x".parse::<f64>().context("Failed to parse price").unwrap_err();
+
+		assert!(
+			matches!(ExchangeError::from(typed.unwrap_err()), ExchangeError::UnknownSymbol(symbol) if symbol == "FOOUSDT")
+		);
+		assert!(matches!(ExchangeError::from(decode), ExchangeError::Decode(_)));
+		assert!(matches!(ExchangeError::from(anyhow::anyhow!("Server timeout")), ExchangeError::Other(_)));
+		assert!(ExchangeError::RateLimited { retry_after: None }.is_transient());
+	}
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+
+use crate::SymbolInfo;
+
+/// Quote assets recognized at the end of concatenated symbols, `USD` last so it doesn't shadow the others
+const QUOTE_ASSETS: [&str; 5] = ["USDT", "USDC", "FDUSD", "BUSD", "USD"];
+
+/// Venue specific codes of the same asset
+const ASSET_ALIASES: &[(&str, &str)] = &[("XBT", "BTC"), ("LUNA2", "LUNA"), ("BEAMX", "BEAM")];
+
+/// Multiplied bases that don't follow the `1000PEPE` prefix form
+const MULTIPLIER_ALIASES: &[(&str, &str, u64)] = &[("SHIB1000", "SHIB", 1000)];
+
+/// Coin or currency code shared by every venue, e.g. `PEPE` for both `1000PEPEUSDT` and `PEPE-USDT-SWAP`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Asset(String);
+
+impl Asset {
+	/// Upper-cased and resolved through the alias table
+	#[must_use]
+	pub fn new(code: &str) -> Self {
+		let code = code.to_ascii_uppercase();
+
+		match ASSET_ALIASES.iter().find(|(alias, _)| *alias == code) {
+			Some((_, canonical)) => Self(String::from(*canonical)),
+			None => Self(code),
+		}
+	}
+
+	#[must_use]
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+impl fmt::Display for Asset {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+/// Market identity independent of the venue, equal for the same pair on every exchange
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InstrumentId {
+	pub base: Asset,
+	pub quote: Asset,
+}
+
+impl fmt::Display for InstrumentId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{}", self.base, self.quote)
+	}
+}
+
+/// Contract as one venue lists it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Instrument {
+	pub base: Asset,
+	pub quote: Asset,
+	/// Units of `base` per unit the venue quotes, e.g. 1000 for `1000PEPEUSDT`
+	pub multiplier: u64,
+	/// Symbol as the venue spells it
+	pub venue_symbol: String,
+}
+
+impl Instrument {
+	/// Parse `BTCUSDT`, `1000PEPEUSDT`, `1MBABYDOGEUSDT`, `BTC-USDT-SWAP` or `BTCUSDT_250328`,
+	/// `None` when no known quote asset is found
+	#[must_use]
+	pub fn parse(venue_symbol: &str) -> Option<Self> {
+		let symbol = venue_symbol.to_ascii_uppercase();
+		// Delivery date of Binance futures
+		let symbol = symbol.split('_').next()?;
+
+		let (base, quote) = if symbol.contains('-') {
+			let mut parts = symbol.split('-');
+			(parts.next()?, parts.next()?)
+		} else {
+			QUOTE_ASSETS
+				.iter()
+				.find_map(|quote| symbol.strip_suffix(quote).filter(|base| !base.is_empty()).map(|base| (base, *quote)))?
+		};
+
+		(!base.is_empty() && !quote.is_empty()).then(|| Self::from_assets(venue_symbol, base, quote))
+	}
+
+	/// Use the base and quote reported by the venue instead of parsing the symbol
+	#[must_use]
+	pub fn from_symbol_info(info: &SymbolInfo) -> Self {
+		Self::from_assets(&info.symbol, &info.base_asset, &info.quote_asset)
+	}
+
+	fn from_assets(venue_symbol: &str, base: &str, quote: &str) -> Self {
+		let (base, multiplier) = split_multiplier(&base.to_ascii_uppercase());
+
+		Self { base, quote: Asset::new(quote), multiplier, venue_symbol: String::from(venue_symbol) }
+	}
+
+	#[must_use]
+	pub fn id(&self) -> InstrumentId {
+		InstrumentId { base: self.base.clone(), quote: self.quote.clone() }
+	}
+}
+
+/// `InstrumentId` of `symbol` for deduplication, the symbol itself when it can't be parsed
+pub fn identity_key(symbol: &str) -> String {
+	Instrument::parse(symbol).map_or_else(|| String::from(symbol), |instrument| instrument.id().to_string())
+}
+
+/// Strip a `1000`, `10000`, ... or `1M` multiplier prefix, `1INCH` and other coins starting with a digit are kept
+fn split_multiplier(base: &str) -> (Asset, u64) {
+	if let Some((_, canonical, multiplier)) = MULTIPLIER_ALIASES.iter().find(|(alias, ..)| *alias == base) {
+		return (Asset::new(canonical), *multiplier);
+	}
+
+	let starts_with_letter = |code: &str| code.starts_with(|c: char| c.is_ascii_alphabetic());
+
+	if let Some(rest) = base.strip_prefix("1M").filter(|rest| starts_with_letter(rest)) {
+		return (Asset::new(rest), 1_000_000);
+	}
+
+	let digits = base.len() - base.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+	let (prefix, rest) = base.split_at(digits);
+	let is_power_of_ten = prefix.len() >= 3 && prefix.starts_with('1') && prefix[1..].chars().all(|c| c == '0');
+
+	match prefix.parse() {
+		Ok(multiplier) if is_power_of_ten && starts_with_letter(rest) => (Asset::new(rest), multiplier),
+		_ => (Asset::new(base), 1),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_venue_symbols() {
+		let pepe = Instrument::parse("1000PEPEUSDT").unwrap();
+		let babydoge = Instrument::parse("1MBABYDOGEUSDT").unwrap();
+		let okx = Instrument::parse("BTC-USDT-SWAP").unwrap();
+		let delivery = Instrument::parse("BTCUSDC_250328").unwrap();
+
+		assert_eq!((pepe.base.as_str(), pepe.quote.as_str(), pepe.multiplier), ("PEPE", "USDT", 1000));
+		assert_eq!((babydoge.base.as_str(), babydoge.multiplier), ("BABYDOGE", 1_000_000));
+		assert_eq!(okx.id().to_string(), "BTC/USDT");
+		assert_eq!(delivery.id().to_string(), "BTC/USDC");
+		assert_eq!(Instrument::parse("1INCHUSDT").unwrap().base.as_str(), "1INCH");
+		assert_eq!(Instrument::parse("USDCUSDT").unwrap().base.as_str(), "USDC");
+		assert!(Instrument::parse("USDT").is_none());
+	}
+
+	#[test]
+	fn resolves_aliases() {
+		let bybit_shib = Instrument::parse("SHIB1000USDT").unwrap();
+		let binance_shib = Instrument::parse("1000SHIBUSDT").unwrap();
+
+		assert_eq!(bybit_shib.id(), binance_shib.id());
+		assert_eq!(bybit_shib.multiplier, 1000);
+		assert_eq!(Instrument::parse("LUNA2USDT").unwrap().id(), Instrument::parse("LUNA-USDT-SWAP").unwrap().id());
+		assert_ne!(bybit_shib.venue_symbol, binance_shib.venue_symbol);
+	}
+}
@@ -0,0 +1,148 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Kline interval, serialized in Binance notation (`1m`, `4h`, `1d`, `1M`, ...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Interval {
+	#[serde(rename = "1m")]
+	Minute1,
+	#[serde(rename = "3m")]
+	Minute3,
+	#[serde(rename = "5m")]
+	Minute5,
+	#[serde(rename = "15m")]
+	Minute15,
+	#[serde(rename = "30m")]
+	Minute30,
+	#[serde(rename = "1h")]
+	Hour1,
+	#[serde(rename = "2h")]
+	Hour2,
+	#[serde(rename = "4h")]
+	Hour4,
+	#[serde(rename = "6h")]
+	Hour6,
+	#[serde(rename = "8h")]
+	Hour8,
+	#[serde(rename = "12h")]
+	Hour12,
+	#[serde(rename = "1d")]
+	Day1,
+	#[serde(rename = "3d")]
+	Day3,
+	#[serde(rename = "1w")]
+	Week1,
+	#[serde(rename = "1M")]
+	Month1,
+}
+
+impl Interval {
+	pub const ALL: [Self; 15] = [
+		Self::Minute1,
+		Self::Minute3,
+		Self::Minute5,
+		Self::Minute15,
+		Self::Minute30,
+		Self::Hour1,
+		Self::Hour2,
+		Self::Hour4,
+		Self::Hour6,
+		Self::Hour8,
+		Self::Hour12,
+		Self::Day1,
+		Self::Day3,
+		Self::Week1,
+		Self::Month1,
+	];
+
+	#[must_use]
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Minute1 => "1m",
+			Self::Minute3 => "3m",
+			Self::Minute5 => "5m",
+			Self::Minute15 => "15m",
+			Self::Minute30 => "30m",
+			Self::Hour1 => "1h",
+			Self::Hour2 => "2h",
+			Self::Hour4 => "4h",
+			Self::Hour6 => "6h",
+			Self::Hour8 => "8h",
+			Self::Hour12 => "12h",
+			Self::Day1 => "1d",
+			Self::Day3 => "3d",
+			Self::Week1 => "1w",
+			Self::Month1 => "1M",
+		}
+	}
+
+	/// Length of one candle, a month is approximated as 30 days
+	#[must_use]
+	pub const fn duration(self) -> Duration {
+		match self {
+			Self::Minute1 => Duration::from_mins(1),
+			Self::Minute3 => Duration::from_mins(3),
+			Self::Minute5 => Duration::from_mins(5),
+			Self::Minute15 => Duration::from_mins(15),
+			Self::Minute30 => Duration::from_mins(30),
+			Self::Hour1 => Duration::from_hours(1),
+			Self::Hour2 => Duration::from_hours(2),
+			Self::Hour4 => Duration::from_hours(4),
+			Self::Hour6 => Duration::from_hours(6),
+			Self::Hour8 => Duration::from_hours(8),
+			Self::Hour12 => Duration::from_hours(12),
+			Self::Day1 => Duration::from_hours(24),
+			Self::Day3 => Duration::from_hours(3 * 24),
+			Self::Week1 => Duration::from_hours(7 * 24),
+			Self::Month1 => Duration::from_hours(30 * 24),
+		}
+	}
+
+	#[must_use]
+	pub const fn as_millis(self) -> u64 {
+		self.duration().as_millis() as u64
+	}
+}
+
+impl fmt::Display for Interval {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for Interval {
+	type Err = anyhow::Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.into_iter()
+			.find(|interval| interval.as_str() == value)
+			.ok_or_else(|| anyhow::anyhow!("Unsupported interval: {value}"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_and_formats() {
+		for interval in Interval::ALL {
+			assert_eq!(interval.to_string().parse::<Interval>().unwrap(), interval);
+		}
+		assert!("1H".parse::<Interval>().is_err());
+	}
+
+	#[test]
+	fn serializes_in_binance_notation() {
+		assert_eq!(serde_json::to_string(&Interval::Hour4).unwrap(), r#""4h""#);
+		assert_eq!(serde_json::from_str::<Interval>(r#""1M""#).unwrap(), Interval::Month1);
+	}
+
+	#[test]
+	fn durations() {
+		assert_eq!(Interval::Minute15.as_millis(), 15 * 60 * 1000);
+		assert_eq!(Interval::Week1.duration(), Interval::Day1.duration() * 7);
+	}
+}
@@ -1,35 +1,383 @@
-pub use binance::BinanceExchange;
+pub use aggregated::{AggregatedExchange, VenueResults};
+pub use basis::{MarketLead, SpotPerpPair, market_lead};
+pub use binance::{BinanceExchange, BinanceExchangeBuilder, BinanceMarket, WeightUsage};
+pub use bybit::BybitExchange;
+pub use capture::{CapturedFrame, DEFAULT_CAPTURE_FILE_BYTES, FrameRecorder, capture_files, record_frames};
+pub use error::ExchangeError;
+pub use instrument::{Asset, Instrument, InstrumentId};
+pub use interval::Interval;
+pub use liquidation_cluster::{LiquidationCluster, LiquidationClusterTracker};
+pub use listings::{ListingEvent, ListingTracker, watch_listings};
+pub use okx::OkxExchange;
+pub use open_interest::{OpenInterestPoint, open_interest_change};
+pub use order_book::{DepthUpdate, LocalOrderBook, OrderBookDepth, OrderBookLevel};
+pub use replay::ReplayExchange;
+pub use stream::StreamEvent;
+pub use symbol_info::{ContractKind, SYMBOLS_TTL, SymbolInfo, SymbolStatus};
+pub use volume_delta::{VolumeDelta, VolumeDeltaTracker};
 
+mod aggregated;
+mod basis;
 mod binance;
+mod bybit;
+mod capture;
+mod error;
+mod instrument;
+mod interval;
+mod liquidation_cluster;
+mod listings;
+mod okx;
+mod open_interest;
+mod order_book;
+mod replay;
+mod stream;
+mod symbol_info;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+mod utils;
+mod volume_delta;
+
+use std::sync::Arc;
+
+use futures_util::stream::BoxStream;
+
+/// Settled funding rates `FundingRateInfo` averages are computed over
+pub const FUNDING_HISTORY_LIMIT: u32 = 100;
+
+/// Supported exchanges, used to pick an `Exchange` implementation from config
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeKind {
+	#[default]
+	Binance,
+	Bybit,
+	Okx,
+}
+
+impl ExchangeKind {
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Binance => "Binance",
+			Self::Bybit => "Bybit",
+			Self::Okx => "OKX",
+		}
+	}
+}
+
+impl std::str::FromStr for ExchangeKind {
+	type Err = anyhow::Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value.to_lowercase().as_str() {
+			"binance" => Ok(Self::Binance),
+			"bybit" => Ok(Self::Bybit),
+			"okx" => Ok(Self::Okx),
+			_ => Err(anyhow::anyhow!("Unsupported exchange: {value}. Use binance, bybit or okx.")),
+		}
+	}
+}
 
 #[async_trait::async_trait]
 pub trait Exchange {
-	async fn get_all_usdt_pairs(&self) -> anyhow::Result<Vec<String>>;
-	async fn watch_market_liquidations<F>(&self, callback: F) -> anyhow::Result<()>
-	where
-		F: FnMut(MarketLiquidationsInfo) + Send;
-	async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> anyhow::Result<Vec<CandleInfo>>;
-	async fn get_open_interest_info(&self, symbol: &str) -> anyhow::Result<OpenInterestInfo>;
-	async fn get_funding_rate_info(&self, symbol: &str) -> anyhow::Result<FundingRateInfo>;
+	/// Symbols of USDT-margined contracts open for trading
+	async fn get_all_usdt_pairs(&self) -> Result<Vec<String>, ExchangeError>;
+	/// Metadata of every USDT-margined contract regardless of status, cached for `SYMBOLS_TTL`
+	async fn get_symbols(&self) -> Result<Vec<SymbolInfo>, ExchangeError>;
+	/// Metadata of `symbol` from the `get_symbols` cache
+	async fn get_symbol_info(&self, symbol: &str) -> Result<SymbolInfo, ExchangeError> {
+		self
+			.get_symbols()
+			.await?
+			.into_iter()
+			.find(|info| info.symbol == symbol)
+			.ok_or_else(|| ExchangeError::UnknownSymbol(String::from(symbol)))
+	}
+	/// Liquidations across all USDT pairs, the connection is kept alive and re-established in the background.
+	/// Gaps caused by reconnects are reported as `StreamEvent::Reconnected`.
+	fn watch_market_liquidations(&self) -> BoxStream<'static, StreamEvent<MarketLiquidationsInfo>>;
+	/// Rolling 24h statistics of all USDT pairs, pushed as they change (about once a second)
+	fn watch_tickers(&self) -> BoxStream<'static, StreamEvent<TickerInfo>>;
+	/// Mark price and current funding of all USDT perpetuals, pushed every few seconds
+	fn watch_mark_prices(&self) -> BoxStream<'static, StreamEvent<MarkPriceInfo>>;
+	/// Trades of `symbol` as they happen, fills of one taker order at the same price are aggregated where supported
+	fn watch_trades(&self, symbol: &str) -> BoxStream<'static, StreamEvent<TradeInfo>>;
+	async fn get_klines(&self, symbol: &str, interval: Interval, limit: u32) -> Result<Vec<CandleInfo>, ExchangeError>;
+	/// Candles opened within `from..=to` (milliseconds since epoch), fetched page by page past the per-request limit.
+	/// Ordered oldest → newest without duplicates.
+	async fn get_klines_range(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<CandleInfo>, ExchangeError>;
+	async fn get_open_interest_info(&self, symbol: &str) -> Result<OpenInterestInfo, ExchangeError>;
+	/// Open interest sampled every `period`, ordered oldest → newest.
+	/// `limit` is capped per request by the exchange (500 on Binance, 200 on Bybit, 100 on OKX).
+	async fn get_open_interest_history(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<OpenInterestPoint>, ExchangeError>;
+	/// Order book snapshot with about `limit` levels per side, exchanges only serve certain depths
+	async fn get_order_book(&self, symbol: &str, limit: u32) -> Result<LocalOrderBook, ExchangeError>;
+	/// Current funding compared against the last `FUNDING_HISTORY_LIMIT` settlements
+	async fn get_funding_rate_info(&self, symbol: &str) -> Result<FundingRateInfo, ExchangeError>;
+	/// Long/short ratio history sampled every `period`, ordered oldest → newest
+	async fn get_long_short_ratio(
+		&self,
+		symbol: &str,
+		kind: LongShortRatioKind,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<LongShortRatio>, ExchangeError>;
+	/// Taker buy/sell volume per `period`, ordered oldest → newest. Not reported by Bybit.
+	async fn get_taker_volume(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<TakerVolume>, ExchangeError>;
 }
 
-#[derive(Debug)]
+/// Shared clients, e.g. the venues of an `AggregatedExchange`, are exchanges themselves
+#[async_trait::async_trait]
+impl<E> Exchange for Arc<E>
+where
+	E: Exchange + Send + Sync + ?Sized,
+{
+	async fn get_all_usdt_pairs(&self) -> Result<Vec<String>, ExchangeError> {
+		(**self).get_all_usdt_pairs().await
+	}
+
+	async fn get_symbols(&self) -> Result<Vec<SymbolInfo>, ExchangeError> {
+		(**self).get_symbols().await
+	}
+
+	async fn get_symbol_info(&self, symbol: &str) -> Result<SymbolInfo, ExchangeError> {
+		(**self).get_symbol_info(symbol).await
+	}
+
+	fn watch_market_liquidations(&self) -> BoxStream<'static, StreamEvent<MarketLiquidationsInfo>> {
+		(**self).watch_market_liquidations()
+	}
+
+	fn watch_tickers(&self) -> BoxStream<'static, StreamEvent<TickerInfo>> {
+		(**self).watch_tickers()
+	}
+
+	fn watch_mark_prices(&self) -> BoxStream<'static, StreamEvent<MarkPriceInfo>> {
+		(**self).watch_mark_prices()
+	}
+
+	fn watch_trades(&self, symbol: &str) -> BoxStream<'static, StreamEvent<TradeInfo>> {
+		(**self).watch_trades(symbol)
+	}
+
+	async fn get_klines(&self, symbol: &str, interval: Interval, limit: u32) -> Result<Vec<CandleInfo>, ExchangeError> {
+		(**self).get_klines(symbol, interval, limit).await
+	}
+
+	async fn get_klines_range(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<CandleInfo>, ExchangeError> {
+		(**self).get_klines_range(symbol, interval, from, to).await
+	}
+
+	async fn get_open_interest_info(&self, symbol: &str) -> Result<OpenInterestInfo, ExchangeError> {
+		(**self).get_open_interest_info(symbol).await
+	}
+
+	async fn get_open_interest_history(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<OpenInterestPoint>, ExchangeError> {
+		(**self).get_open_interest_history(symbol, period, limit).await
+	}
+
+	async fn get_order_book(&self, symbol: &str, limit: u32) -> Result<LocalOrderBook, ExchangeError> {
+		(**self).get_order_book(symbol, limit).await
+	}
+
+	async fn get_funding_rate_info(&self, symbol: &str) -> Result<FundingRateInfo, ExchangeError> {
+		(**self).get_funding_rate_info(symbol).await
+	}
+
+	async fn get_long_short_ratio(
+		&self,
+		symbol: &str,
+		kind: LongShortRatioKind,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<LongShortRatio>, ExchangeError> {
+		(**self).get_long_short_ratio(symbol, kind, period, limit).await
+	}
+
+	async fn get_taker_volume(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<TakerVolume>, ExchangeError> {
+		(**self).get_taker_volume(symbol, period, limit).await
+	}
+}
+
+#[derive(Debug, Clone)]
 pub struct MarketLiquidationsInfo {
 	pub symbol: String,
-	pub side: String,
+	/// Venue the liquidation happened on
+	pub exchange: ExchangeKind,
+	/// Side of the liquidation order, see `liquidated_position`
+	pub side: Side,
+	/// Reported by Binance only (e.g. `LIMIT`)
+	pub order_type: Option<String>,
+	/// Order price, bankruptcy price on Bybit and OKX
 	pub symbol_price: f64,
+	/// Average fill price, same as `symbol_price` when the exchange doesn't report fills
+	pub average_price: f64,
 	pub usd_price: f64,
+	/// Order quantity in base asset
 	pub quantity: f64,
+	/// Accumulated filled quantity, same as `quantity` when the exchange doesn't report fills
+	pub filled_quantity: f64,
+	/// Event time, milliseconds since epoch
 	pub time: u64,
+	/// Trade time, milliseconds since epoch
+	pub trade_time: u64,
 }
 
-#[derive(Debug)]
+impl MarketLiquidationsInfo {
+	#[must_use]
+	pub const fn liquidated_position(&self) -> LiquidatedPosition {
+		self.side.liquidated_position()
+	}
+}
+
+/// Order side, accepts `BUY`, `Buy` and `buy` spellings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Side {
+	#[serde(alias = "Buy", alias = "buy")]
+	Buy,
+	#[serde(alias = "Sell", alias = "sell")]
+	Sell,
+}
+
+impl Side {
+	/// A forced `Sell` closes a long position, a forced `Buy` closes a short one
+	#[must_use]
+	pub const fn liquidated_position(self) -> LiquidatedPosition {
+		match self {
+			Self::Buy => LiquidatedPosition::Short,
+			Self::Sell => LiquidatedPosition::Long,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LiquidatedPosition {
+	Long,
+	Short,
+}
+
+impl LiquidatedPosition {
+	/// Side of the order that force-closes the position
+	#[must_use]
+	pub const fn closing_side(self) -> Side {
+		match self {
+			Self::Long => Side::Sell,
+			Self::Short => Side::Buy,
+		}
+	}
+}
+
+/// Rolling 24h statistics of a symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickerInfo {
+	pub symbol: String,
+	pub last_price: f64,
+	/// Price 24 hours ago
+	pub open_price: f64,
+	pub high_price: f64,
+	pub low_price: f64,
+	/// e.g. `2.5` for +2.5%
+	pub price_change_percent: f64,
+	/// Base asset volume
+	pub volume: f64,
+	/// Estimated from base volume and last price on OKX
+	pub quote_volume: f64,
+	/// Reported by Binance only
+	pub trades: Option<u64>,
+	/// Event time, milliseconds since epoch
+	pub time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkPriceInfo {
+	pub symbol: String,
+	pub mark_price: f64,
+	pub index_price: f64,
+	/// Funding rate of the current period, e.g. `0.0001` for 0.01%
+	pub funding_rate: f64,
+	/// Next funding settlement, milliseconds since epoch
+	pub next_funding_time: u64,
+	/// Event time, milliseconds since epoch
+	pub time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeInfo {
+	pub symbol: String,
+	pub price: f64,
+	/// Base asset quantity
+	pub quantity: f64,
+	/// Side of the aggressing order, `Buy` lifts asks and `Sell` hits bids
+	pub taker_side: Side,
+	/// Trade time, milliseconds since epoch
+	pub time: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct CandleInfo {
+	/// Milliseconds since epoch
+	pub open_time: u64,
+	/// Milliseconds since epoch, last millisecond covered by the candle
+	pub close_time: u64,
 	pub open: f64,
 	pub high: f64,
 	pub low: f64,
 	pub close: f64,
+	/// Base asset volume
 	pub volume: f64,
+	pub quote_volume: f64,
+	/// Not every exchange reports trade count and taker volumes
+	pub trades: Option<u64>,
+	pub taker_buy_volume: Option<f64>,
+	pub taker_buy_quote_volume: Option<f64>,
+}
+
+impl CandleInfo {
+	/// Whether the candle is final at `timestamp` (milliseconds since epoch)
+	#[must_use]
+	pub const fn is_closed_at(&self, timestamp: u64) -> bool {
+		timestamp > self.close_time
+	}
+
+	/// Whether the candle is final now, the latest candle returned by exchanges is usually still open
+	#[must_use]
+	pub fn is_closed(&self) -> bool {
+		self.is_closed_at(utils::now_millis())
+	}
 }
 
 #[derive(Debug)]
@@ -43,8 +391,100 @@ pub struct OpenInterestInfo {
 	pub percent_change_30_days: f64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FundingRateInfo {
-	pub funding_rate: String,
-	pub average_funding_rate: String,
+	/// Rate of the current period, e.g. `0.0001` for 0.01%
+	pub funding_rate: f64,
+	/// Mean of the settled rates in the history window
+	pub average_funding_rate: f64,
+	/// Share of settled rates in the history window at or below `funding_rate`, `0.0..=1.0`
+	pub percentile: f64,
+	/// Number of settled rates the average and percentile are computed over
+	pub history_len: usize,
+	/// Hours between settlements, 4 or 8 on most pairs
+	pub funding_interval_hours: u32,
+	/// Milliseconds since epoch
+	pub next_funding_time: u64,
+}
+
+impl FundingRateInfo {
+	/// Current rate extrapolated to a year of settlements, e.g. `0.1095` for 10.95%
+	#[must_use]
+	pub fn annualized_rate(&self) -> f64 {
+		self.funding_rate * 24.0 / f64::from(self.funding_interval_hours.max(1)) * 365.0
+	}
+}
+
+/// Which traders a long/short ratio is computed over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LongShortRatioKind {
+	/// Accounts with open positions
+	GlobalAccount,
+	/// Top 20% of accounts by margin balance
+	TopTraderAccount,
+	/// Positions of top accounts weighted by size
+	TopTraderPosition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LongShortRatio {
+	pub long_short_ratio: f64,
+	/// Share of longs, `0.0..=1.0`
+	pub long_share: f64,
+	/// Share of shorts, `0.0..=1.0`
+	pub short_share: f64,
+	/// Milliseconds since epoch
+	pub timestamp: u64,
+}
+
+impl LongShortRatio {
+	/// For exchanges that report the ratio only
+	#[must_use]
+	pub fn from_ratio(long_short_ratio: f64, timestamp: u64) -> Self {
+		let long_share = long_short_ratio / (1.0 + long_short_ratio);
+		Self { long_short_ratio, long_share, short_share: 1.0 - long_share, timestamp }
+	}
+}
+
+/// Volume of market orders over one statistics period
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TakerVolume {
+	/// Base asset bought by takers
+	pub buy_volume: f64,
+	/// Base asset sold by takers
+	pub sell_volume: f64,
+	/// Milliseconds since epoch
+	pub timestamp: u64,
+}
+
+impl TakerVolume {
+	/// Sum of `series`, stamped with its first timestamp. `None` when empty.
+	#[must_use]
+	pub fn combined(series: &[Self]) -> Option<Self> {
+		let first = series.first()?;
+
+		Some(series.iter().fold(Self { timestamp: first.timestamp, ..Self::default() }, |total, volume| Self {
+			buy_volume: total.buy_volume + volume.buy_volume,
+			sell_volume: total.sell_volume + volume.sell_volume,
+			timestamp: total.timestamp,
+		}))
+	}
+
+	/// Buy volume over sell volume, `None` when takers sold nothing
+	#[must_use]
+	pub fn buy_sell_ratio(&self) -> Option<f64> {
+		(self.sell_volume > 0.0).then(|| self.buy_volume / self.sell_volume)
+	}
+
+	/// Buy/sell imbalance in `-1.0..=1.0`, `1.0` when every taker bought
+	#[must_use]
+	pub fn imbalance(&self) -> f64 {
+		let total = self.buy_volume + self.sell_volume;
+		if total == 0.0 {
+			return 0.0;
+		}
+
+		(self.buy_volume - self.sell_volume) / total
+	}
 }
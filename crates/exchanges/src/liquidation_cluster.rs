@@ -0,0 +1,140 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{ExchangeKind, LiquidatedPosition, MarketLiquidationsInfo, instrument::identity_key};
+
+/// Liquidations of one symbol and position side, possibly spread over several venues
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidationCluster {
+	pub symbol: String,
+	pub position: LiquidatedPosition,
+	/// Sum of `usd_price`
+	pub usd_value: f64,
+	pub liquidations: usize,
+	/// Venues in order of their first liquidation
+	pub venues: Vec<ExchangeKind>,
+	/// Milliseconds between the first and the last liquidation
+	pub span_millis: u64,
+}
+
+/// Rolling liquidation totals per instrument and liquidated side over the last `window_millis`, measured in event time.
+/// Symbols are matched by `InstrumentId`, so `1000SHIBUSDT` and `SHIB1000USDT` add up.
+#[derive(Debug)]
+pub struct LiquidationClusterTracker {
+	window_millis: u64,
+	liquidations: HashMap<(String, LiquidatedPosition), VecDeque<MarketLiquidationsInfo>>,
+}
+
+impl LiquidationClusterTracker {
+	#[must_use]
+	pub fn new(window_millis: u64) -> Self {
+		Self { window_millis, liquidations: HashMap::new() }
+	}
+
+	/// Add a liquidation and drop every liquidation that fell out of the window at its time
+	pub fn push(&mut self, liquidation: MarketLiquidationsInfo) {
+		let key = (identity_key(&liquidation.symbol), liquidation.liquidated_position());
+		let cutoff = liquidation.time.saturating_sub(self.window_millis);
+
+		self.liquidations.entry(key).or_default().push_back(liquidation);
+		self.liquidations.retain(|_, liquidations| {
+			while liquidations.front().is_some_and(|oldest| oldest.time <= cutoff) {
+				liquidations.pop_front();
+			}
+			!liquidations.is_empty()
+		});
+	}
+
+	/// Liquidations of `symbol` closing `position` within the window ending at `now`, `None` when there are none
+	pub fn cluster(&mut self, symbol: &str, position: LiquidatedPosition, now: u64) -> Option<LiquidationCluster> {
+		let key = (identity_key(symbol), position);
+		let cutoff = now.saturating_sub(self.window_millis);
+		let liquidations = self.liquidations.get_mut(&key)?;
+
+		while liquidations.front().is_some_and(|oldest| oldest.time <= cutoff) {
+			liquidations.pop_front();
+		}
+		if liquidations.is_empty() {
+			self.liquidations.remove(&key);
+			return None;
+		}
+
+		let liquidations = &self.liquidations[&key];
+		let first_time = liquidations.iter().map(|liquidation| liquidation.time).min()?;
+		let last_time = liquidations.iter().map(|liquidation| liquidation.time).max()?;
+
+		let mut venues = Vec::new();
+		for liquidation in liquidations {
+			if !venues.contains(&liquidation.exchange) {
+				venues.push(liquidation.exchange);
+			}
+		}
+
+		Some(LiquidationCluster {
+			symbol: String::from(symbol),
+			position,
+			usd_value: liquidations.iter().map(|liquidation| liquidation.usd_price).sum(),
+			liquidations: liquidations.len(),
+			venues,
+			span_millis: last_time - first_time,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Side;
+
+	fn liquidation(exchange: ExchangeKind, side: Side, usd_price: f64, time: u64) -> MarketLiquidationsInfo {
+		MarketLiquidationsInfo {
+			symbol: String::from("BTCUSDT"),
+			exchange,
+			side,
+			order_type: None,
+			symbol_price: 100.0,
+			average_price: 100.0,
+			usd_price,
+			quantity: usd_price / 100.0,
+			filled_quantity: usd_price / 100.0,
+			time,
+			trade_time: time,
+		}
+	}
+
+	#[test]
+	fn sums_liquidations_across_venues() {
+		let mut tracker = LiquidationClusterTracker::new(30_000);
+		tracker.push(liquidation(ExchangeKind::Binance, Side::Sell, 500_000.0, 0));
+		tracker.push(liquidation(ExchangeKind::Bybit, Side::Sell, 1_000_000.0, 10_000));
+		tracker.push(liquidation(ExchangeKind::Bybit, Side::Buy, 50_000.0, 12_000));
+		// Pushes the first long liquidation out of the window
+		tracker.push(liquidation(ExchangeKind::Binance, Side::Sell, 600_000.0, 30_000));
+
+		let longs = tracker.cluster("BTCUSDT", LiquidatedPosition::Long, 30_000).unwrap();
+
+		assert!((longs.usd_value - 1_600_000.0).abs() < f64::EPSILON);
+		assert_eq!(longs.liquidations, 2);
+		assert_eq!(longs.venues, vec![ExchangeKind::Bybit, ExchangeKind::Binance]);
+		assert_eq!(longs.span_millis, 20_000);
+		assert_eq!(tracker.cluster("BTCUSDT", LiquidatedPosition::Short, 30_000).unwrap().liquidations, 1);
+		assert!(tracker.cluster("ETHUSDT", LiquidatedPosition::Long, 30_000).is_none());
+	}
+
+	#[test]
+	fn evicts_stale_symbols() {
+		let mut tracker = LiquidationClusterTracker::new(30_000);
+		tracker.push(liquidation(ExchangeKind::Binance, Side::Buy, 50_000.0, 5_000));
+		tracker.push(liquidation(ExchangeKind::Binance, Side::Sell, 500_000.0, 20_000));
+
+		// The short liquidation is stale by now even though nothing was pushed for its side since
+		assert!(tracker.cluster("BTCUSDT", LiquidatedPosition::Short, 40_000).is_none());
+		assert_eq!(tracker.liquidations.len(), 1);
+
+		// Any later push drops whole keys that fell out of the window
+		let mut eth = liquidation(ExchangeKind::Bybit, Side::Sell, 10_000.0, 60_000);
+		eth.symbol = String::from("ETHUSDT");
+		tracker.push(eth);
+		assert_eq!(tracker.liquidations.len(), 1);
+		assert!(tracker.cluster("BTCUSDT", LiquidatedPosition::Long, 60_000).is_none());
+	}
+}
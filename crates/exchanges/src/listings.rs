@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use futures_util::{StreamExt, stream::BoxStream};
+use tokio::time::{Duration, MissedTickBehavior};
+use tracing::error;
+
+use crate::{Exchange, SymbolInfo, SymbolStatus};
+
+/// Change of the contract universe between two `Exchange::get_symbols` snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListingEvent {
+	/// Symbol seen for the first time, possibly not trading yet
+	Listed(SymbolInfo),
+	StatusChanged {
+		previous: SymbolStatus,
+		info: SymbolInfo,
+	},
+	/// Symbol no longer reported by the exchange, `info` is the last known state
+	Removed(SymbolInfo),
+}
+
+impl ListingEvent {
+	#[must_use]
+	pub const fn symbol_info(&self) -> &SymbolInfo {
+		match self {
+			Self::Listed(info) | Self::StatusChanged { info, .. } | Self::Removed(info) => info,
+		}
+	}
+}
+
+/// Diffs symbol snapshots against the previous one
+#[derive(Debug, Default)]
+pub struct ListingTracker {
+	symbols: Option<HashMap<String, SymbolInfo>>,
+}
+
+impl ListingTracker {
+	/// Changes since the previous call, the first call only records the baseline.
+	/// Listings and status changes follow the order of `symbols`, removals are sorted by symbol.
+	pub fn update(&mut self, symbols: Vec<SymbolInfo>) -> Vec<ListingEvent> {
+		let current: HashMap<String, SymbolInfo> = symbols.iter().map(|info| (info.symbol.clone(), info.clone())).collect();
+
+		let Some(mut previous) = self.symbols.replace(current) else {
+			return Vec::new();
+		};
+
+		let mut events = Vec::new();
+
+		for info in symbols {
+			match previous.remove(&info.symbol) {
+				None => events.push(ListingEvent::Listed(info)),
+				Some(known) if known.status != info.status => {
+					events.push(ListingEvent::StatusChanged { previous: known.status, info });
+				},
+				Some(_) => {},
+			}
+		}
+
+		let mut removed: Vec<_> = previous.into_values().collect();
+		removed.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+		events.extend(removed.into_iter().map(ListingEvent::Removed));
+
+		events
+	}
+}
+
+/// Poll `get_symbols` every `poll_every` and yield listing changes. Polling faster than `SYMBOLS_TTL` only hits the cache.
+/// Failed polls are logged and retried on the next tick.
+pub fn watch_listings<E>(exchange: E, poll_every: Duration) -> BoxStream<'static, ListingEvent>
+where
+	E: Exchange + Send + Sync + 'static,
+{
+	let mut interval = tokio::time::interval(poll_every);
+	interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+	futures_util::stream::unfold(
+		(exchange, ListingTracker::default(), interval),
+		|(exchange, mut tracker, mut interval)| async move {
+			interval.tick().await;
+
+			let events = match exchange.get_symbols().await {
+				Ok(symbols) => tracker.update(symbols),
+				Err(e) => {
+					error!("Failed to poll listings: {e:#}");
+					Vec::new()
+				},
+			};
+
+			Some((events, (exchange, tracker, interval)))
+		},
+	)
+	.flat_map(futures_util::stream::iter)
+	.boxed()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ContractKind;
+
+	fn symbol(symbol: &str, status: SymbolStatus) -> SymbolInfo {
+		SymbolInfo {
+			symbol: String::from(symbol),
+			base_asset: String::from(symbol.trim_end_matches("USDT")),
+			quote_asset: String::from("USDT"),
+			contract_kind: ContractKind::Perpetual,
+			onboard_date: 0,
+			tick_size: 0.1,
+			step_size: 0.001,
+			min_notional: None,
+			contract_size: None,
+			status,
+		}
+	}
+
+	#[test]
+	fn diffs_symbol_snapshots() {
+		let mut tracker = ListingTracker::default();
+
+		let baseline = tracker.update(vec![
+			symbol("BTCUSDT", SymbolStatus::Trading),
+			symbol("OLDUSDT", SymbolStatus::Trading),
+			symbol("GONEUSDT", SymbolStatus::Settling),
+		]);
+		let events = tracker.update(vec![
+			symbol("BTCUSDT", SymbolStatus::Trading),
+			symbol("OLDUSDT", SymbolStatus::Settling),
+			symbol("NEWUSDT", SymbolStatus::PreTrading),
+		]);
+
+		assert!(baseline.is_empty());
+		assert_eq!(
+			events,
+			vec![
+				ListingEvent::StatusChanged {
+					previous: SymbolStatus::Trading,
+					info: symbol("OLDUSDT", SymbolStatus::Settling)
+				},
+				ListingEvent::Listed(symbol("NEWUSDT", SymbolStatus::PreTrading)),
+				ListingEvent::Removed(symbol("GONEUSDT", SymbolStatus::Settling)),
+			]
+		);
+		assert_eq!(events[2].symbol_info().symbol, "GONEUSDT");
+		assert_eq!(tracker.update(vec![symbol("BTCUSDT", SymbolStatus::Trading)]).len(), 2);
+	}
+}
@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Side;
+
+// https://www.okx.com/docs-v5/en/#overview-rest-authentication-making-requests
+#[derive(Debug, Deserialize)]
+pub struct ApiResponse<T> {
+	pub code: String,
+	pub msg: String,
+	pub data: T,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum InstrumentType {
+	#[default]
+	Swap,
+}
+
+// https://www.okx.com/docs-v5/en/#public-data-rest-api-get-instruments
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentsRequestParams {
+	pub inst_type: InstrumentType,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentInfo {
+	/// e.g. `BTC-USDT-SWAP`
+	pub inst_id: String,
+	pub settle_ccy: String,
+	/// Contract value, amount of base currency per contract
+	pub ct_val: String,
+	pub ct_type: ContractType,
+	pub state: InstrumentState,
+	/// Milliseconds since epoch
+	pub list_time: String,
+	pub tick_sz: String,
+	/// Order size increment in contracts
+	pub lot_sz: String,
+}
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractType {
+	Linear,
+	Inverse,
+	#[serde(other)]
+	Unknown,
+}
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InstrumentState {
+	Live,
+	Suspend,
+	Preopen,
+	Test,
+	#[serde(other)]
+	Unknown,
+}
+
+// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-get-order-book
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBookRequestParams {
+	pub inst_id: String,
+	/// default 1, max 400
+	pub sz: Option<u32>,
+}
+#[derive(Debug, Deserialize)]
+pub struct OrderBookItem {
+	/// `[price, contracts, deprecated, order count]`, best first
+	pub asks: Vec<(String, String, String, String)>,
+	pub bids: Vec<(String, String, String, String)>,
+	pub ts: String,
+}
+
+// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-get-candlesticks
+// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-get-candlesticks-history
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CandlesRequestParams {
+	pub inst_id: String,
+	/// 1m, 3m, 5m, 15m, 30m, 1H, 2H, 4H, 6Hutc, 12Hutc, 1Dutc, 1Wutc, 1Mutc
+	pub bar: String,
+	/// default 100, max 300 (100 for history)
+	pub limit: Option<u32>,
+	/// Return records earlier than this timestamp
+	pub after: Option<u64>,
+	/// Return records newer than this timestamp
+	pub before: Option<u64>,
+}
+/// Sorted in reverse order by timestamp
+pub type CandleItem = (
+	String, // Open time
+	String, // Open
+	String, // High
+	String, // Low
+	String, // Close
+	String, // Volume in contracts
+	String, // Volume in base currency
+	String, // Volume in quote currency
+	String, // 0 - candle is uncompleted, 1 - completed
+);
+
+// https://www.okx.com/docs-v5/en/#trading-statistics-rest-api-get-contract-open-interest-history
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterestHistoryRequestParams {
+	pub inst_id: String,
+	/// 5m, 15m, 30m, 1H, 2H, 4H, 6Hutc, 12Hutc, 1Dutc
+	pub period: String,
+	/// default 100, max 100
+	pub limit: Option<u32>,
+}
+/// Sorted in reverse order by timestamp
+pub type OpenInterestItem = (
+	String, // Timestamp
+	String, // Open interest in contracts
+	String, // Open interest in base currency
+	String, // Open interest in USD
+);
+
+// https://www.okx.com/docs-v5/en/#trading-statistics-rest-api-get-contract-long-short-ratio
+// https://www.okx.com/docs-v5/en/#trading-statistics-rest-api-get-top-traders-contract-long-short-ratio
+// https://www.okx.com/docs-v5/en/#trading-statistics-rest-api-get-top-traders-contract-long-short-ratio-by-position
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LongShortRatioRequestParams {
+	pub inst_id: String,
+	/// 5m, 15m, 30m, 1H, 2H, 4H, 6Hutc, 12Hutc, 1Dutc
+	pub period: String,
+	/// default 100, max 100
+	pub limit: Option<u32>,
+}
+/// Sorted in reverse order by timestamp
+pub type LongShortRatioItem = (
+	String, // Timestamp
+	String, // Long/short ratio
+);
+
+// https://www.okx.com/docs-v5/en/#trading-statistics-rest-api-get-contract-taker-volume
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TakerVolumeRequestParams {
+	pub inst_id: String,
+	/// 5m, 15m, 30m, 1H, 2H, 4H, 6Hutc, 12Hutc, 1Dutc, ...
+	pub period: String,
+	/// 0: base currency, 1: contracts, 2: USD
+	pub unit: Option<&'static str>,
+	/// default 100, max 100
+	pub limit: Option<u32>,
+}
+/// Sorted in reverse order by timestamp
+pub type TakerVolumeItem = (
+	String, // Timestamp
+	String, // Sell volume
+	String, // Buy volume
+);
+
+// https://www.okx.com/docs-v5/en/#public-data-rest-api-get-funding-rate-history
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateHistoryRequestParams {
+	pub inst_id: String,
+	/// default 100, max 400
+	pub limit: Option<u32>,
+}
+/// Sorted in reverse order by funding time
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateHistoryItem {
+	pub funding_rate: String,
+	pub funding_time: String,
+}
+
+// https://www.okx.com/docs-v5/en/#overview-websocket-subscribe
+#[derive(Serialize)]
+pub struct WsRequest {
+	pub op: &'static str,
+	pub args: Vec<WsChannelArg>,
+}
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsChannelArg {
+	pub channel: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub inst_type: Option<InstrumentType>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub inst_id: Option<String>,
+}
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct WsEventResponse {
+	pub event: String,
+	#[serde(default)]
+	pub code: String,
+	#[serde(default)]
+	pub msg: String,
+}
+
+// https://www.okx.com/docs-v5/en/#public-data-websocket-liquidation-orders-channel
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct LiquidationOrdersStream {
+	pub arg: WsChannelArg,
+	pub data: Vec<LiquidationOrder>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationOrder {
+	pub inst_id: String,
+	pub details: Vec<LiquidationOrderDetails>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationOrderDetails {
+	/// Liquidation order side: `sell` closes a long, `buy` closes a short
+	pub side: Side,
+	#[allow(dead_code)]
+	pub pos_side: String,
+	/// Bankruptcy price
+	pub bk_px: String,
+	/// Size in contracts
+	pub sz: String,
+	pub ts: String,
+}
+
+// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-ws-trades-channel
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct TradesStream {
+	pub arg: WsChannelArg,
+	pub data: Vec<TradeData>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeData {
+	pub inst_id: String,
+	pub px: String,
+	/// Size in contracts
+	pub sz: String,
+	/// Taker side
+	pub side: Side,
+	pub ts: String,
+}
+
+// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-ws-tickers-channel
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub struct TickersStream {
+	pub arg: WsChannelArg,
+	pub data: Vec<TickerData>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickerData {
+	pub inst_id: String,
+	pub last: String,
+	pub open_24h: String,
+	pub high_24h: String,
+	pub low_24h: String,
+	/// Volume in base currency for swaps
+	pub vol_ccy_24h: String,
+	pub ts: String,
+}
+
+/// Push message of channels handled together, `data` is decoded once the channel is known
+#[derive(Debug, Deserialize)]
+pub struct ChannelPush {
+	pub arg: WsChannelArg,
+	pub data: serde_json::Value,
+}
+
+// https://www.okx.com/docs-v5/en/#public-data-websocket-mark-price-channel
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkPriceData {
+	pub inst_id: String,
+	pub mark_px: String,
+	pub ts: String,
+}
+
+// https://www.okx.com/docs-v5/en/#public-data-websocket-index-tickers-channel
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexTickerData {
+	/// e.g. `BTC-USDT`
+	pub inst_id: String,
+	pub idx_px: String,
+}
+
+// https://www.okx.com/docs-v5/en/#public-data-rest-api-get-funding-rate
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateRequestParams {
+	pub inst_id: String,
+}
+
+// https://www.okx.com/docs-v5/en/#public-data-websocket-funding-rate-channel
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateData {
+	pub inst_id: String,
+	pub funding_rate: String,
+	/// Settlement time of the current period
+	pub funding_time: String,
+}
@@ -0,0 +1,1021 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, bail};
+use futures_util::stream::BoxStream;
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::time::Duration;
+use tracing::error;
+
+use crate::{
+	CandleInfo, ContractKind, Exchange, ExchangeError, ExchangeKind, FUNDING_HISTORY_LIMIT, FundingRateInfo, Interval,
+	LocalOrderBook, LongShortRatio, LongShortRatioKind, MarkPriceInfo, MarketLiquidationsInfo, OpenInterestInfo,
+	OpenInterestPoint, OrderBookLevel, StreamEvent, SymbolInfo, SymbolStatus, TakerVolume, TickerInfo, TradeInfo,
+	capture,
+	okx::api_schemes::{
+		ApiResponse, CandleItem, CandlesRequestParams, ChannelPush, ContractType, FundingRateData, FundingRateHistoryItem,
+		FundingRateHistoryRequestParams, FundingRateRequestParams, IndexTickerData, InstrumentInfo, InstrumentState,
+		InstrumentType, InstrumentsRequestParams, LiquidationOrdersStream, LongShortRatioItem, LongShortRatioRequestParams,
+		MarkPriceData, OpenInterestHistoryRequestParams, OpenInterestItem, OrderBookItem, OrderBookRequestParams,
+		TakerVolumeItem, TakerVolumeRequestParams, TickersStream, TradesStream, WsChannelArg, WsEventResponse, WsRequest,
+	},
+	replay::Replay,
+	stream::{Heartbeat, RECONNECT_DELAY, Subscription, reconnecting_stream},
+	symbol_info::SymbolCache,
+	utils::{fetch_candles_range, funding_rate_info_from_history, open_interest_info_from_series},
+};
+mod api_schemes;
+
+const OKX_API_BASE: &str = "https://www.okx.com";
+
+const WS_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
+/// OKX closes connections idle for 30 seconds, a plain `ping` text frame keeps it alive
+const PING_EVERY: Duration = Duration::from_secs(25);
+const PONG_TIMEOUT: Duration = Duration::from_mins(1);
+const CANDLES_MAX_LIMIT: u32 = 300;
+const HISTORY_CANDLES_MAX_LIMIT: u32 = 100;
+const LONG_SHORT_RATIO_MAX_LIMIT: u32 = 100;
+const OPEN_INTEREST_MAX_LIMIT: u32 = 100;
+const ORDER_BOOK_MAX_LIMIT: u32 = 400;
+const TAKER_VOLUME_MAX_LIMIT: u32 = 100;
+/// Taker volume in base currency rather than contracts
+const TAKER_VOLUME_BASE_UNIT: &str = "0";
+
+const USDT_SWAP_SUFFIX: &str = "-USDT-SWAP";
+/// Error code when the request rate is exceeded
+const RATE_LIMIT_CODE: &str = "50011";
+/// Error code for an instrument the exchange doesn't list
+const INSTRUMENT_NOT_FOUND_CODE: &str = "51001";
+
+#[derive(Clone)]
+pub struct OkxExchange {
+	client: reqwest::Client,
+	symbols: SymbolCache,
+}
+
+impl OkxExchange {
+	#[must_use]
+	pub fn new() -> Self {
+		Self { client: reqwest::Client::new(), symbols: SymbolCache::default() }
+	}
+
+	async fn get<T, Q>(&self, path: &str, query: &Q) -> anyhow::Result<T>
+	where
+		T: DeserializeOwned,
+		Q: Serialize + Sync,
+	{
+		let request = self.client.get(format!("{OKX_API_BASE}{path}")).query(query).build()?;
+		let url = request.url().to_string();
+		let body = if let Some(replay) = Replay::current() {
+			replay.response(&url)?
+		} else {
+			let body = self.client.execute(request).await?.error_for_status()?.text().await?;
+			capture::record(&url, &body);
+			body
+		};
+
+		// Error responses may carry a `data` payload of a different shape, so decode it only after checking `code`
+		let response: ApiResponse<serde_json::Value> = serde_json::from_str(&body)?;
+
+		match response.code.as_str() {
+			"0" => {},
+			RATE_LIMIT_CODE => return Err(ExchangeError::RateLimited { retry_after: None }.into()),
+			INSTRUMENT_NOT_FOUND_CODE => {
+				let inst_id = serde_json::to_value(query)?.get("instId").and_then(|inst_id| inst_id.as_str()).map(String::from);
+				return Err(ExchangeError::UnknownSymbol(inst_id.unwrap_or_default()).into());
+			},
+			code => bail!("OKX API error {code}: {}", response.msg),
+		}
+
+		serde_json::from_value(response.data).context("Failed to decode OKX response")
+	}
+
+	/// Live USDT-margined perpetual swaps
+	async fn get_usdt_swap_instruments(&self) -> anyhow::Result<Vec<InstrumentInfo>> {
+		let instruments: Vec<InstrumentInfo> = self
+			.get("/api/v5/public/instruments", &InstrumentsRequestParams { inst_type: InstrumentType::Swap })
+			.await
+			.context("Failed to fetch instruments")?;
+
+		Ok(
+			instruments
+				.into_iter()
+				.filter(|instrument| {
+					instrument.settle_ccy == "USDT"
+						&& instrument.ct_type == ContractType::Linear
+						&& instrument.state == InstrumentState::Live
+				})
+				.collect(),
+		)
+	}
+}
+
+impl Default for OkxExchange {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[async_trait::async_trait]
+impl Exchange for OkxExchange {
+	async fn get_all_usdt_pairs(&self) -> Result<Vec<String>, ExchangeError> {
+		let symbols = self.get_symbols().await?;
+
+		Ok(symbols.into_iter().filter(SymbolInfo::is_trading).map(|info| info.symbol).collect())
+	}
+
+	async fn get_symbols(&self) -> Result<Vec<SymbolInfo>, ExchangeError> {
+		self
+			.symbols
+			.get_or_fetch(|| async {
+				let instruments: Vec<InstrumentInfo> = self
+					.get("/api/v5/public/instruments", &InstrumentsRequestParams { inst_type: InstrumentType::Swap })
+					.await
+					.context("Failed to fetch instruments")?;
+
+				Ok(instruments.iter().filter_map(parse_instrument).collect())
+			})
+			.await
+	}
+
+	fn watch_market_liquidations(&self) -> BoxStream<'static, StreamEvent<MarketLiquidationsInfo>> {
+		let exchange = self.clone();
+
+		// Contract values are refetched on every reconnect to pick up new listings
+		reconnecting_stream(RECONNECT_DELAY, move || {
+			let exchange = exchange.clone();
+			async move {
+				let contract_values = contract_values(&exchange.get_usdt_swap_instruments().await?);
+
+				Ok(Subscription {
+					url: String::from(WS_URL),
+					messages: vec![subscription_message(vec![WsChannelArg {
+						channel: String::from("liquidation-orders"),
+						inst_type: Some(InstrumentType::Swap),
+						inst_id: None,
+					}])?],
+					heartbeat: Heartbeat::Text { ping: String::from("ping"), is_pong },
+					ping_every: PING_EVERY,
+					pong_timeout: PONG_TIMEOUT,
+					parse: move |text: &str| {
+						if is_event(text) {
+							return Ok(Vec::new());
+						}
+						parse_liquidations(text, &contract_values)
+					},
+				})
+			}
+		})
+	}
+
+	fn watch_tickers(&self) -> BoxStream<'static, StreamEvent<TickerInfo>> {
+		let exchange = self.clone();
+
+		reconnecting_stream(RECONNECT_DELAY, move || {
+			let exchange = exchange.clone();
+			async move {
+				let inst_ids = exchange.get_usdt_swap_instruments().await?.into_iter().map(|instrument| instrument.inst_id);
+
+				Ok(Subscription {
+					url: String::from(WS_URL),
+					messages: vec![subscription_message(channel_args("tickers", inst_ids))?],
+					heartbeat: Heartbeat::Text { ping: String::from("ping"), is_pong },
+					ping_every: PING_EVERY,
+					pong_timeout: PONG_TIMEOUT,
+					parse: |text: &str| {
+						if is_event(text) {
+							return Ok(Vec::new());
+						}
+						parse_tickers(text)
+					},
+				})
+			}
+		})
+	}
+
+	fn watch_mark_prices(&self) -> BoxStream<'static, StreamEvent<MarkPriceInfo>> {
+		let exchange = self.clone();
+
+		// OKX splits mark price, index price and funding into separate channels, they are merged per symbol
+		reconnecting_stream(RECONNECT_DELAY, move || {
+			let exchange = exchange.clone();
+			async move {
+				let inst_ids: Vec<String> =
+					exchange.get_usdt_swap_instruments().await?.into_iter().map(|instrument| instrument.inst_id).collect();
+				let index_ids = inst_ids.iter().filter_map(|inst_id| inst_id.strip_suffix("-SWAP")).map(String::from);
+				let messages = vec![
+					subscription_message(channel_args("mark-price", inst_ids.iter().cloned()))?,
+					subscription_message(channel_args("funding-rate", inst_ids.iter().cloned()))?,
+					subscription_message(channel_args("index-tickers", index_ids))?,
+				];
+				let mut states = HashMap::new();
+
+				Ok(Subscription {
+					url: String::from(WS_URL),
+					messages,
+					heartbeat: Heartbeat::Text { ping: String::from("ping"), is_pong },
+					ping_every: PING_EVERY,
+					pong_timeout: PONG_TIMEOUT,
+					parse: move |text: &str| {
+						if is_event(text) {
+							return Ok(Vec::new());
+						}
+						parse_mark_price_push(text, &mut states)
+					},
+				})
+			}
+		})
+	}
+
+	fn watch_trades(&self, symbol: &str) -> BoxStream<'static, StreamEvent<TradeInfo>> {
+		let exchange = self.clone();
+		let symbol = String::from(symbol);
+
+		reconnecting_stream(RECONNECT_DELAY, move || {
+			let exchange = exchange.clone();
+			let symbol = symbol.clone();
+			async move {
+				let inst_id = to_inst_id(&symbol)?;
+				let contract_values = contract_values(&exchange.get_usdt_swap_instruments().await?);
+
+				Ok(Subscription {
+					url: String::from(WS_URL),
+					messages: vec![subscription_message(channel_args("trades", std::iter::once(inst_id.clone())))?],
+					heartbeat: Heartbeat::Text { ping: String::from("ping"), is_pong },
+					ping_every: PING_EVERY,
+					pong_timeout: PONG_TIMEOUT,
+					parse: move |text: &str| {
+						if is_event(text) {
+							return Ok(Vec::new());
+						}
+						parse_trades(text, &inst_id, &contract_values)
+					},
+				})
+			}
+		})
+	}
+
+	async fn get_klines(&self, symbol: &str, interval: Interval, limit: u32) -> Result<Vec<CandleInfo>, ExchangeError> {
+		let response: Vec<CandleItem> = self
+			.get(
+				"/api/v5/market/candles",
+				&CandlesRequestParams {
+					inst_id: to_inst_id(symbol)?,
+					bar: String::from(to_okx_bar(interval)?),
+					limit: Some(limit.min(CANDLES_MAX_LIMIT)),
+					..Default::default()
+				},
+			)
+			.await
+			.context(format!("Failed to fetch klines info for {symbol}"))?;
+
+		Ok(parse_candles(&response, interval))
+	}
+
+	/// Uses the history endpoint, the regular one only serves the latest 1440 candles
+	async fn get_klines_range(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<CandleInfo>, ExchangeError> {
+		let inst_id = to_inst_id(symbol)?;
+		let bar = to_okx_bar(interval)?;
+
+		fetch_candles_range(interval, from, to, HISTORY_CANDLES_MAX_LIMIT, |start, end| {
+			let params = CandlesRequestParams {
+				inst_id: inst_id.clone(),
+				bar: String::from(bar),
+				limit: Some(HISTORY_CANDLES_MAX_LIMIT),
+				// Both bounds are exclusive
+				after: Some(end + 1),
+				before: start.checked_sub(1),
+			};
+
+			async move {
+				let response: Vec<CandleItem> = self
+					.get("/api/v5/market/history-candles", &params)
+					.await
+					.context(format!("Failed to fetch klines history for {symbol}"))?;
+
+				Ok(parse_candles(&response, interval))
+			}
+		})
+		.await
+	}
+
+	async fn get_funding_rate_info(&self, symbol: &str) -> Result<FundingRateInfo, ExchangeError> {
+		let inst_id = to_inst_id(symbol)?;
+
+		let current_params = FundingRateRequestParams { inst_id: inst_id.clone() };
+		let history_params = FundingRateHistoryRequestParams { inst_id, limit: Some(FUNDING_HISTORY_LIMIT) };
+
+		let (current, history) = tokio::try_join!(
+			self.get::<Vec<FundingRateData>, _>("/api/v5/public/funding-rate", &current_params),
+			self.get::<Vec<FundingRateHistoryItem>, _>("/api/v5/public/funding-rate-history", &history_params),
+		)
+		.context(format!("Failed to fetch funding rate info for {symbol}"))?;
+
+		let current = current.first().context(format!("No funding rate for {symbol}"))?;
+		Ok(parse_funding_rate_info(current, &history)?)
+	}
+
+	async fn get_open_interest_info(&self, symbol: &str) -> Result<OpenInterestInfo, ExchangeError> {
+		// 48 x 5m to get 5m - 4h distance, 30 x 1d for 30 days
+		let (series_5m, series_1d) = tokio::try_join!(
+			self.get_open_interest_history(symbol, Interval::Minute5, 48),
+			self.get_open_interest_history(symbol, Interval::Day1, 30),
+		)?;
+
+		Ok(open_interest_info_from_series(&series_5m, &series_1d)?)
+	}
+
+	async fn get_open_interest_history(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<OpenInterestPoint>, ExchangeError> {
+		let response: Vec<OpenInterestItem> = self
+			.get(
+				"/api/v5/rubik/stat/contracts/open-interest-history",
+				&OpenInterestHistoryRequestParams {
+					inst_id: to_inst_id(symbol)?,
+					period: String::from(to_okx_stat_period(period)?),
+					limit: Some(limit.min(OPEN_INTEREST_MAX_LIMIT)),
+				},
+			)
+			.await
+			.context(format!("Failed to fetch open interest history for {symbol} ({period})"))?;
+
+		Ok(parse_open_interest_series(&response)?)
+	}
+
+	async fn get_order_book(&self, symbol: &str, limit: u32) -> Result<LocalOrderBook, ExchangeError> {
+		let inst_id = to_inst_id(symbol)?;
+		let request = OrderBookRequestParams { inst_id: inst_id.clone(), sz: Some(limit.min(ORDER_BOOK_MAX_LIMIT)) };
+
+		// Contract values come from the symbol cache, so only the book itself is requested in most calls
+		let normalized = normalize_symbol(&inst_id).ok_or_else(|| ExchangeError::UnknownSymbol(String::from(symbol)))?;
+		let (info, response) = tokio::try_join!(
+			async { anyhow::Ok(self.get_symbol_info(&normalized).await?) },
+			self.get::<Vec<OrderBookItem>, _>("/api/v5/market/books", &request),
+		)
+		.context(format!("Failed to fetch order book for {symbol}"))?;
+
+		let contract_value = info.contract_size.ok_or_else(|| ExchangeError::UnknownSymbol(String::from(symbol)))?;
+		let book = response.first().context(format!("Empty order book for {symbol}"))?;
+
+		Ok(parse_order_book(symbol, book, contract_value)?)
+	}
+
+	async fn get_long_short_ratio(
+		&self,
+		symbol: &str,
+		kind: LongShortRatioKind,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<LongShortRatio>, ExchangeError> {
+		let path = match kind {
+			LongShortRatioKind::GlobalAccount => "long-short-account-ratio-contract",
+			LongShortRatioKind::TopTraderAccount => "long-short-account-ratio-contract-top-trader",
+			LongShortRatioKind::TopTraderPosition => "long-short-position-ratio-contract-top-trader",
+		};
+
+		let response: Vec<LongShortRatioItem> = self
+			.get(
+				&format!("/api/v5/rubik/stat/contracts/{path}"),
+				&LongShortRatioRequestParams {
+					inst_id: to_inst_id(symbol)?,
+					period: String::from(to_okx_stat_period(period)?),
+					limit: Some(limit.min(LONG_SHORT_RATIO_MAX_LIMIT)),
+				},
+			)
+			.await
+			.context(format!("Failed to fetch long/short ratio for {symbol}"))?;
+
+		Ok(parse_long_short_ratios(&response)?)
+	}
+
+	async fn get_taker_volume(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<TakerVolume>, ExchangeError> {
+		let response: Vec<TakerVolumeItem> = self
+			.get(
+				"/api/v5/rubik/stat/taker-volume-contract",
+				&TakerVolumeRequestParams {
+					inst_id: to_inst_id(symbol)?,
+					period: String::from(to_okx_stat_period(period)?),
+					unit: Some(TAKER_VOLUME_BASE_UNIT),
+					limit: Some(limit.min(TAKER_VOLUME_MAX_LIMIT)),
+				},
+			)
+			.await
+			.context(format!("Failed to fetch taker volume for {symbol}"))?;
+
+		Ok(parse_taker_volumes(&response)?)
+	}
+}
+
+/// Only USDT-margined swaps are kept, sizes are converted from contracts into base currency
+fn parse_instrument(instrument: &InstrumentInfo) -> Option<SymbolInfo> {
+	if instrument.settle_ccy != "USDT" || instrument.ct_type != ContractType::Linear {
+		return None;
+	}
+
+	let base_asset = instrument.inst_id.strip_suffix(USDT_SWAP_SUFFIX)?;
+
+	Some(SymbolInfo {
+		symbol: format!("{base_asset}USDT"),
+		base_asset: String::from(base_asset),
+		quote_asset: String::from("USDT"),
+		contract_kind: ContractKind::Perpetual,
+		onboard_date: instrument.list_time.parse().ok()?,
+		tick_size: instrument.tick_sz.parse().ok()?,
+		step_size: base_step_size(&instrument.lot_sz, &instrument.ct_val)?,
+		min_notional: None,
+		contract_size: Some(instrument.ct_val.parse().ok()?),
+		status: match instrument.state {
+			InstrumentState::Preopen => SymbolStatus::PreTrading,
+			InstrumentState::Live => SymbolStatus::Trading,
+			InstrumentState::Suspend => SymbolStatus::Suspended,
+			InstrumentState::Test | InstrumentState::Unknown => SymbolStatus::Unknown,
+		},
+	})
+}
+
+/// Lot size in base units, rounded to the decimals of both fields so `0.1` × `0.1` is `0.01` and not `0.010000000000000002`
+fn base_step_size(lot_sz: &str, ct_val: &str) -> Option<f64> {
+	let decimals = |value: &str| value.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+	let scale = 10_f64.powi(i32::try_from(decimals(lot_sz) + decimals(ct_val)).ok()?);
+	let step = lot_sz.parse::<f64>().ok()? * ct_val.parse::<f64>().ok()?;
+
+	Some((step * scale).round() / scale)
+}
+
+/// `BTC-USDT-SWAP` → `BTCUSDT`, `None` for anything but USDT-margined swaps
+fn normalize_symbol(inst_id: &str) -> Option<String> {
+	inst_id.strip_suffix(USDT_SWAP_SUFFIX).map(|base| format!("{base}USDT"))
+}
+
+/// `BTCUSDT` → `BTC-USDT-SWAP`, OKX instrument ids are passed through as is
+fn to_inst_id(symbol: &str) -> anyhow::Result<String> {
+	if symbol.contains('-') {
+		return Ok(String::from(symbol));
+	}
+
+	symbol
+		.strip_suffix("USDT")
+		.filter(|base| !base.is_empty())
+		.map(|base| format!("{base}{USDT_SWAP_SUFFIX}"))
+		.ok_or_else(|| ExchangeError::UnknownSymbol(String::from(symbol)).into())
+}
+
+/// Daily and longer bars are aligned to UTC
+fn to_okx_bar(interval: Interval) -> anyhow::Result<&'static str> {
+	let bar = match interval {
+		Interval::Minute1 => "1m",
+		Interval::Minute3 => "3m",
+		Interval::Minute5 => "5m",
+		Interval::Minute15 => "15m",
+		Interval::Minute30 => "30m",
+		Interval::Hour1 => "1H",
+		Interval::Hour2 => "2H",
+		Interval::Hour4 => "4H",
+		Interval::Hour6 => "6Hutc",
+		Interval::Hour12 => "12Hutc",
+		Interval::Day1 => "1Dutc",
+		Interval::Day3 => "3Dutc",
+		Interval::Week1 => "1Wutc",
+		Interval::Month1 => "1Mutc",
+		Interval::Hour8 => return Err(ExchangeError::Unsupported(format!("OKX kline interval {interval}")).into()),
+	};
+
+	Ok(bar)
+}
+
+/// Trading statistics (open interest history, long/short ratio, taker volume) start at 5 minute periods
+fn to_okx_stat_period(period: Interval) -> anyhow::Result<&'static str> {
+	match period {
+		Interval::Minute1 | Interval::Minute3 => {
+			Err(ExchangeError::Unsupported(format!("OKX trading statistics period {period}")).into())
+		},
+		_ => to_okx_bar(period),
+	}
+}
+
+/// Contract value per normalized symbol, used to convert contract sizes into base currency
+fn contract_values(instruments: &[InstrumentInfo]) -> HashMap<String, f64> {
+	instruments
+		.iter()
+		.filter_map(|instrument| Some((normalize_symbol(&instrument.inst_id)?, instrument.ct_val.parse().ok()?)))
+		.collect()
+}
+
+/// OKX returns newest candles first, `CandleInfo` list is ordered oldest → newest
+fn parse_candles(list: &[CandleItem], interval: Interval) -> Vec<CandleInfo> {
+	list
+		.iter()
+		.rev()
+		.filter_map(|v| {
+			let open_time: u64 = v.0.parse().ok()?;
+
+			Some(CandleInfo {
+				open_time,
+				close_time: open_time + interval.as_millis() - 1,
+				open: v.1.parse().ok()?,
+				high: v.2.parse().ok()?,
+				low: v.3.parse().ok()?,
+				close: v.4.parse().ok()?,
+				volume: v.6.parse().ok()?,
+				quote_volume: v.7.parse().ok()?,
+				trades: None,
+				taker_buy_volume: None,
+				taker_buy_quote_volume: None,
+			})
+		})
+		.collect()
+}
+
+/// OKX returns newest entries first, the series (in base currency) is ordered oldest → newest
+fn parse_open_interest_series(list: &[OpenInterestItem]) -> anyhow::Result<Vec<OpenInterestPoint>> {
+	list
+		.iter()
+		.rev()
+		.map(|item| {
+			Ok(OpenInterestPoint {
+				open_interest: item.2.parse().context(format!("Failed to parse open interest: {}", item.2))?,
+				open_interest_usd: Some(item.3.parse().context(format!("Failed to parse open interest value: {}", item.3))?),
+				timestamp: item.0.parse().context(format!("Failed to parse timestamp: {}", item.0))?,
+			})
+		})
+		.collect()
+}
+
+/// OKX returns funding history newest first, `fundingTime` of the current period is its settlement time
+fn parse_funding_rate_info(
+	current: &FundingRateData,
+	history: &[FundingRateHistoryItem],
+) -> anyhow::Result<FundingRateInfo> {
+	let funding_rate =
+		current.funding_rate.parse().context(format!("Failed to parse funding rate: {}", current.funding_rate))?;
+	let next_funding_time =
+		current.funding_time.parse().context(format!("Failed to parse funding time: {}", current.funding_time))?;
+
+	let history = history
+		.iter()
+		.rev()
+		.map(|item| {
+			let rate = item.funding_rate.parse().context(format!("Failed to parse funding rate: {}", item.funding_rate))?;
+			let time = item.funding_time.parse().context(format!("Failed to parse funding time: {}", item.funding_time))?;
+			Ok((time, rate))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	Ok(funding_rate_info_from_history(funding_rate, next_funding_time, &history))
+}
+
+/// OKX returns newest entries first and reports the ratio only, the series is ordered oldest → newest
+fn parse_long_short_ratios(list: &[LongShortRatioItem]) -> anyhow::Result<Vec<LongShortRatio>> {
+	list
+		.iter()
+		.rev()
+		.map(|item| {
+			let ratio = item.1.parse::<f64>().context(format!("Failed to parse long/short ratio: {}", item.1))?;
+			let timestamp = item.0.parse::<u64>().context(format!("Failed to parse timestamp: {}", item.0))?;
+			Ok(LongShortRatio::from_ratio(ratio, timestamp))
+		})
+		.collect()
+}
+
+/// OKX returns newest entries first, the series is ordered oldest → newest
+fn parse_taker_volumes(list: &[TakerVolumeItem]) -> anyhow::Result<Vec<TakerVolume>> {
+	list
+		.iter()
+		.rev()
+		.map(|item| {
+			let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse taker volume: {value}"));
+
+			Ok(TakerVolume {
+				buy_volume: parse(&item.2)?,
+				sell_volume: parse(&item.1)?,
+				timestamp: item.0.parse().context(format!("Failed to parse timestamp: {}", item.0))?,
+			})
+		})
+		.collect()
+}
+
+fn channel_args(channel: &str, inst_ids: impl Iterator<Item = String>) -> Vec<WsChannelArg> {
+	inst_ids
+		.map(|inst_id| WsChannelArg { channel: String::from(channel), inst_type: None, inst_id: Some(inst_id) })
+		.collect()
+}
+
+fn subscription_message(args: Vec<WsChannelArg>) -> anyhow::Result<String> {
+	Ok(serde_json::to_string(&WsRequest { op: "subscribe", args })?)
+}
+
+fn is_pong(text: &str) -> bool {
+	text == "pong"
+}
+
+/// Event frames carry no data, failed subscriptions are logged
+fn is_event(text: &str) -> bool {
+	let Ok(response) = serde_json::from_str::<WsEventResponse>(text) else {
+		return false;
+	};
+
+	if response.event == "error" {
+		error!("Subscribe failed: {} {}", response.code, response.msg);
+	}
+	true
+}
+
+/// Liquidations of instruments missing from `contract_values` (e.g. coin-margined swaps) and pushes of other
+/// channels are skipped
+fn parse_liquidations(
+	text: &str,
+	contract_values: &HashMap<String, f64>,
+) -> anyhow::Result<Vec<MarketLiquidationsInfo>> {
+	let stream: LiquidationOrdersStream = serde_json::from_str(text)?;
+	let mut liquidations = Vec::new();
+	if stream.arg.channel != "liquidation-orders" {
+		return Ok(liquidations);
+	}
+
+	for order in stream.data {
+		let Some(symbol) = normalize_symbol(&order.inst_id) else {
+			continue;
+		};
+		let Some(contract_value) = contract_values.get(&symbol) else {
+			continue;
+		};
+
+		for details in order.details {
+			let symbol_price = details.bk_px.parse::<f64>().context(format!("Failed to parse price: {}", details.bk_px))?;
+			let contracts = details.sz.parse::<f64>().context(format!("Failed to parse quantity: {}", details.sz))?;
+			let time = details.ts.parse::<u64>().context(format!("Failed to parse time: {}", details.ts))?;
+			let quantity = contracts * contract_value;
+
+			liquidations.push(MarketLiquidationsInfo {
+				symbol: symbol.clone(),
+				exchange: ExchangeKind::Okx,
+				side: details.side,
+				order_type: None,
+				symbol_price,
+				average_price: symbol_price,
+				usd_price: symbol_price * quantity,
+				quantity,
+				filled_quantity: quantity,
+				time,
+				trade_time: time,
+			});
+		}
+	}
+
+	Ok(liquidations)
+}
+
+/// Pushes of other channels are skipped
+fn parse_tickers(text: &str) -> anyhow::Result<Vec<TickerInfo>> {
+	let stream: TickersStream = serde_json::from_str(text)?;
+	if stream.arg.channel != "tickers" {
+		return Ok(Vec::new());
+	}
+
+	stream
+		.data
+		.into_iter()
+		.filter_map(|ticker| Some((normalize_symbol(&ticker.inst_id)?, ticker)))
+		.map(|(symbol, ticker)| {
+			let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse ticker value: {value}"));
+			let last_price = parse(&ticker.last)?;
+			let open_price = parse(&ticker.open_24h)?;
+			let volume = parse(&ticker.vol_ccy_24h)?;
+			let price_change_percent = if open_price == 0.0 { 0.0 } else { (last_price - open_price) / open_price * 100.0 };
+
+			Ok(TickerInfo {
+				symbol,
+				last_price,
+				open_price,
+				high_price: parse(&ticker.high_24h)?,
+				low_price: parse(&ticker.low_24h)?,
+				price_change_percent,
+				volume,
+				quote_volume: volume * last_price,
+				trades: None,
+				time: ticker.ts.parse().context(format!("Failed to parse time: {}", ticker.ts))?,
+			})
+		})
+		.collect()
+}
+
+/// Latest values of the channels making up `MarkPriceInfo`
+#[derive(Debug, Default)]
+struct MarkPriceState {
+	index_price: Option<f64>,
+	funding_rate: Option<f64>,
+	next_funding_time: Option<u64>,
+}
+
+/// Trades of instruments other than `inst_id` or missing from `contract_values` are skipped
+fn parse_trades(text: &str, inst_id: &str, contract_values: &HashMap<String, f64>) -> anyhow::Result<Vec<TradeInfo>> {
+	let stream: TradesStream = serde_json::from_str(text)?;
+	let mut trades = Vec::new();
+	if stream.arg.channel != "trades" {
+		return Ok(trades);
+	}
+
+	for item in stream.data.into_iter().filter(|item| item.inst_id == inst_id) {
+		let Some(symbol) = normalize_symbol(&item.inst_id) else {
+			continue;
+		};
+		let Some(contract_value) = contract_values.get(&symbol) else {
+			continue;
+		};
+
+		let price = item.px.parse::<f64>().context(format!("Failed to parse price: {}", item.px))?;
+		let contracts = item.sz.parse::<f64>().context(format!("Failed to parse quantity: {}", item.sz))?;
+		let time = item.ts.parse::<u64>().context(format!("Failed to parse time: {}", item.ts))?;
+
+		trades.push(TradeInfo { symbol, price, quantity: contracts * contract_value, taker_side: item.side, time });
+	}
+
+	Ok(trades)
+}
+
+/// Sizes are converted from contracts to base asset. REST snapshots carry no sequence id, so `last_update_id` is 0.
+fn parse_order_book(symbol: &str, book: &OrderBookItem, contract_value: f64) -> anyhow::Result<LocalOrderBook> {
+	let parse_levels = |levels: &[(String, String, String, String)]| {
+		levels
+			.iter()
+			.map(|(price, contracts, ..)| {
+				let contracts: f64 = contracts.parse().context(format!("Failed to parse size: {contracts}"))?;
+				Ok(OrderBookLevel {
+					price: price.parse().context(format!("Failed to parse price: {price}"))?,
+					quantity: contracts * contract_value,
+				})
+			})
+			.collect::<anyhow::Result<Vec<_>>>()
+	};
+
+	Ok(LocalOrderBook::from_snapshot(symbol, 0, parse_levels(&book.bids)?, parse_levels(&book.asks)?, book.ts.parse()?))
+}
+
+/// Index and funding pushes update `states`, mark price pushes are emitted once the rest is known
+fn parse_mark_price_push(
+	text: &str,
+	states: &mut HashMap<String, MarkPriceState>,
+) -> anyhow::Result<Vec<MarkPriceInfo>> {
+	let push: ChannelPush = serde_json::from_str(text)?;
+	let parse = |value: &str| value.parse::<f64>().context(format!("Failed to parse {}: {value}", push.arg.channel));
+
+	match push.arg.channel.as_str() {
+		"index-tickers" => {
+			for item in serde_json::from_value::<Vec<IndexTickerData>>(push.data.clone())? {
+				if let Some(symbol) = normalize_symbol(&format!("{}-SWAP", item.inst_id)) {
+					states.entry(symbol).or_default().index_price = Some(parse(&item.idx_px)?);
+				}
+			}
+			Ok(Vec::new())
+		},
+		"funding-rate" => {
+			for item in serde_json::from_value::<Vec<FundingRateData>>(push.data.clone())? {
+				if let Some(symbol) = normalize_symbol(&item.inst_id) {
+					let state = states.entry(symbol).or_default();
+					state.funding_rate = Some(parse(&item.funding_rate)?);
+					state.next_funding_time = Some(item.funding_time.parse()?);
+				}
+			}
+			Ok(Vec::new())
+		},
+		"mark-price" => {
+			let mut mark_prices = Vec::new();
+
+			for item in serde_json::from_value::<Vec<MarkPriceData>>(push.data.clone())? {
+				let Some(symbol) = normalize_symbol(&item.inst_id) else {
+					continue;
+				};
+				let Some(state) = states.get(&symbol) else {
+					continue;
+				};
+				let (Some(index_price), Some(funding_rate), Some(next_funding_time)) =
+					(state.index_price, state.funding_rate, state.next_funding_time)
+				else {
+					continue;
+				};
+
+				mark_prices.push(MarkPriceInfo {
+					symbol,
+					mark_price: parse(&item.mark_px)?,
+					index_price,
+					funding_rate,
+					next_funding_time,
+					time: item.ts.parse()?,
+				});
+			}
+
+			Ok(mark_prices)
+		},
+		channel => bail!("Unexpected channel: {channel}"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse_data<T: DeserializeOwned>(fixture: &str) -> T {
+		let response: ApiResponse<T> = serde_json::from_str(fixture).unwrap();
+		assert_eq!(response.code, "0");
+		response.data
+	}
+
+	#[test]
+	fn normalizes_symbols() {
+		assert_eq!(normalize_symbol("BTC-USDT-SWAP").as_deref(), Some("BTCUSDT"));
+		assert_eq!(normalize_symbol("BTC-USD-SWAP"), None);
+		assert_eq!(to_inst_id("1000PEPEUSDT").unwrap(), "1000PEPE-USDT-SWAP");
+		assert_eq!(to_inst_id("ETH-USDT-SWAP").unwrap(), "ETH-USDT-SWAP");
+		assert!(to_inst_id("USDT").is_err());
+	}
+
+	#[test]
+	fn parses_instruments() {
+		let instruments: Vec<InstrumentInfo> = parse_data(include_str!("../../fixtures/okx/instruments.json"));
+
+		assert_eq!(instruments.len(), 3);
+		assert_eq!(instruments[0].ct_type, ContractType::Linear);
+		assert_eq!(instruments[1].ct_type, ContractType::Inverse);
+		assert_eq!(instruments[2].state, InstrumentState::Preopen);
+
+		let values = contract_values(&instruments);
+		assert_eq!(values.get("BTCUSDT"), Some(&0.01));
+		assert_eq!(values.len(), 2);
+
+		let symbols: Vec<_> = instruments.iter().filter_map(parse_instrument).collect();
+		assert_eq!(symbols.len(), 2);
+		assert_eq!(symbols[0].base_asset, "BTC");
+		assert!((symbols[0].step_size - 0.0001).abs() < f64::EPSILON);
+		assert_eq!(symbols[0].quantity_precision(), 4);
+		assert_eq!(symbols[0].contract_size, Some(0.01));
+		assert_eq!(base_step_size("0.1", "0.1").map(|step| step.to_string()).as_deref(), Some("0.01"));
+		assert_eq!(base_step_size("1", "1000").map(|step| step.to_string()).as_deref(), Some("1000"));
+		assert_eq!(symbols[0].format_price(96_123.456), "96123.5");
+		assert_eq!(symbols[1].symbol, "NEWUSDT");
+		assert_eq!(symbols[1].status, SymbolStatus::PreTrading);
+		assert_eq!(symbols[1].onboard_date, 1_760_000_000_000);
+	}
+
+	#[test]
+	fn parses_candles_oldest_first() {
+		let candles =
+			parse_candles(&parse_data::<Vec<CandleItem>>(include_str!("../../fixtures/okx/candles.json")), Interval::Hour1);
+
+		assert_eq!(candles.len(), 2);
+		assert_eq!(candles[0].close_time, 1_760_003_599_999);
+		assert!(candles[0].is_closed_at(1_760_003_600_000));
+		assert!(!candles[1].is_closed_at(1_760_003_600_000));
+		assert!((candles[0].open - 96_000.0).abs() < f64::EPSILON);
+		assert!((candles[1].close - 97_500.5).abs() < f64::EPSILON);
+		assert!((candles[1].volume - 123.45).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn parses_open_interest_oldest_first() {
+		let response: Vec<OpenInterestItem> = parse_data(include_str!("../../fixtures/okx/open_interest_history.json"));
+
+		let series = parse_open_interest_series(&response).unwrap();
+
+		assert_eq!(series.iter().map(|point| point.open_interest).collect::<Vec<_>>(), vec![25_000.0, 25_500.0, 26_000.0]);
+		assert_eq!(series[2].open_interest_usd, Some(2_535_000_000.0));
+	}
+
+	#[test]
+	fn parses_funding_rate_history() {
+		let response: Vec<FundingRateHistoryItem> =
+			parse_data(include_str!("../../fixtures/okx/funding_rate_history.json"));
+
+		let current: Vec<FundingRateData> = parse_data(include_str!("../../fixtures/okx/public_funding_rate.json"));
+
+		let info = parse_funding_rate_info(&current[0], &response).unwrap();
+
+		assert_eq!(response.len(), 2);
+		assert!((info.funding_rate - 0.000_15).abs() < f64::EPSILON);
+		assert!((info.percentile - 1.0).abs() < f64::EPSILON);
+		assert_eq!(info.funding_interval_hours, 8);
+		assert_eq!(info.next_funding_time, 1_760_054_400_000);
+	}
+
+	#[test]
+	fn parses_liquidation_orders() {
+		let contract_values = HashMap::from([(String::from("BTCUSDT"), 0.01)]);
+		let liquidations =
+			parse_liquidations(include_str!("../../fixtures/okx/liquidation_orders.json"), &contract_values).unwrap();
+
+		assert_eq!(liquidations.len(), 1);
+		assert_eq!(liquidations[0].symbol, "BTCUSDT");
+		assert_eq!(liquidations[0].side, crate::Side::Sell);
+		assert!((liquidations[0].quantity - 1.5).abs() < 1e-9);
+		assert!((liquidations[0].usd_price - 145_500.0).abs() < 1e-6);
+		assert_eq!(liquidations[0].time, 1_760_000_000_123);
+	}
+
+	#[test]
+	fn parses_taker_volume_oldest_first() {
+		let volumes =
+			parse_taker_volumes(&parse_data::<Vec<TakerVolumeItem>>(include_str!("../../fixtures/okx/taker_volume.json")))
+				.unwrap();
+
+		assert_eq!(volumes.len(), 2);
+		assert_eq!(volumes[0].timestamp, 1_760_000_000_000);
+		assert!((volumes[0].imbalance() - 0.5).abs() < f64::EPSILON);
+		assert!((volumes[1].buy_volume - 150.25).abs() < f64::EPSILON);
+		assert!((volumes[1].sell_volume - 200.5).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn parses_long_short_ratio_oldest_first() {
+		let response: ApiResponse<Vec<LongShortRatioItem>> =
+			serde_json::from_str(include_str!("../../fixtures/okx/long_short_ratio.json")).unwrap();
+		let ratios = parse_long_short_ratios(&response.data).unwrap();
+
+		assert_eq!(ratios.len(), 2);
+		assert_eq!(ratios[0].timestamp, 1_760_000_000_000);
+		assert!((ratios[1].long_short_ratio - 1.5).abs() < f64::EPSILON);
+		assert!((ratios[1].long_share - 0.6).abs() < 1e-9);
+		assert!((ratios[1].short_share - 0.4).abs() < 1e-9);
+	}
+
+	#[test]
+	fn merges_mark_price_channels() {
+		let mut states = HashMap::new();
+		let mark_price = include_str!("../../fixtures/okx/mark_price.json");
+
+		// Nothing to emit until index price and funding are known
+		assert!(parse_mark_price_push(mark_price, &mut states).unwrap().is_empty());
+
+		parse_mark_price_push(include_str!("../../fixtures/okx/index_tickers.json"), &mut states).unwrap();
+		parse_mark_price_push(include_str!("../../fixtures/okx/funding_rate.json"), &mut states).unwrap();
+		let mark_prices = parse_mark_price_push(mark_price, &mut states).unwrap();
+
+		assert_eq!(mark_prices.len(), 1);
+		assert_eq!(mark_prices[0].symbol, "BTCUSDT");
+		assert!((mark_prices[0].mark_price - 102_001.5).abs() < f64::EPSILON);
+		assert!((mark_prices[0].index_price - 102_010.2).abs() < f64::EPSILON);
+		assert!((mark_prices[0].funding_rate - 0.000_15).abs() < f64::EPSILON);
+		assert_eq!(mark_prices[0].next_funding_time, 1_760_025_600_000);
+		assert_eq!(mark_prices[0].time, 1_760_000_000_200);
+	}
+
+	#[test]
+	fn parses_trades_in_base_asset() {
+		let contract_values = HashMap::from([(String::from("BTCUSDT"), 0.01)]);
+		let trade_push = include_str!("../../fixtures/okx/trades.json");
+		let trades = parse_trades(trade_push, "BTC-USDT-SWAP", &contract_values).unwrap();
+
+		assert!(parse_trades(trade_push, "ETH-USDT-SWAP", &contract_values).unwrap().is_empty());
+
+		assert_eq!(trades.len(), 1);
+		assert_eq!(trades[0].symbol, "BTCUSDT");
+		assert_eq!(trades[0].taker_side, crate::Side::Sell);
+		assert!((trades[0].quantity - 0.35).abs() < f64::EPSILON);
+		assert_eq!(trades[0].time, 1_760_000_000_400);
+	}
+
+	#[test]
+	fn parses_order_book_in_base_asset() {
+		let response: Vec<OrderBookItem> = parse_data(include_str!("../../fixtures/okx/books.json"));
+		let book = parse_order_book("BTCUSDT", &response[0], 0.01).unwrap();
+
+		assert_eq!(book.best_bid(), Some(OrderBookLevel { price: 97_000.1, quantity: 1.2 }));
+		assert_eq!(book.best_ask().map(|level| level.price), Some(97_000.2));
+		assert_eq!(book.time(), 1_760_000_000_321);
+	}
+
+	#[test]
+	fn parses_tickers() {
+		let tickers = parse_tickers(include_str!("../../fixtures/okx/tickers.json")).unwrap();
+
+		assert_eq!(tickers.len(), 1);
+		assert_eq!(tickers[0].symbol, "BTCUSDT");
+		assert!((tickers[0].price_change_percent - 2.0).abs() < 1e-9);
+		assert!((tickers[0].quote_volume - 102_000_000.0).abs() < 1e-6);
+		assert_eq!(tickers[0].time, 1_760_000_000_500);
+	}
+
+	#[test]
+	fn maps_intervals() {
+		assert_eq!(to_okx_bar(Interval::Hour1).unwrap(), "1H");
+		assert_eq!(to_okx_bar(Interval::Day1).unwrap(), "1Dutc");
+		assert!(to_okx_bar(Interval::Hour8).is_err());
+		assert_eq!(to_okx_stat_period(Interval::Minute5).unwrap(), "5m");
+		assert!(matches!(
+			ExchangeError::from(to_okx_stat_period(Interval::Minute1).unwrap_err()),
+			ExchangeError::Unsupported(_)
+		));
+	}
+}
@@ -0,0 +1,46 @@
+/// Open interest at one point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenInterestPoint {
+	/// Base asset amount held in open positions
+	pub open_interest: f64,
+	/// USD value of `open_interest`, not reported by Bybit
+	pub open_interest_usd: Option<f64>,
+	/// Milliseconds since epoch
+	pub timestamp: u64,
+}
+
+/// Percent change from the latest point at least `lookback_millis` older than the last one to the last one.
+/// `series` is ordered oldest → newest, `None` when it doesn't reach back that far.
+#[must_use]
+pub fn open_interest_change(series: &[OpenInterestPoint], lookback_millis: u64) -> Option<f64> {
+	let latest = series.last()?;
+	let cutoff = latest.timestamp.checked_sub(lookback_millis)?;
+	let previous = series.iter().rev().find(|point| point.timestamp <= cutoff)?;
+
+	if previous.open_interest == 0.0 {
+		return Some(0.0);
+	}
+
+	Some((latest.open_interest - previous.open_interest) / previous.open_interest * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn point(open_interest: f64, timestamp: u64) -> OpenInterestPoint {
+		OpenInterestPoint { open_interest, open_interest_usd: None, timestamp }
+	}
+
+	#[test]
+	fn changes_over_lookback() {
+		let series = [point(100.0, 0), point(110.0, 300_000), point(120.0, 600_000), point(132.0, 900_000)];
+
+		assert!((open_interest_change(&series, 300_000).unwrap() - 10.0).abs() < f64::EPSILON);
+		// Falls back to the closest older point
+		assert!((open_interest_change(&series, 400_000).unwrap() - 20.0).abs() < f64::EPSILON);
+		assert!((open_interest_change(&series, 900_000).unwrap() - 32.0).abs() < f64::EPSILON);
+		assert!(open_interest_change(&series, 900_001).is_none());
+		assert!(open_interest_change(&[], 0).is_none());
+	}
+}
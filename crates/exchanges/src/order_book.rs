@@ -0,0 +1,368 @@
+use std::{cmp::Ordering, collections::BTreeMap, future::Future};
+
+use anyhow::bail;
+use futures_util::{StreamExt, stream::BoxStream};
+use tracing::warn;
+
+use crate::StreamEvent;
+
+/// Price level, quantity in base asset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderBookLevel {
+	pub price: f64,
+	pub quantity: f64,
+}
+
+impl OrderBookLevel {
+	/// Quote asset value of the level
+	#[must_use]
+	pub fn notional(self) -> f64 {
+		self.price * self.quantity
+	}
+}
+
+/// Incremental book update. Levels replace the quantity at their price, zero quantity removes the level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthUpdate {
+	/// First update id covered by this event (`U` on Binance)
+	pub first_update_id: u64,
+	/// Last update id covered by this event (`u` on Binance)
+	pub final_update_id: u64,
+	/// Last update id of the previous event (`pu` on Binance)
+	pub previous_update_id: u64,
+	pub bids: Vec<OrderBookLevel>,
+	pub asks: Vec<OrderBookLevel>,
+	/// Event time, milliseconds since epoch
+	pub time: u64,
+}
+
+/// Resting quote volume on each side of the book
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderBookDepth {
+	/// Quote asset (USDT) value of bids
+	pub bid_notional: f64,
+	/// Quote asset (USDT) value of asks
+	pub ask_notional: f64,
+}
+
+/// Order book kept in sync by applying `DepthUpdate`s on top of a snapshot
+#[derive(Debug, Clone)]
+pub struct LocalOrderBook {
+	symbol: String,
+	last_update_id: u64,
+	/// Whether an update has been chained to the snapshot yet
+	bridged: bool,
+	bids: BTreeMap<Price, f64>,
+	asks: BTreeMap<Price, f64>,
+	/// Milliseconds since epoch of the snapshot or the last applied update
+	time: u64,
+}
+
+impl LocalOrderBook {
+	/// Book from a snapshot, `last_update_id` is the update the snapshot reflects
+	#[must_use]
+	pub fn from_snapshot(
+		symbol: impl Into<String>,
+		last_update_id: u64,
+		bids: impl IntoIterator<Item = OrderBookLevel>,
+		asks: impl IntoIterator<Item = OrderBookLevel>,
+		time: u64,
+	) -> Self {
+		let mut book = Self {
+			symbol: symbol.into(),
+			last_update_id,
+			bridged: false,
+			bids: BTreeMap::new(),
+			asks: BTreeMap::new(),
+			time,
+		};
+		apply_levels(&mut book.bids, bids);
+		apply_levels(&mut book.asks, asks);
+		book
+	}
+
+	#[must_use]
+	pub fn symbol(&self) -> &str {
+		&self.symbol
+	}
+
+	#[must_use]
+	pub const fn last_update_id(&self) -> u64 {
+		self.last_update_id
+	}
+
+	/// Milliseconds since epoch of the snapshot or the last applied update
+	#[must_use]
+	pub const fn time(&self) -> u64 {
+		self.time
+	}
+
+	/// Apply an update, returns `false` for updates already covered by the book.
+	/// Fails when updates were missed, the book has to be rebuilt from a new snapshot then.
+	pub fn apply(&mut self, update: &DepthUpdate) -> anyhow::Result<bool> {
+		if update.final_update_id < self.last_update_id {
+			return Ok(false);
+		}
+
+		// The first update after a REST snapshot straddles it, later ones chain onto the previous update
+		let continuous = update.previous_update_id == self.last_update_id
+			|| (!self.bridged && update.first_update_id <= self.last_update_id);
+		if !continuous {
+			bail!(
+				"{} order book is out of sync: expected update after {}, got {}..={}",
+				self.symbol,
+				self.last_update_id,
+				update.first_update_id,
+				update.final_update_id
+			);
+		}
+
+		apply_levels(&mut self.bids, update.bids.iter().copied());
+		apply_levels(&mut self.asks, update.asks.iter().copied());
+		self.last_update_id = update.final_update_id;
+		self.bridged = true;
+		self.time = update.time;
+		Ok(true)
+	}
+
+	/// Bids from best (highest) to worst
+	pub fn bids(&self) -> impl Iterator<Item = OrderBookLevel> + '_ {
+		self.bids.iter().rev().map(|(price, &quantity)| OrderBookLevel { price: price.0, quantity })
+	}
+
+	/// Asks from best (lowest) to worst
+	pub fn asks(&self) -> impl Iterator<Item = OrderBookLevel> + '_ {
+		self.asks.iter().map(|(price, &quantity)| OrderBookLevel { price: price.0, quantity })
+	}
+
+	#[must_use]
+	pub fn best_bid(&self) -> Option<OrderBookLevel> {
+		self.bids().next()
+	}
+
+	#[must_use]
+	pub fn best_ask(&self) -> Option<OrderBookLevel> {
+		self.asks().next()
+	}
+
+	#[must_use]
+	pub fn mid_price(&self) -> Option<f64> {
+		Some(f64::midpoint(self.best_bid()?.price, self.best_ask()?.price))
+	}
+
+	/// Best ask minus best bid
+	#[must_use]
+	pub fn spread(&self) -> Option<f64> {
+		Some(self.best_ask()?.price - self.best_bid()?.price)
+	}
+
+	/// Spread relative to the mid price, e.g. `0.05` for 0.05%
+	#[must_use]
+	pub fn spread_percent(&self) -> Option<f64> {
+		Some(self.spread()? / self.mid_price()? * 100.0)
+	}
+
+	/// Cumulative depth of levels priced within `percent` of the mid price, e.g. `1.0` for ±1%
+	#[must_use]
+	pub fn depth_within(&self, percent: f64) -> Option<OrderBookDepth> {
+		let mid_price = self.mid_price()?;
+		let lowest_bid = mid_price * (1.0 - percent / 100.0);
+		let highest_ask = mid_price * (1.0 + percent / 100.0);
+
+		Some(OrderBookDepth {
+			bid_notional: self.bids().take_while(|level| level.price >= lowest_bid).map(OrderBookLevel::notional).sum(),
+			ask_notional: self.asks().take_while(|level| level.price <= highest_ask).map(OrderBookLevel::notional).sum(),
+		})
+	}
+}
+
+/// Maintain a book from diff events: once an update arrives without a synced book a snapshot is fetched
+/// while later updates queue up in the stream, then updates older than the snapshot are dropped and the rest applied.
+/// Sequence gaps and reconnects drop the book, so it is rebuilt from a fresh snapshot.
+/// Yields a copy of the book after every applied update.
+pub fn synced_order_book<F, Fut>(
+	updates: BoxStream<'static, StreamEvent<DepthUpdate>>,
+	fetch_snapshot: F,
+) -> BoxStream<'static, StreamEvent<LocalOrderBook>>
+where
+	F: Fn() -> Fut + Send + 'static,
+	Fut: Future<Output = anyhow::Result<LocalOrderBook>> + Send,
+{
+	futures_util::stream::unfold((updates, fetch_snapshot, None), |(mut updates, fetch_snapshot, mut book)| async move {
+		loop {
+			let update = match updates.next().await? {
+				StreamEvent::Data(update) => update,
+				StreamEvent::Reconnected { disconnected_at, reconnected_at } => {
+					return Some((StreamEvent::Reconnected { disconnected_at, reconnected_at }, (updates, fetch_snapshot, None)));
+				},
+			};
+
+			let synced: &mut LocalOrderBook = match &mut book {
+				Some(book) => book,
+				None => match fetch_snapshot().await {
+					Ok(snapshot) => book.insert(snapshot),
+					Err(error) => {
+						warn!("Failed to fetch order book snapshot: {error:#}");
+						continue;
+					},
+				},
+			};
+
+			match synced.apply(&update) {
+				Ok(true) => {
+					let event = StreamEvent::Data(synced.clone());
+					return Some((event, (updates, fetch_snapshot, book)));
+				},
+				Ok(false) => {},
+				Err(error) => {
+					warn!("{error:#}, resyncing");
+					book = None;
+				},
+			}
+		}
+	})
+	.boxed()
+}
+
+/// Replace the quantity at each level's price, zero quantity removes the level
+fn apply_levels(side: &mut BTreeMap<Price, f64>, levels: impl IntoIterator<Item = OrderBookLevel>) {
+	for level in levels {
+		if level.quantity == 0.0 {
+			side.remove(&Price(level.price));
+		} else {
+			side.insert(Price(level.price), level.quantity);
+		}
+	}
+}
+
+/// Totally ordered price key, exchanges never send NaN prices
+#[derive(Debug, Clone, Copy)]
+struct Price(f64);
+
+impl PartialEq for Price {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Price {}
+
+impl PartialOrd for Price {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Price {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.0.total_cmp(&other.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::{
+		Arc,
+		atomic::{AtomicU64, Ordering as AtomicOrdering},
+	};
+
+	use super::*;
+
+	fn level(price: f64, quantity: f64) -> OrderBookLevel {
+		OrderBookLevel { price, quantity }
+	}
+
+	fn update(first_update_id: u64, final_update_id: u64, previous_update_id: u64) -> DepthUpdate {
+		DepthUpdate { first_update_id, final_update_id, previous_update_id, bids: Vec::new(), asks: Vec::new(), time: 0 }
+	}
+
+	fn book() -> LocalOrderBook {
+		LocalOrderBook::from_snapshot(
+			"BTCUSDT",
+			100,
+			[level(99.0, 1.0), level(98.0, 2.0), level(90.0, 10.0)],
+			[level(101.0, 1.0), level(102.0, 3.0), level(110.0, 10.0)],
+			0,
+		)
+	}
+
+	#[test]
+	fn exposes_top_of_book() {
+		let book = book();
+
+		assert_eq!(book.best_bid(), Some(level(99.0, 1.0)));
+		assert_eq!(book.best_ask(), Some(level(101.0, 1.0)));
+		assert_eq!(book.mid_price(), Some(100.0));
+		assert_eq!(book.spread(), Some(2.0));
+		assert_eq!(book.spread_percent(), Some(2.0));
+	}
+
+	#[test]
+	fn sums_depth_around_mid() {
+		let depth = book().depth_within(2.0).unwrap();
+
+		assert!((depth.bid_notional - 295.0).abs() < f64::EPSILON);
+		assert!((depth.ask_notional - 407.0).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn applies_chained_updates() {
+		let mut book = book();
+
+		// Already covered by the snapshot
+		assert!(!book.apply(&update(90, 99, 89)).unwrap());
+
+		let first = DepthUpdate { bids: vec![level(99.0, 0.0), level(99.5, 4.0)], ..update(95, 105, 94) };
+		assert!(book.apply(&first).unwrap());
+		assert_eq!(book.best_bid(), Some(level(99.5, 4.0)));
+		assert_eq!(book.bids().count(), 3);
+
+		let second = DepthUpdate { asks: vec![level(100.5, 1.0)], ..update(106, 110, 105) };
+		assert!(book.apply(&second).unwrap());
+		assert_eq!(book.best_ask(), Some(level(100.5, 1.0)));
+		assert_eq!(book.last_update_id(), 110);
+	}
+
+	#[test]
+	fn rejects_sequence_gaps() {
+		let mut book = book();
+
+		// Snapshot taken before the first buffered update
+		assert!(book.apply(&update(101, 105, 100)).is_ok());
+		// Previous update id doesn't match the last applied one
+		assert!(book.apply(&update(110, 115, 109)).is_err());
+		assert_eq!(book.last_update_id(), 105);
+	}
+
+	#[tokio::test]
+	async fn resyncs_after_gap() {
+		let updates = futures_util::stream::iter([
+			// Triggers the first snapshot, which already covers it
+			StreamEvent::Data(update(95, 99, 94)),
+			StreamEvent::Data(update(98, 102, 97)),
+			StreamEvent::Data(update(103, 104, 102)),
+			// 105..=107 were missed
+			StreamEvent::Data(update(108, 110, 107)),
+			StreamEvent::Data(update(111, 112, 110)),
+		])
+		.boxed();
+
+		// Every snapshot request sees the book 10 updates further
+		let snapshots = Arc::new(AtomicU64::new(100));
+		let books: Vec<_> = synced_order_book(updates, move || {
+			let last_update_id = snapshots.fetch_add(10, AtomicOrdering::SeqCst);
+			async move { Ok(LocalOrderBook::from_snapshot("BTCUSDT", last_update_id, [], [], 0)) }
+		})
+		.collect()
+		.await;
+
+		let update_ids: Vec<_> = books
+			.iter()
+			.map(|event| match event {
+				StreamEvent::Data(book) => book.last_update_id(),
+				StreamEvent::Reconnected { .. } => 0,
+			})
+			.collect();
+		assert_eq!(update_ids, vec![102, 104, 112]);
+	}
+}
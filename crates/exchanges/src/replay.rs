@@ -0,0 +1,333 @@
+use std::{
+	collections::HashMap,
+	future::Future,
+	path::PathBuf,
+	sync::{
+		Arc,
+		atomic::{AtomicU64, Ordering},
+	},
+};
+
+use anyhow::Context;
+use futures_util::stream::BoxStream;
+use tokio::{
+	fs::File,
+	io::{AsyncBufReadExt, BufReader},
+	sync::mpsc,
+	task::futures::TaskLocalFuture,
+	time::{Duration, Instant},
+};
+
+use crate::{
+	CandleInfo, Exchange, ExchangeError, FundingRateInfo, Interval, LocalOrderBook, LongShortRatio, LongShortRatioKind,
+	MarkPriceInfo, MarketLiquidationsInfo, OpenInterestInfo, OpenInterestPoint, StreamEvent, SymbolInfo, TakerVolume,
+	TickerInfo, TradeInfo, capture::CapturedFrame,
+};
+
+tokio::task_local! {
+	/// Set while a `ReplayExchange` opens streams or makes REST calls, so both read the capture instead of connecting
+	static REPLAY: Arc<Replay>;
+}
+
+/// Capture being played back, shared by every stream of one `ReplayExchange`
+pub struct Replay {
+	files: Vec<PathBuf>,
+	speed: f64,
+	/// Receive time of the first captured frame, milliseconds since epoch
+	origin: u64,
+	started: Instant,
+	/// Receive time of the latest frame sent by any stream, milliseconds since epoch
+	played: AtomicU64,
+	/// Captured REST response bodies by request URL, in capture order
+	responses: HashMap<String, Vec<(u64, String)>>,
+}
+
+impl Replay {
+	/// Replay of the `reconnecting_stream` being set up or the REST call being made, if any
+	pub fn current() -> Option<Arc<Self>> {
+		REPLAY.try_with(Arc::clone).ok()
+	}
+
+	/// Run `future` with `self` as the current replay
+	pub fn scope<F: Future>(self: Arc<Self>, future: F) -> TaskLocalFuture<Arc<Self>, F> {
+		REPLAY.scope(self, future)
+	}
+
+	/// Body of the GET request to `url` as last captured before the replay position,
+	/// or the first capture of it if the position is earlier
+	pub fn response(&self, url: &str) -> anyhow::Result<String> {
+		let captured = self.responses.get(url).context(format!("No captured response for {url}"))?;
+		let position = self.position();
+		let (_, body) = captured.iter().rev().find(|(received_at, _)| *received_at <= position).unwrap_or(&captured[0]);
+
+		Ok(body.clone())
+	}
+
+	/// Parse frames captured from `url` and send them at their original pace scaled by `speed`.
+	/// Venues serving every channel on one endpoint rely on `parse` skipping frames of other subscriptions.
+	/// Frames the clock has passed or that are no later than the last frame sent are skipped, so a stream opened
+	/// mid-replay joins where the others are like a live subscriber would. Returns once the capture is exhausted
+	/// or the receiver is dropped.
+	pub async fn play<T, P>(&self, url: &str, mut parse: P, events: &mpsc::Sender<StreamEvent<T>>) -> anyhow::Result<()>
+	where
+		P: FnMut(&str) -> anyhow::Result<Vec<T>>,
+	{
+		let clock = self.clock();
+		let played = self.played.load(Ordering::Relaxed);
+
+		for path in &self.files {
+			let file = File::open(path).await.context(format!("Failed to open capture file {}", path.display()))?;
+			let mut lines = BufReader::new(file).lines();
+
+			while let Some(line) = lines.next_line().await? {
+				let frame: CapturedFrame =
+					serde_json::from_str(&line).context(format!("Malformed frame in {}", path.display()))?;
+				if frame.url != url || frame.received_at < clock || frame.received_at <= played {
+					continue;
+				}
+
+				tokio::time::sleep_until(self.deadline(frame.received_at)).await;
+				self.played.fetch_max(frame.received_at, Ordering::Relaxed);
+
+				let Ok(items) = parse(&frame.frame) else {
+					continue;
+				};
+
+				for item in items {
+					if events.send(StreamEvent::Data(item)).await.is_err() {
+						return Ok(());
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Capture time the replay has reached, by the clock and by the frames already sent.
+	/// Without a clock, i.e. at infinite speed, only the frames sent count.
+	fn position(&self) -> u64 {
+		self.clock().max(self.played.load(Ordering::Relaxed))
+	}
+
+	/// Capture time by the clock alone, `origin` at infinite speed
+	fn clock(&self) -> u64 {
+		let elapsed = Duration::try_from_secs_f64(self.started.elapsed().as_secs_f64() * self.speed);
+		self.origin + elapsed.map_or(0, |elapsed| elapsed.as_millis() as u64)
+	}
+
+	fn deadline(&self, received_at: u64) -> Instant {
+		let offset = received_at.saturating_sub(self.origin) as f64 / 1000.0 / self.speed;
+		self.started + Duration::from_secs_f64(offset)
+	}
+}
+
+/// Plays captured websocket frames (see `record_frames`) through the stream methods of `inner`,
+/// e.g. to reproduce a market day offline.
+///
+/// REST calls, including those made by stream setup, are answered with the captured responses of the same URL
+/// and fail when the request was never captured.
+#[derive(Clone)]
+pub struct ReplayExchange<E> {
+	inner: E,
+	replay: Arc<Replay>,
+}
+
+impl<E> ReplayExchange<E> {
+	/// Replay `files` in order. `speed` scales the captured pace, `1.0` is real time and `f64::INFINITY`
+	/// sends frames as fast as they are consumed. Every stream follows the same clock, started here.
+	pub async fn open(inner: E, files: Vec<PathBuf>, speed: f64) -> anyhow::Result<Self> {
+		anyhow::ensure!(speed > 0.0, "Replay speed must be positive, got {speed}");
+
+		let first = files.first().context("No capture files to replay")?;
+		let file = File::open(first).await.context(format!("Failed to open capture file {}", first.display()))?;
+		let line =
+			BufReader::new(file).lines().next_line().await?.context(format!("Empty capture file {}", first.display()))?;
+		let origin = serde_json::from_str::<CapturedFrame>(&line)?.received_at;
+		let responses = captured_responses(&files).await?;
+
+		let replay = Replay { files, speed, origin, started: Instant::now(), played: AtomicU64::new(0), responses };
+		Ok(Self { inner, replay: Arc::new(replay) })
+	}
+
+	/// `inner` answered from the same capture and following the same clock, e.g. one venue of an aggregated client
+	pub fn replaying<F>(&self, inner: F) -> ReplayExchange<F> {
+		ReplayExchange { inner, replay: Arc::clone(&self.replay) }
+	}
+
+	fn replayed<T>(
+		&self,
+		open: impl FnOnce() -> BoxStream<'static, StreamEvent<T>>,
+	) -> BoxStream<'static, StreamEvent<T>> {
+		REPLAY.sync_scope(Arc::clone(&self.replay), open)
+	}
+
+	fn answered<F: Future>(&self, request: F) -> TaskLocalFuture<Arc<Replay>, F> {
+		Arc::clone(&self.replay).scope(request)
+	}
+}
+
+/// REST responses among the frames of `files`, keyed by request URL
+async fn captured_responses(files: &[PathBuf]) -> anyhow::Result<HashMap<String, Vec<(u64, String)>>> {
+	let mut responses: HashMap<String, Vec<(u64, String)>> = HashMap::new();
+
+	for path in files {
+		let file = File::open(path).await.context(format!("Failed to open capture file {}", path.display()))?;
+		let mut lines = BufReader::new(file).lines();
+
+		while let Some(line) = lines.next_line().await? {
+			// Cheap check first, websocket frames make up nearly all of a capture
+			if !line.contains(r#""url":"http"#) {
+				continue;
+			}
+
+			let frame: CapturedFrame =
+				serde_json::from_str(&line).context(format!("Malformed frame in {}", path.display()))?;
+			responses.entry(frame.url).or_default().push((frame.received_at, frame.frame));
+		}
+	}
+
+	Ok(responses)
+}
+
+#[async_trait::async_trait]
+impl<E> Exchange for ReplayExchange<E>
+where
+	E: Exchange + Send + Sync,
+{
+	async fn get_all_usdt_pairs(&self) -> Result<Vec<String>, ExchangeError> {
+		self.answered(self.inner.get_all_usdt_pairs()).await
+	}
+
+	async fn get_symbols(&self) -> Result<Vec<SymbolInfo>, ExchangeError> {
+		self.answered(self.inner.get_symbols()).await
+	}
+
+	async fn get_symbol_info(&self, symbol: &str) -> Result<SymbolInfo, ExchangeError> {
+		self.answered(self.inner.get_symbol_info(symbol)).await
+	}
+
+	fn watch_market_liquidations(&self) -> BoxStream<'static, StreamEvent<MarketLiquidationsInfo>> {
+		self.replayed(|| self.inner.watch_market_liquidations())
+	}
+
+	fn watch_tickers(&self) -> BoxStream<'static, StreamEvent<TickerInfo>> {
+		self.replayed(|| self.inner.watch_tickers())
+	}
+
+	fn watch_mark_prices(&self) -> BoxStream<'static, StreamEvent<MarkPriceInfo>> {
+		self.replayed(|| self.inner.watch_mark_prices())
+	}
+
+	fn watch_trades(&self, symbol: &str) -> BoxStream<'static, StreamEvent<TradeInfo>> {
+		self.replayed(|| self.inner.watch_trades(symbol))
+	}
+
+	async fn get_klines(&self, symbol: &str, interval: Interval, limit: u32) -> Result<Vec<CandleInfo>, ExchangeError> {
+		self.answered(self.inner.get_klines(symbol, interval, limit)).await
+	}
+
+	async fn get_klines_range(
+		&self,
+		symbol: &str,
+		interval: Interval,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<CandleInfo>, ExchangeError> {
+		self.answered(self.inner.get_klines_range(symbol, interval, from, to)).await
+	}
+
+	async fn get_open_interest_info(&self, symbol: &str) -> Result<OpenInterestInfo, ExchangeError> {
+		self.answered(self.inner.get_open_interest_info(symbol)).await
+	}
+
+	async fn get_open_interest_history(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<OpenInterestPoint>, ExchangeError> {
+		self.answered(self.inner.get_open_interest_history(symbol, period, limit)).await
+	}
+
+	async fn get_order_book(&self, symbol: &str, limit: u32) -> Result<LocalOrderBook, ExchangeError> {
+		self.answered(self.inner.get_order_book(symbol, limit)).await
+	}
+
+	async fn get_funding_rate_info(&self, symbol: &str) -> Result<FundingRateInfo, ExchangeError> {
+		self.answered(self.inner.get_funding_rate_info(symbol)).await
+	}
+
+	async fn get_long_short_ratio(
+		&self,
+		symbol: &str,
+		kind: LongShortRatioKind,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<LongShortRatio>, ExchangeError> {
+		self.answered(self.inner.get_long_short_ratio(symbol, kind, period, limit)).await
+	}
+
+	async fn get_taker_volume(
+		&self,
+		symbol: &str,
+		period: Interval,
+		limit: u32,
+	) -> Result<Vec<TakerVolume>, ExchangeError> {
+		self.answered(self.inner.get_taker_volume(symbol, period, limit)).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use futures_util::StreamExt;
+
+	use super::*;
+	use crate::BinanceExchange;
+
+	const FORCE_ORDER: &str = include_str!("../fixtures/binance/force_order.json");
+	const LIQUIDATIONS_URL: &str = "wss://fstream.binance.com/ws/!forceOrder@arr";
+
+	#[tokio::test]
+	async fn replays_captured_frames() {
+		let path = std::env::temp_dir().join(format!("capture-test-{}.jsonl", fastrand::u64(..)));
+		let frames: Vec<String> =
+			[(1_000, LIQUIDATIONS_URL), (1_500, "wss://fstream.binance.com/ws/!ticker@arr"), (2_000, LIQUIDATIONS_URL)]
+				.into_iter()
+				.map(|(received_at, url)| {
+					let frame = CapturedFrame { received_at, url: String::from(url), frame: String::from(FORCE_ORDER) };
+					serde_json::to_string(&frame).unwrap()
+				})
+				.collect();
+		tokio::fs::write(&path, frames.join("\n")).await.unwrap();
+
+		let exchange = ReplayExchange::open(BinanceExchange::new(), vec![path.clone()], f64::INFINITY).await.unwrap();
+		let events: Vec<_> = exchange.watch_market_liquidations().collect().await;
+		// Opened after the capture was played up to the last frame, which must not be sent again
+		let late_events: Vec<_> = exchange.watch_market_liquidations().collect().await;
+		tokio::fs::remove_file(&path).await.unwrap();
+
+		assert_eq!(events.len(), 2);
+		assert!(matches!(&events[0], StreamEvent::Data(liquidation) if liquidation.symbol == "BTCUSDT"));
+		assert!(late_events.is_empty());
+	}
+
+	#[tokio::test]
+	async fn answers_rest_calls_from_capture() {
+		let path = std::env::temp_dir().join(format!("capture-test-{}.jsonl", fastrand::u64(..)));
+		let frame = CapturedFrame {
+			received_at: 1_000,
+			url: String::from("https://fapi.binance.com/fapi/v1/exchangeInfo"),
+			frame: String::from(include_str!("../fixtures/binance/exchange_info.json")),
+		};
+		tokio::fs::write(&path, serde_json::to_string(&frame).unwrap()).await.unwrap();
+
+		let exchange = ReplayExchange::open(BinanceExchange::new(), vec![path.clone()], f64::INFINITY).await.unwrap();
+		let symbols = exchange.get_symbols().await;
+		let klines = exchange.get_klines("BTCUSDT", Interval::Minute5, 10).await;
+		tokio::fs::remove_file(&path).await.unwrap();
+
+		assert!(symbols.unwrap().iter().any(|info| info.symbol == "BTCUSDT"));
+		assert!(klines.is_err());
+	}
+}
@@ -0,0 +1,284 @@
+use std::{future::Future, sync::Arc};
+
+use anyhow::Context;
+use futures_util::{SinkExt, StreamExt, stream::BoxStream};
+use tokio::{
+	sync::mpsc,
+	time::{Duration, Instant},
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{error, warn};
+
+use crate::{capture, replay::Replay, utils::now_millis};
+
+const HOURS_24: Duration = Duration::from_hours(24);
+/// Delay before reconnecting after a failed or closed session
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const CHANNEL_CAPACITY: usize = 4096;
+
+/// Item of a websocket-backed stream
+#[derive(Debug, Clone)]
+pub enum StreamEvent<T> {
+	Data(T),
+	/// The connection dropped and was re-established, events in between were missed.
+	/// Timestamps are milliseconds since epoch.
+	Reconnected {
+		disconnected_at: u64,
+		reconnected_at: u64,
+	},
+}
+
+/// How a connection is kept alive
+pub enum Heartbeat {
+	/// Websocket ping frames, answered with pong frames
+	PingFrame,
+	/// Application level text ping, `is_pong` recognizes the reply
+	Text { ping: String, is_pong: fn(&str) -> bool },
+}
+
+/// Why a session ended without an error
+enum SessionEnd {
+	/// Rotated after 24 hours or the stream was dropped, reconnecting right away isn't a gap worth reporting
+	Planned,
+	/// The server closed the connection or it ended
+	Closed,
+}
+
+/// Everything needed to open one websocket session
+pub struct Subscription<P> {
+	pub url: String,
+	/// Sent right after connecting
+	pub messages: Vec<String>,
+	pub heartbeat: Heartbeat,
+	pub ping_every: Duration,
+	pub pong_timeout: Duration,
+	/// Decodes a text frame, frames that fail to decode are skipped
+	pub parse: P,
+}
+
+/// Run a websocket subscription in the background, reconnecting every 24 hours and `reconnect_delay` after errors
+/// or a closed connection.
+/// `setup` is called before each connection, so it can refresh symbols or other REST-derived state.
+/// The background task stops once the returned stream is dropped.
+/// Within a `ReplayExchange` the captured frames of the subscription are played once instead,
+/// with `setup` answered from the captured REST responses.
+pub fn reconnecting_stream<T, S, Fut, P>(reconnect_delay: Duration, mut setup: S) -> BoxStream<'static, StreamEvent<T>>
+where
+	T: Send + 'static,
+	S: FnMut() -> Fut + Send + 'static,
+	Fut: Future<Output = anyhow::Result<Subscription<P>>> + Send,
+	P: FnMut(&str) -> anyhow::Result<Vec<T>> + Send,
+{
+	let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+	if let Some(replay) = Replay::current() {
+		tokio::spawn(async move {
+			let result = match Arc::clone(&replay).scope(setup()).await {
+				Ok(subscription) => replay.play(&subscription.url, subscription.parse, &tx).await,
+				Err(e) => Err(e),
+			};
+
+			if let Err(e) = result {
+				error!("Replay error: {e:#}");
+			}
+		});
+
+		return futures_util::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|event| (event, rx)) }).boxed();
+	}
+
+	tokio::spawn(async move {
+		let mut disconnected_at = None;
+
+		while !tx.is_closed() {
+			let result = match setup().await {
+				Ok(subscription) => run_session(subscription, &tx, &mut disconnected_at).await,
+				Err(e) => Err(e),
+			};
+
+			match result {
+				Ok(SessionEnd::Planned) => continue,
+				Ok(SessionEnd::Closed) => warn!("Stream closed, reconnecting in {}s...", reconnect_delay.as_secs_f64()),
+				Err(e) => error!("Stream error: {}, reconnecting in {}s...", e, reconnect_delay.as_secs_f64()),
+			}
+
+			// Servers closing right after the handshake would otherwise be reconnected to in a tight loop
+			disconnected_at.get_or_insert_with(now_millis);
+			tokio::time::sleep(reconnect_delay).await;
+		}
+	});
+
+	futures_util::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|event| (event, rx)) }).boxed()
+}
+
+async fn run_session<T, P>(
+	mut subscription: Subscription<P>,
+	events: &mpsc::Sender<StreamEvent<T>>,
+	disconnected_at: &mut Option<u64>,
+) -> anyhow::Result<SessionEnd>
+where
+	P: FnMut(&str) -> anyhow::Result<Vec<T>>,
+{
+	let (ws, _) = connect_async(subscription.url.as_str()).await.context("Failed to connect")?;
+	let (mut tx, mut rx) = ws.split();
+
+	for message in subscription.messages.drain(..) {
+		tx.send(Message::Text(message.into())).await?;
+	}
+
+	if let Some(disconnected_at) = disconnected_at.take() {
+		warn!("Stream reconnected to {}", subscription.url);
+		let event = StreamEvent::Reconnected { disconnected_at, reconnected_at: now_millis() };
+		if events.send(event).await.is_err() {
+			return Ok(SessionEnd::Planned);
+		}
+	}
+
+	let start = Instant::now();
+	let mut last_ping = Instant::now();
+	let mut last_pong = Instant::now();
+
+	loop {
+		if start.elapsed() >= HOURS_24 || events.is_closed() {
+			let _ = tx.send(Message::Close(None)).await;
+			return Ok(SessionEnd::Planned);
+		}
+
+		if last_ping.elapsed() >= subscription.ping_every {
+			let ping = match &subscription.heartbeat {
+				Heartbeat::PingFrame => Message::Ping(vec![].into()),
+				Heartbeat::Text { ping, .. } => Message::Text(ping.as_str().into()),
+			};
+			tx.send(ping).await?;
+			last_ping = Instant::now();
+		}
+
+		if last_pong.elapsed() > subscription.pong_timeout {
+			return Err(anyhow::anyhow!("Server timeout"));
+		}
+
+		match tokio::time::timeout(subscription.ping_every, rx.next()).await {
+			Ok(Some(Ok(Message::Text(text)))) => {
+				if let Heartbeat::Text { is_pong, .. } = &subscription.heartbeat
+					&& is_pong(&text)
+				{
+					last_pong = Instant::now();
+					continue;
+				}
+
+				capture::record(&subscription.url, &text);
+
+				let Ok(items) = (subscription.parse)(&text) else {
+					continue;
+				};
+
+				for item in items {
+					if events.send(StreamEvent::Data(item)).await.is_err() {
+						return Ok(SessionEnd::Planned);
+					}
+				}
+			},
+			Ok(Some(Ok(Message::Pong(_)))) => last_pong = Instant::now(),
+			Ok(Some(Ok(Message::Ping(p)))) => {
+				tx.send(Message::Pong(p)).await?;
+				last_pong = Instant::now();
+			},
+			Ok(Some(Ok(Message::Close(_))) | None) => return Ok(SessionEnd::Closed),
+			Ok(Some(Err(e))) => return Err(e.into()),
+			_ => {},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::{MockServer, WsAction};
+
+	#[tokio::test]
+	async fn reconnects_and_reports_gap() {
+		let server = MockServer::start().await.unwrap();
+		server.session(vec![WsAction::Text(String::from("1")), WsAction::Close]);
+		server.session(vec![WsAction::Text(String::from("2"))]);
+		let url = String::from(server.ws_url());
+
+		let events = reconnecting_stream(Duration::from_millis(50), move || {
+			let url = url.clone();
+			async move {
+				Ok(Subscription {
+					url,
+					messages: Vec::new(),
+					heartbeat: Heartbeat::PingFrame,
+					ping_every: Duration::from_secs(1),
+					pong_timeout: Duration::from_secs(5),
+					parse: |text: &str| Ok(vec![text.parse::<u32>()?]),
+				})
+			}
+		});
+
+		let events: Vec<_> = events.take(3).collect().await;
+
+		assert!(matches!(events[0], StreamEvent::Data(1)));
+		assert!(matches!(
+			events[1],
+			StreamEvent::Reconnected { disconnected_at, reconnected_at } if disconnected_at <= reconnected_at
+		));
+		assert!(matches!(events[2], StreamEvent::Data(2)));
+	}
+
+	#[tokio::test]
+	async fn waits_before_reconnecting_to_closing_server() {
+		let server = MockServer::start().await.unwrap();
+		server.session(vec![WsAction::Close]);
+		server.session(vec![WsAction::Close]);
+		server.session(vec![WsAction::Text(String::from("1"))]);
+		let url = String::from(server.ws_url());
+		let started = Instant::now();
+
+		let events = reconnecting_stream(Duration::from_millis(200), move || {
+			let url = url.clone();
+			async move {
+				Ok(Subscription {
+					url,
+					messages: Vec::new(),
+					heartbeat: Heartbeat::PingFrame,
+					ping_every: Duration::from_secs(1),
+					pong_timeout: Duration::from_secs(5),
+					parse: |text: &str| Ok(vec![text.parse::<u32>()?]),
+				})
+			}
+		});
+
+		let events: Vec<_> = events.take(3).collect().await;
+
+		assert!(started.elapsed() >= Duration::from_millis(400));
+		assert!(matches!(events[0], StreamEvent::Reconnected { .. }));
+		assert!(matches!(events[1], StreamEvent::Reconnected { .. }));
+		assert!(matches!(events[2], StreamEvent::Data(1)));
+	}
+
+	#[tokio::test]
+	async fn sends_subscription_messages() {
+		let server = MockServer::start().await.unwrap();
+		let url = String::from(server.ws_url());
+
+		let mut events = reconnecting_stream(RECONNECT_DELAY, move || {
+			let url = url.clone();
+			async move {
+				Ok(Subscription {
+					url,
+					messages: vec![String::from("subscribe")],
+					heartbeat: Heartbeat::Text { ping: String::from("ping"), is_pong: |text| text == "pong" },
+					ping_every: Duration::from_millis(50),
+					pong_timeout: Duration::from_secs(5),
+					parse: |_: &str| Ok(Vec::<()>::new()),
+				})
+			}
+		});
+
+		tokio::time::timeout(Duration::from_millis(300), events.next()).await.unwrap_err();
+
+		let received = server.received();
+		assert_eq!(received[0], "subscribe");
+		assert!(received[1..].iter().all(|text| text == "ping"));
+	}
+}
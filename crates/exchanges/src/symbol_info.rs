@@ -0,0 +1,179 @@
+use std::{future::Future, sync::Arc};
+
+use tokio::{
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+use crate::ExchangeError;
+
+/// How long `Exchange::get_symbols` serves cached metadata before fetching it again
+pub const SYMBOLS_TTL: Duration = Duration::from_mins(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContractKind {
+	Perpetual,
+	/// Settles at a fixed delivery date
+	Delivery,
+	/// Spot pair, listed by spot markets only
+	Spot,
+	Unknown,
+}
+
+/// Trading status, exchange specific states are folded into the closest one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolStatus {
+	/// Announced but not open for trading yet
+	PreTrading,
+	Trading,
+	/// Trading halted temporarily
+	Suspended,
+	/// Delivering or settling before delisting
+	Settling,
+	Closed,
+	Unknown,
+}
+
+/// Static metadata of a futures contract or spot pair
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+	/// Normalized symbol, e.g. `BTCUSDT`
+	pub symbol: String,
+	pub base_asset: String,
+	pub quote_asset: String,
+	pub contract_kind: ContractKind,
+	/// Listing time, milliseconds since epoch
+	pub onboard_date: u64,
+	/// Minimum price increment
+	pub tick_size: f64,
+	/// Minimum quantity increment in base asset, in contracts for inverse contracts
+	pub step_size: f64,
+	/// Minimum order value in quote asset, not reported by OKX
+	pub min_notional: Option<f64>,
+	/// Size of one contract where quantities are reported in contracts: quote value of an inverse contract,
+	/// e.g. 100 USD for Binance `BTCUSD_PERP`, or base quantity of an OKX swap. `None` otherwise.
+	pub contract_size: Option<f64>,
+	pub status: SymbolStatus,
+}
+
+impl SymbolInfo {
+	#[must_use]
+	pub fn is_trading(&self) -> bool {
+		self.status == SymbolStatus::Trading
+	}
+
+	/// Listed less than `window_millis` before `now` (milliseconds since epoch)
+	#[must_use]
+	pub const fn is_new_listing(&self, now: u64, window_millis: u64) -> bool {
+		now.saturating_sub(self.onboard_date) < window_millis
+	}
+
+	/// Decimals of `tick_size`, e.g. 2 for `0.01`
+	#[must_use]
+	pub fn price_precision(&self) -> usize {
+		decimals(self.tick_size)
+	}
+
+	/// Decimals of `step_size`
+	#[must_use]
+	pub fn quantity_precision(&self) -> usize {
+		decimals(self.step_size)
+	}
+
+	/// `price` rounded to the tick size, formatted with `price_precision` decimals
+	#[must_use]
+	pub fn format_price(&self, price: f64) -> String {
+		let price = if self.tick_size > 0.0 { (price / self.tick_size).round() * self.tick_size } else { price };
+
+		format!("{price:.precision$}", precision = self.price_precision())
+	}
+}
+
+/// Display of `f64` never uses exponents, so the decimals can be counted from it
+fn decimals(step: f64) -> usize {
+	step.to_string().split_once('.').map_or(0, |(_, fraction)| fraction.len())
+}
+
+/// Symbol metadata shared by clones of an exchange, fetched again once older than `SYMBOLS_TTL`
+#[derive(Clone, Default)]
+pub struct SymbolCache {
+	state: Arc<Mutex<Option<CachedSymbols>>>,
+}
+
+struct CachedSymbols {
+	fetched_at: Instant,
+	symbols: Vec<SymbolInfo>,
+}
+
+impl SymbolCache {
+	/// The lock is held while fetching, so concurrent callers share a single request
+	#[allow(clippy::significant_drop_tightening)]
+	pub async fn get_or_fetch<F, Fut>(&self, fetch: F) -> Result<Vec<SymbolInfo>, ExchangeError>
+	where
+		F: FnOnce() -> Fut,
+		Fut: Future<Output = anyhow::Result<Vec<SymbolInfo>>>,
+	{
+		let mut state = self.state.lock().await;
+
+		if let Some(cached) = state.as_ref()
+			&& cached.fetched_at.elapsed() < SYMBOLS_TTL
+		{
+			return Ok(cached.symbols.clone());
+		}
+
+		let symbols = fetch().await?;
+		*state = Some(CachedSymbols { fetched_at: Instant::now(), symbols: symbols.clone() });
+
+		Ok(symbols)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::{AtomicU32, Ordering};
+
+	use super::*;
+
+	fn symbol(tick_size: f64, step_size: f64) -> SymbolInfo {
+		SymbolInfo {
+			symbol: String::from("BTCUSDT"),
+			base_asset: String::from("BTC"),
+			quote_asset: String::from("USDT"),
+			contract_kind: ContractKind::Perpetual,
+			onboard_date: 1_000,
+			tick_size,
+			step_size,
+			min_notional: Some(5.0),
+			contract_size: None,
+			status: SymbolStatus::Trading,
+		}
+	}
+
+	#[test]
+	fn formats_prices_to_tick_size() {
+		let info = symbol(0.1, 0.001);
+
+		assert_eq!(info.price_precision(), 1);
+		assert_eq!(info.quantity_precision(), 3);
+		assert_eq!(info.format_price(96_123.456), "96123.5");
+		assert_eq!(symbol(0.00001, 1.0).format_price(0.123_456_7), "0.12346");
+		assert_eq!(symbol(0.5, 1.0).format_price(10.3), "10.5");
+		assert_eq!(symbol(1.0, 1.0).quantity_precision(), 0);
+		assert!(info.is_new_listing(2_000, 1_001));
+		assert!(!info.is_new_listing(2_000, 1_000));
+	}
+
+	#[tokio::test]
+	async fn caches_symbols() {
+		let cache = SymbolCache::default();
+		let fetches = AtomicU32::new(0);
+		let fetch = || async {
+			fetches.fetch_add(1, Ordering::Relaxed);
+			Ok(vec![symbol(0.1, 0.001)])
+		};
+
+		assert_eq!(cache.get_or_fetch(fetch).await.unwrap().len(), 1);
+		assert_eq!(cache.clone().get_or_fetch(fetch).await.unwrap().len(), 1);
+		assert_eq!(fetches.load(Ordering::Relaxed), 1);
+	}
+}
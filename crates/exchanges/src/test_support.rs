@@ -0,0 +1,217 @@
+//! Local stand-in for exchange REST and websocket endpoints, serving canned responses and scripted sessions.
+//! Enabled in this crate's tests and with the `test-support` feature.
+
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::{Arc, Mutex, PoisonError},
+};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::{TcpListener, TcpStream},
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+/// Canned HTTP response
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+	pub status: u16,
+	pub headers: Vec<(String, String)>,
+	pub body: String,
+}
+
+impl MockResponse {
+	#[must_use]
+	pub fn json(body: impl Into<String>) -> Self {
+		Self { status: 200, headers: Vec::new(), body: body.into() }
+	}
+
+	#[must_use]
+	pub const fn status(status: u16) -> Self {
+		Self { status, headers: Vec::new(), body: String::new() }
+	}
+
+	#[must_use]
+	pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+		self.headers.push((name.into(), value.into()));
+		self
+	}
+}
+
+/// Step of a scripted websocket session
+#[derive(Debug, Clone)]
+pub enum WsAction {
+	/// Send a text frame
+	Text(String),
+	/// Close the connection
+	Close,
+	/// Stop reading from the socket, so client pings are never answered
+	Stall,
+}
+
+#[derive(Default)]
+struct State {
+	/// Responses per path, the last one is repeated once the queue drains
+	routes: HashMap<String, VecDeque<MockResponse>>,
+	/// Scripts for upcoming websocket connections, in connection order
+	sessions: VecDeque<Vec<WsAction>>,
+	/// Path and query of every HTTP request
+	requests: Vec<String>,
+	/// Text frames received over websocket
+	received: Vec<String>,
+}
+
+/// Serves HTTP and websocket on two local ports until dropped
+pub struct MockServer {
+	state: Arc<Mutex<State>>,
+	rest_url: String,
+	ws_url: String,
+	tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl MockServer {
+	pub async fn start() -> anyhow::Result<Self> {
+		let state = Arc::new(Mutex::new(State::default()));
+
+		let http_listener = TcpListener::bind("127.0.0.1:0").await?;
+		let ws_listener = TcpListener::bind("127.0.0.1:0").await?;
+		let rest_url = format!("http://{}", http_listener.local_addr()?);
+		let ws_url = format!("ws://{}", ws_listener.local_addr()?);
+
+		let http_state = Arc::clone(&state);
+		let http_task = tokio::spawn(async move {
+			while let Ok((socket, _)) = http_listener.accept().await {
+				tokio::spawn(serve_http(socket, Arc::clone(&http_state)));
+			}
+		});
+
+		let ws_state = Arc::clone(&state);
+		let ws_task = tokio::spawn(async move {
+			while let Ok((socket, _)) = ws_listener.accept().await {
+				let script = lock(&ws_state).sessions.pop_front().unwrap_or_default();
+				tokio::spawn(serve_ws(socket, script, Arc::clone(&ws_state)));
+			}
+		});
+
+		Ok(Self { state, rest_url, ws_url, tasks: vec![http_task, ws_task] })
+	}
+
+	/// Base URL for REST requests, e.g. `http://127.0.0.1:12345`
+	#[must_use]
+	pub fn rest_url(&self) -> &str {
+		&self.rest_url
+	}
+
+	/// Base URL for websocket connections, the requested path is ignored
+	#[must_use]
+	pub fn ws_url(&self) -> &str {
+		&self.ws_url
+	}
+
+	/// Queue a response for `path` (query string excluded)
+	pub fn route(&self, path: &str, response: MockResponse) {
+		lock(&self.state).routes.entry(String::from(path)).or_default().push_back(response);
+	}
+
+	/// Script the next websocket connection. Once the script runs out the connection stays open and answers pings.
+	/// Connections without a script behave the same way.
+	pub fn session(&self, script: Vec<WsAction>) {
+		lock(&self.state).sessions.push_back(script);
+	}
+
+	/// Path and query of every HTTP request served so far
+	#[must_use]
+	pub fn requests(&self) -> Vec<String> {
+		lock(&self.state).requests.clone()
+	}
+
+	/// Text frames received from websocket clients so far
+	#[must_use]
+	pub fn received(&self) -> Vec<String> {
+		lock(&self.state).received.clone()
+	}
+}
+
+impl Drop for MockServer {
+	fn drop(&mut self) {
+		for task in &self.tasks {
+			task.abort();
+		}
+	}
+}
+
+fn lock(state: &Mutex<State>) -> std::sync::MutexGuard<'_, State> {
+	state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+async fn serve_http(mut socket: TcpStream, state: Arc<Mutex<State>>) {
+	let mut request = Vec::new();
+	let mut buffer = [0; 1024];
+
+	while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+		match socket.read(&mut buffer).await {
+			Ok(0) | Err(_) => return,
+			Ok(read) => request.extend_from_slice(&buffer[..read]),
+		}
+	}
+
+	let request = String::from_utf8_lossy(&request);
+	let target = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+	let path = target.split('?').next().unwrap_or("/").to_string();
+
+	let response = {
+		let mut state = lock(&state);
+		state.requests.push(target);
+
+		match state.routes.get_mut(&path) {
+			Some(queue) if queue.len() > 1 => queue.pop_front(),
+			Some(queue) => queue.front().cloned(),
+			None => None,
+		}
+	}
+	.unwrap_or_else(|| MockResponse::status(404));
+
+	let headers: String =
+		response.headers.iter().map(|(name, value)| format!("{name}: {value}\r\n")).collect::<Vec<_>>().concat();
+	let payload = format!(
+		"HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{headers}\r\n{}",
+		response.status,
+		response.body.len(),
+		response.body
+	);
+
+	let _ = socket.write_all(payload.as_bytes()).await;
+	let _ = socket.shutdown().await;
+}
+
+async fn serve_ws(socket: TcpStream, script: Vec<WsAction>, state: Arc<Mutex<State>>) {
+	let Ok(mut ws) = accept_async(socket).await else {
+		return;
+	};
+
+	for action in script {
+		match action {
+			WsAction::Text(text) => {
+				if ws.send(Message::Text(text.into())).await.is_err() {
+					return;
+				}
+			},
+			WsAction::Close => {
+				let _ = ws.close(None).await;
+				return;
+			},
+			WsAction::Stall => {
+				// Keep the socket open without reading, the client gives up on its own
+				std::future::pending::<()>().await;
+			},
+		}
+	}
+
+	// Reading lets tungstenite answer pings
+	while let Some(Ok(message)) = ws.next().await {
+		if let Message::Text(text) = message {
+			lock(&state).received.push(text.to_string());
+		}
+	}
+}
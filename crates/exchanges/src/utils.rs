@@ -0,0 +1,213 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+
+use crate::{
+	CandleInfo, ExchangeError, FundingRateInfo, Interval, OpenInterestInfo, OpenInterestPoint, OrderBookLevel,
+};
+
+/// Milliseconds since epoch
+pub fn now_millis() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_millis() as u64)
+}
+
+/// Fetch candles opened within `from..=to` one window at a time, each window spans at most `page_limit` candles.
+/// `fetch_page` receives inclusive window bounds in milliseconds since epoch.
+pub async fn fetch_candles_range<F, Fut>(
+	interval: Interval,
+	from: u64,
+	to: u64,
+	page_limit: u32,
+	mut fetch_page: F,
+) -> Result<Vec<CandleInfo>, ExchangeError>
+where
+	F: FnMut(u64, u64) -> Fut,
+	Fut: Future<Output = anyhow::Result<Vec<CandleInfo>>>,
+{
+	let interval_millis = interval.as_millis();
+	let page_span = interval_millis * u64::from(page_limit);
+	let mut candles = Vec::new();
+	let mut cursor = from;
+
+	while cursor <= to {
+		let window_end = to.min(cursor + page_span - 1);
+		let page = fetch_page(cursor, window_end).await?;
+
+		// A full page may have been cut short (e.g. months are longer than the approximated interval),
+		// so continue right after the last returned candle instead of skipping the rest of the window
+		let last_open_time = page.iter().map(|candle| candle.open_time).max();
+		cursor = match last_open_time {
+			Some(open_time) if page.len() >= page_limit as usize && open_time + interval_millis <= window_end => {
+				open_time + 1
+			},
+			_ => window_end + 1,
+		};
+
+		candles.extend(page);
+	}
+
+	candles.retain(|candle| (from..=to).contains(&candle.open_time));
+	Ok(merge_candles(candles))
+}
+
+/// Sort candles by open time and drop duplicates, the latest copy of a candle wins
+pub fn merge_candles(mut candles: Vec<CandleInfo>) -> Vec<CandleInfo> {
+	candles.reverse();
+	candles.sort_by_key(|candle| candle.open_time);
+	candles.dedup_by_key(|candle| candle.open_time);
+	candles
+}
+
+/// Build `OpenInterestInfo` from two open interest series ordered oldest → newest:
+/// one sampled every 5 minutes (at least 48 items) and one sampled daily (at least 30 items).
+pub fn open_interest_info_from_series(
+	series_5m: &[OpenInterestPoint],
+	series_1d: &[OpenInterestPoint],
+) -> anyhow::Result<OpenInterestInfo> {
+	let series_5m: Vec<f64> = series_5m.iter().map(|point| point.open_interest).collect();
+	let series_1d: Vec<f64> = series_1d.iter().map(|point| point.open_interest).collect();
+
+	Ok(OpenInterestInfo {
+		percent_change_5_minutes: calculate_percent_change(&series_5m, 1)?,
+		percent_change_15_minutes: calculate_percent_change(&series_5m, 3)?,
+		percent_change_1_hour: calculate_percent_change(&series_5m, 12)?,
+		percent_change_4_hours: calculate_percent_change(&series_5m, 47)?,
+		percent_change_1_day: calculate_percent_change(&series_1d, 1)?,
+		percent_change_7_days: calculate_percent_change(&series_1d, 7)?,
+		percent_change_30_days: calculate_percent_change(&series_1d, 29)?,
+	})
+}
+
+/// Used when the history is too short to tell the funding interval
+const DEFAULT_FUNDING_INTERVAL_HOURS: u32 = 8;
+const HOUR_MILLIS: u64 = 3_600_000;
+
+/// Build `FundingRateInfo` from the current rate and settled `(funding_time, rate)` pairs ordered oldest → newest.
+/// The interval is the gap between the last two settlements.
+pub fn funding_rate_info_from_history(
+	funding_rate: f64,
+	next_funding_time: u64,
+	history: &[(u64, f64)],
+) -> FundingRateInfo {
+	let history_len = history.len();
+	let (average_funding_rate, percentile) = if history_len == 0 {
+		(0.0, 0.0)
+	} else {
+		let sum: f64 = history.iter().map(|(_, rate)| rate).sum();
+		let at_or_below = history.iter().filter(|(_, rate)| *rate <= funding_rate).count();
+		(sum / history_len as f64, at_or_below as f64 / history_len as f64)
+	};
+
+	// Settlement times are rounded to the hour but may be reported a few milliseconds late
+	let funding_interval_hours = match history {
+		[.., (previous, _), (last, _)] if last > previous => {
+			u32::try_from((last - previous + HOUR_MILLIS / 2) / HOUR_MILLIS).unwrap_or(DEFAULT_FUNDING_INTERVAL_HOURS)
+		},
+		_ => DEFAULT_FUNDING_INTERVAL_HOURS,
+	};
+
+	FundingRateInfo {
+		funding_rate,
+		average_funding_rate,
+		percentile,
+		history_len,
+		funding_interval_hours: funding_interval_hours.max(1),
+		next_funding_time,
+	}
+}
+
+/// Calculate percent change between the most recent value (last index) and a value at a given offset back in time
+pub fn calculate_percent_change(data: &[f64], offset: usize) -> anyhow::Result<f64> {
+	if data.len() <= offset {
+		let message = format!("need at least {} items, got {}", offset + 1, data.len());
+		return Err(ExchangeError::InsufficientData(message).into());
+	}
+
+	let last_idx = data.len() - 1;
+	let current = data[last_idx];
+	let previous = data[last_idx - offset];
+
+	if previous == 0.0 {
+		return Ok(0.0);
+	}
+
+	let percent_change = ((current - previous) / previous) * 100.0;
+	Ok(percent_change)
+}
+
+/// Order book levels sent as `[price, quantity]` string pairs
+pub fn parse_levels(levels: &[(String, String)]) -> anyhow::Result<Vec<OrderBookLevel>> {
+	levels
+		.iter()
+		.map(|(price, quantity)| {
+			Ok(OrderBookLevel {
+				price: price.parse().context(format!("Failed to parse price: {price}"))?,
+				quantity: quantity.parse().context(format!("Failed to parse quantity: {quantity}"))?,
+			})
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn candle(open_time: u64) -> CandleInfo {
+		CandleInfo { open_time, close_time: open_time + 59_999, ..Default::default() }
+	}
+
+	#[tokio::test]
+	async fn fetches_range_in_windows() {
+		let minute = Interval::Minute1.as_millis();
+		let mut windows = Vec::new();
+
+		let candles = fetch_candles_range(Interval::Minute1, 0, 9 * minute, 4, |start, end| {
+			windows.push((start, end));
+			let page = (start..=end).step_by(minute as usize).map(candle).collect();
+			async move { Ok(page) }
+		})
+		.await
+		.unwrap();
+
+		assert_eq!(windows, vec![(0, 4 * minute - 1), (4 * minute, 8 * minute - 1), (8 * minute, 9 * minute)]);
+		assert_eq!(candles.len(), 10);
+		assert!(candles.windows(2).all(|pair| pair[1].open_time - pair[0].open_time == minute));
+	}
+
+	#[test]
+	fn merges_duplicates_keeping_latest() {
+		let stale = CandleInfo { close: 1.0, ..candle(60_000) };
+		let fresh = CandleInfo { close: 2.0, ..candle(60_000) };
+
+		let merged = merge_candles(vec![candle(120_000), stale, candle(0), fresh]);
+
+		assert_eq!(merged.iter().map(|candle| candle.open_time).collect::<Vec<_>>(), vec![0, 60_000, 120_000]);
+		assert!((merged[1].close - 2.0).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn funding_info_from_history() {
+		let hour = 3_600_000;
+		let history = [(0, 0.0001), (4 * hour, -0.0002), (8 * hour + 12, 0.0004), (12 * hour + 3, 0.0001)];
+
+		let info = funding_rate_info_from_history(0.0003, 16 * hour, &history);
+
+		assert_eq!(info.funding_interval_hours, 4);
+		assert!((info.average_funding_rate - 0.0001).abs() < f64::EPSILON);
+		assert!((info.percentile - 0.75).abs() < f64::EPSILON);
+		assert!((info.annualized_rate() - 0.657).abs() < f64::EPSILON);
+		assert_eq!(funding_rate_info_from_history(0.0001, 0, &[]).funding_interval_hours, 8);
+	}
+
+	#[test]
+	fn percent_change_from_offset() {
+		let data = [100.0, 110.0, 120.0];
+		assert!((calculate_percent_change(&data, 2).unwrap() - 20.0).abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn percent_change_insufficient_data() {
+		let error = calculate_percent_change(&[1.0], 1).unwrap_err();
+		assert!(error.to_string().contains("Insufficient data"));
+	}
+}
@@ -0,0 +1,125 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Side, TradeInfo};
+
+/// Taker volume split by side
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VolumeDelta {
+	/// Base asset bought by takers
+	pub buy_volume: f64,
+	/// Base asset sold by takers
+	pub sell_volume: f64,
+	/// Quote asset value of `buy_volume`
+	pub buy_notional: f64,
+	/// Quote asset value of `sell_volume`
+	pub sell_notional: f64,
+	pub trades: u64,
+}
+
+impl VolumeDelta {
+	pub fn add(&mut self, trade: &TradeInfo) {
+		let notional = trade.price * trade.quantity;
+
+		match trade.taker_side {
+			Side::Buy => {
+				self.buy_volume += trade.quantity;
+				self.buy_notional += notional;
+			},
+			Side::Sell => {
+				self.sell_volume += trade.quantity;
+				self.sell_notional += notional;
+			},
+		}
+		self.trades += 1;
+	}
+
+	/// Cumulative volume delta in base asset, positive when takers bought more than they sold
+	#[must_use]
+	pub fn delta(&self) -> f64 {
+		self.buy_volume - self.sell_volume
+	}
+
+	/// Buy/sell imbalance by notional in `-1.0..=1.0`, `1.0` when every taker bought
+	#[must_use]
+	pub fn imbalance(&self) -> f64 {
+		let total = self.buy_notional + self.sell_notional;
+		if total == 0.0 {
+			return 0.0;
+		}
+
+		(self.buy_notional - self.sell_notional) / total
+	}
+}
+
+/// Rolling `VolumeDelta` per symbol over the trades of the last `window_millis`, measured in trade time
+#[derive(Debug)]
+pub struct VolumeDeltaTracker {
+	window_millis: u64,
+	trades: HashMap<String, VecDeque<TradeInfo>>,
+}
+
+impl VolumeDeltaTracker {
+	#[must_use]
+	pub fn new(window_millis: u64) -> Self {
+		Self { window_millis, trades: HashMap::new() }
+	}
+
+	/// Add a trade and drop trades of its symbol that fell out of the window
+	pub fn push(&mut self, trade: TradeInfo) {
+		let trades = self.trades.entry(trade.symbol.clone()).or_default();
+		let cutoff = trade.time.saturating_sub(self.window_millis);
+
+		trades.push_back(trade);
+		while trades.front().is_some_and(|oldest| oldest.time <= cutoff) {
+			trades.pop_front();
+		}
+	}
+
+	/// Volume delta of `symbol` within the window, `None` before its first trade
+	#[must_use]
+	pub fn volume_delta(&self, symbol: &str) -> Option<VolumeDelta> {
+		let trades = self.trades.get(symbol)?;
+
+		Some(trades.iter().fold(VolumeDelta::default(), |mut delta, trade| {
+			delta.add(trade);
+			delta
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn trade(symbol: &str, taker_side: Side, quantity: f64, time: u64) -> TradeInfo {
+		TradeInfo { symbol: String::from(symbol), price: 100.0, quantity, taker_side, time }
+	}
+
+	#[test]
+	fn sums_taker_volume_by_side() {
+		let mut delta = VolumeDelta::default();
+		delta.add(&trade("BTCUSDT", Side::Buy, 3.0, 0));
+		delta.add(&trade("BTCUSDT", Side::Sell, 1.0, 0));
+
+		assert!((delta.delta() - 2.0).abs() < f64::EPSILON);
+		assert!((delta.imbalance() - 0.5).abs() < f64::EPSILON);
+		assert_eq!(delta.trades, 2);
+		assert!(VolumeDelta::default().imbalance().abs() < f64::EPSILON);
+	}
+
+	#[test]
+	fn rolls_window_per_symbol() {
+		let mut tracker = VolumeDeltaTracker::new(1000);
+		tracker.push(trade("BTCUSDT", Side::Sell, 5.0, 0));
+		tracker.push(trade("BTCUSDT", Side::Buy, 1.0, 500));
+		tracker.push(trade("ETHUSDT", Side::Buy, 2.0, 600));
+		// Pushes the first trade out of the BTC window
+		tracker.push(trade("BTCUSDT", Side::Buy, 2.0, 1000));
+
+		let btc = tracker.volume_delta("BTCUSDT").unwrap();
+		assert!((btc.delta() - 3.0).abs() < f64::EPSILON);
+		assert_eq!(btc.trades, 2);
+		assert_eq!(tracker.volume_delta("ETHUSDT").map(|delta| delta.trades), Some(1));
+		assert!(tracker.volume_delta("SOLUSDT").is_none());
+	}
+}
@@ -1,6 +1,7 @@
 use anyhow::Context;
+use exchanges::ExchangeKind;
 use serde::Deserialize;
-use std::fs;
+use std::{fs, path::PathBuf};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -12,9 +13,60 @@ pub struct Config {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ScannerConfig {
+	#[serde(default)]
+	pub exchange: ExchangeKind,
+	/// Scan several venues at once, overrides `exchange` when set
+	#[serde(default)]
+	pub exchanges: Vec<ExchangeKind>,
+	/// Also scan Binance COIN-M inverse contracts, e.g. `BTCUSD_PERP`
+	#[serde(default)]
+	pub binance_coin_margined: bool,
 	pub min_liquidation_usd_price: f64,
 	pub big_tokens: Vec<String>,
 	pub big_tokens_min_liquidation_usd_price: f64,
+	/// How long taker trades are collected after a liquidation before the alert is sent
+	#[serde(default = "default_trade_flow_window_secs")]
+	pub trade_flow_window_secs: u64,
+	/// Liquidations of the same symbol and side within this window are summed in alerts
+	#[serde(default = "default_liquidation_cluster_window_secs")]
+	pub liquidation_cluster_window_secs: u64,
+	/// Post new perpetual listings to the Telegram chat
+	#[serde(default)]
+	pub announce_listings: bool,
+	/// Record raw websocket frames into rotating JSONL files in this directory
+	#[serde(default)]
+	pub capture_dir: Option<PathBuf>,
+	/// Play a recorded capture instead of the live exchange streams
+	#[serde(default)]
+	pub replay: Option<ReplayConfig>,
+}
+
+const fn default_trade_flow_window_secs() -> u64 {
+	30
+}
+
+const fn default_liquidation_cluster_window_secs() -> u64 {
+	30
+}
+
+impl ScannerConfig {
+	/// Configured venues, `exchange` alone unless `exchanges` is set
+	pub fn venues(&self) -> Vec<ExchangeKind> {
+		if self.exchanges.is_empty() { vec![self.exchange] } else { self.exchanges.clone() }
+	}
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplayConfig {
+	/// Directory written by `capture_dir`
+	pub dir: PathBuf,
+	/// Playback speed, 1.0 is real time
+	#[serde(default = "default_replay_speed")]
+	pub speed: f64,
+}
+
+const fn default_replay_speed() -> f64 {
+	1.0
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1,20 +1,40 @@
+use std::sync::Arc;
+
 use anyhow::Context;
 use coinglass::Coinglass;
-use exchanges::{BinanceExchange, Exchange, MarketLiquidationsInfo};
-use tokio::sync::mpsc;
+use exchanges::{
+	AggregatedExchange, BinanceExchange, BinanceMarket, BybitExchange, ContractKind, DEFAULT_CAPTURE_FILE_BYTES,
+	Exchange, ExchangeError, ExchangeKind, FrameRecorder, Interval, LiquidationCluster, LiquidationClusterTracker,
+	ListingEvent, LongShortRatioKind, MarketLiquidationsInfo, OkxExchange, ReplayExchange, SYMBOLS_TTL, StreamEvent,
+	TakerVolume, TradeInfo, VolumeDelta, capture_files, record_frames, watch_listings,
+};
+use futures_util::{StreamExt, future, stream::BoxStream};
+use tokio::{
+	sync::mpsc::{self, error::TrySendError},
+	time::{Duration, Instant},
+};
 use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
 use crate::{
-	config::Config,
+	config::{Config, ScannerConfig},
 	telegram::{TelegramBot, TokenAlert},
-	utils::extract_coin_from_pair,
+	utils::{extract_coin_from_pair, extract_venue_coin_from_pair},
 };
 
 mod config;
 mod telegram;
 mod utils;
 
+/// Order book liquidity shown in alerts is summed within this distance from the mid price
+pub const ORDER_BOOK_DEPTH_PERCENT: f64 = 1.0;
+/// Levels per side fetched for the depth, enough to cover `ORDER_BOOK_DEPTH_PERCENT` on most pairs
+const ORDER_BOOK_LIMIT: u32 = 500;
+/// Taker volume shown in alerts covers the last hour in 5 minute periods
+const TAKER_VOLUME_PERIODS: u32 = 12;
+/// Alerts waiting for the worker, further alerts are dropped so the liquidation stream is never held up
+const ALERT_QUEUE_CAPACITY: usize = 128;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
 	tracing_subscriber::fmt()
@@ -26,87 +46,307 @@ async fn main() -> anyhow::Result<()> {
 	let config = Config::load("config.toml").context("Failed to load configuration")?;
 	info!("✅ Configuration loaded");
 
-	let min_liquidation_usd_price = config.scanner.min_liquidation_usd_price;
-
 	let telegram_bot = TelegramBot::new(config.telegram);
 	info!("✅ Telegram bot initialized");
 
 	let coinglass = Coinglass::new()?;
 	info!("✅ Coinglass initialized");
 
-	// Use separate clients: one for the WS stream and one for REST calls in the alert worker.
-	let binance_stream = BinanceExchange::new();
-	let binance_rest = BinanceExchange::new();
-	info!("✅ Binance exchange initialized");
+	let scanner_config = config.scanner;
+	if let Some(dir) = &scanner_config.capture_dir {
+		record_frames(FrameRecorder::start(dir, DEFAULT_CAPTURE_FILE_BYTES).await?);
+		info!("✅ Recording websocket frames to {}", dir.display());
+	}
 
-	// Keep the stream callback synchronous/cheap: forward events to an async worker.
-	// Bounded channel prevents unbounded backlog if the stream is noisy.
-	let (alert_tx, mut alert_rx) = mpsc::channel::<MarketLiquidationsInfo>(128);
+	let venues = scanner_config.venues();
+	let mut names: Vec<_> = venues.iter().map(|venue| venue.name()).collect();
+	if scanner_config.binance_coin_margined {
+		names.push("Binance COIN-M");
+	}
+	info!("✅ {} initialized", names.join("+"));
 
-	tokio::spawn(async move {
-		async fn handle_alert(
-			telegram_bot: &TelegramBot,
-			coinglass: &Coinglass,
-			binance_rest: &BinanceExchange,
-			liquidation_info: MarketLiquidationsInfo,
-		) -> anyhow::Result<()> {
-			let symbol = liquidation_info.symbol.clone();
-			let coin = utils::extract_coin_from_pair(&symbol);
-
-			let liquidation_heatmap_screenshot = tokio::task::block_in_place(|| {
-				coinglass
-					.get_liquidation_heatmap_screenshot(coin)
-					.map_err(|error| anyhow::anyhow!("Failed to get liquidation heatmap screenshot for {symbol}: {error}"))
-			})?;
-
-			let open_interest_info = binance_rest
-				.get_open_interest_info(&symbol)
-				.await
-				.map_err(|error| anyhow::anyhow!("Failed to get open interest info for {symbol}: {error}"))?;
-
-			let token_alert = TokenAlert {
-				symbol: extract_coin_from_pair(&symbol).to_string(),
-				open_interest_info,
-				liquidation_info,
-				liquidation_heatmap_screenshot,
-			};
+	let coin_margined =
+		scanner_config.binance_coin_margined.then(|| BinanceExchange::builder().market(BinanceMarket::CoinM).build());
+
+	if let Some(coin_margined) = coin_margined {
+		let exchange = AggregatedExchange::from_kinds(&venues).with_venue(ExchangeKind::Binance, Arc::new(coin_margined));
+		let listing_venues = exchange.venue_clients().collect();
+		return start(scanner_config, telegram_bot, coinglass, exchange, listing_venues).await;
+	}
+
+	match venues.as_slice() {
+		[ExchangeKind::Binance] => {
+			start_venue(scanner_config, telegram_bot, coinglass, ExchangeKind::Binance, BinanceExchange::new()).await
+		},
+		[ExchangeKind::Bybit] => {
+			start_venue(scanner_config, telegram_bot, coinglass, ExchangeKind::Bybit, BybitExchange::new()).await
+		},
+		[ExchangeKind::Okx] => {
+			start_venue(scanner_config, telegram_bot, coinglass, ExchangeKind::Okx, OkxExchange::new()).await
+		},
+		_ => {
+			let exchange = AggregatedExchange::from_kinds(&venues);
+			let listing_venues = exchange.venue_clients().collect();
+			start(scanner_config, telegram_bot, coinglass, exchange, listing_venues).await
+		},
+	}
+}
+
+/// Run against the single venue `exchange`, its listings are announced through the same client
+async fn start_venue<E>(
+	scanner_config: ScannerConfig,
+	telegram_bot: TelegramBot,
+	coinglass: Coinglass,
+	exchange: ExchangeKind,
+	exchange_client: E,
+) -> anyhow::Result<()>
+where
+	E: Exchange + Clone + Send + Sync + 'static,
+{
+	let listing_venue: Arc<dyn Exchange + Send + Sync> = Arc::new(exchange_client.clone());
+	start(scanner_config, telegram_bot, coinglass, exchange_client, vec![(exchange, listing_venue)]).await
+}
+
+/// Run against the live streams of `exchange_client` or, when configured, a replayed capture.
+///
+/// New listings of `listing_venues`, the venue clients behind `exchange_client`, are announced when enabled.
+async fn start<E>(
+	scanner_config: ScannerConfig,
+	telegram_bot: TelegramBot,
+	coinglass: Coinglass,
+	exchange_client: E,
+	listing_venues: Vec<(ExchangeKind, Arc<dyn Exchange + Send + Sync>)>,
+) -> anyhow::Result<()>
+where
+	E: Exchange + Clone + Send + Sync + 'static,
+{
+	let Some(replay) = scanner_config.replay.clone() else {
+		if scanner_config.announce_listings {
+			for (exchange, venue_client) in listing_venues {
+				spawn_listing_announcements(telegram_bot.clone(), exchange, venue_client);
+			}
+		}
+		return run(scanner_config, telegram_bot, coinglass, exchange_client).await;
+	};
 
-			telegram_bot.send_alert(&token_alert).await.map_err(|error| {
-				anyhow::anyhow!("Failed to send alert for {}: {error}", token_alert.liquidation_info.symbol)
-			})?;
+	let files = capture_files(&replay.dir)?;
+	let exchange_client = ReplayExchange::open(exchange_client, files, replay.speed).await?;
+	info!("✅ Replaying capture from {} at {}x", replay.dir.display(), replay.speed);
 
-			Ok(())
+	if scanner_config.announce_listings {
+		for (exchange, venue_client) in listing_venues {
+			spawn_listing_announcements(telegram_bot.clone(), exchange, exchange_client.replaying(venue_client));
 		}
+	}
+	run(scanner_config, telegram_bot, coinglass, exchange_client).await
+}
+
+/// Run the scanner against one exchange or several aggregated ones
+async fn run<E>(
+	scanner_config: ScannerConfig,
+	telegram_bot: TelegramBot,
+	coinglass: Coinglass,
+	exchange_client: E,
+) -> anyhow::Result<()>
+where
+	E: Exchange + Clone + Send + Sync + 'static,
+{
+	// Every liquidation counts towards the cluster, including ones too small to alert on
+	let mut clusters = LiquidationClusterTracker::new(scanner_config.liquidation_cluster_window_secs * 1000);
+
+	let mut alerts = exchange_client.watch_market_liquidations().filter_map(|event| {
+		let alert = match event {
+			StreamEvent::Data(liquidation) => {
+				clusters.push(liquidation.clone());
+				is_alert_worthy(&scanner_config, &liquidation).then(|| {
+					let cluster = clusters.cluster(&liquidation.symbol, liquidation.liquidated_position(), liquidation.time);
+					(liquidation, cluster)
+				})
+			},
+			StreamEvent::Reconnected { disconnected_at, reconnected_at } => {
+				warn!("Liquidation stream reconnected, missed {}ms of events", reconnected_at.saturating_sub(disconnected_at));
+				None
+			},
+		};
+		future::ready(alert)
+	});
+
+	// Alerts take seconds to prepare, the stream is read on its own so it keeps answering pings meanwhile
+	let (alert_tx, mut alert_rx) =
+		mpsc::channel::<(MarketLiquidationsInfo, Option<LiquidationCluster>)>(ALERT_QUEUE_CAPACITY);
+	let trade_flow_window = Duration::from_secs(scanner_config.trade_flow_window_secs);
+	let alert_exchange = exchange_client.clone();
 
-		while let Some(liquidation_info) = alert_rx.recv().await {
-			if let Err(error) = handle_alert(&telegram_bot, &coinglass, &binance_rest, liquidation_info).await {
+	tokio::spawn(async move {
+		while let Some((liquidation_info, liquidation_cluster)) = alert_rx.recv().await {
+			if let Err(error) = handle_alert(
+				&telegram_bot,
+				&coinglass,
+				&alert_exchange,
+				liquidation_info,
+				liquidation_cluster,
+				trade_flow_window,
+			)
+			.await
+			{
 				error!("{error:#}");
 				warn!("Skipping alert due to error");
 			}
 		}
 	});
 
-	binance_stream
-		.watch_market_liquidations(move |liquidation| {
-			if config.scanner.big_tokens.contains(&extract_coin_from_pair(&liquidation.symbol).to_string())
-				&& liquidation.usd_price < config.scanner.big_tokens_min_liquidation_usd_price
-			{
-				return;
+	while let Some(alert) = alerts.next().await {
+		match alert_tx.try_send(alert) {
+			Ok(()) => {},
+			Err(TrySendError::Full((liquidation, _))) => {
+				warn!("Alert queue is full, dropping alert for {}", liquidation.symbol);
+			},
+			Err(TrySendError::Closed(_)) => break,
+		}
+	}
+
+	Ok(())
+}
+
+/// Post perpetuals that appear on `exchange_client` after startup, the symbols known at startup are the baseline
+fn spawn_listing_announcements<E>(telegram_bot: TelegramBot, exchange: ExchangeKind, exchange_client: E)
+where
+	E: Exchange + Send + Sync + 'static,
+{
+	let mut listings = watch_listings(exchange_client, SYMBOLS_TTL);
+
+	tokio::spawn(async move {
+		while let Some(event) = listings.next().await {
+			let ListingEvent::Listed(info) = event else {
+				continue;
+			};
+			if info.contract_kind != ContractKind::Perpetual {
+				continue;
 			}
 
-			if liquidation.usd_price >= min_liquidation_usd_price {
-				match alert_tx.try_send(liquidation) {
-					Ok(()) => {},
-					Err(tokio::sync::mpsc::error::TrySendError::Full(liquidation)) => {
-						warn!("Alert queue is full; dropping alert for {}", liquidation.symbol);
-					},
-					Err(tokio::sync::mpsc::error::TrySendError::Closed(liquidation)) => {
-						warn!("Alert worker is down; dropping alert for {}", liquidation.symbol);
-					},
-				}
+			info!("🆕 New listing {}", info.symbol);
+			if let Err(error) = telegram_bot.send_listing(exchange, &info).await {
+				error!("{error:#}");
 			}
-		})
-		.await?;
+		}
+	});
+}
+
+fn is_alert_worthy(scanner_config: &ScannerConfig, liquidation: &MarketLiquidationsInfo) -> bool {
+	// Tokens may be listed as the venue spells them (`1000PEPE`) or canonically (`PEPE`)
+	let coins = [extract_venue_coin_from_pair(&liquidation.symbol), &extract_coin_from_pair(&liquidation.symbol)];
+	if scanner_config.big_tokens.iter().any(|token| coins.contains(&token.as_str()))
+		&& liquidation.usd_price < scanner_config.big_tokens_min_liquidation_usd_price
+	{
+		return false;
+	}
+
+	liquidation.usd_price >= scanner_config.min_liquidation_usd_price
+}
+
+/// Prepare the alert of `liquidation_info` and send it once `trade_flow_window` has passed
+async fn handle_alert<E: Exchange + Sync>(
+	telegram_bot: &TelegramBot,
+	coinglass: &Coinglass,
+	exchange_client: &E,
+	liquidation_info: MarketLiquidationsInfo,
+	liquidation_cluster: Option<LiquidationCluster>,
+	trade_flow_window: Duration,
+) -> anyhow::Result<()> {
+	let symbol = liquidation_info.symbol.clone();
+	let coin = extract_coin_from_pair(&symbol);
+
+	// Trades are buffered by the stream while the rest of the alert is prepared
+	let trades = exchange_client.watch_trades(&symbol);
+	let trade_flow_deadline = Instant::now() + trade_flow_window;
+
+	let liquidation_heatmap_screenshot = tokio::task::block_in_place(|| {
+		coinglass
+			.get_liquidation_heatmap_screenshot(&coin)
+			.map_err(|error| anyhow::anyhow!("Failed to get liquidation heatmap screenshot for {symbol}: {error}"))
+	})?;
+
+	let open_interest_info = exchange_client
+		.get_open_interest_info(&symbol)
+		.await
+		.map_err(|error| anyhow::anyhow!("Failed to get open interest info for {symbol}: {error}"))?;
+
+	// Crowd positioning is optional, the alert is sent without it
+	let long_short_ratio = match exchange_client
+		.get_long_short_ratio(&symbol, LongShortRatioKind::GlobalAccount, Interval::Minute5, 1)
+		.await
+	{
+		Ok(ratios) => ratios.last().cloned(),
+		Err(ExchangeError::Unsupported(_)) => None,
+		Err(error) => {
+			warn!("Failed to get long/short ratio for {symbol}: {error:#}");
+			None
+		},
+	};
+
+	// Aggressive buying next to rising open interest points at new longs rather than shorts being squeezed
+	let taker_volume = match exchange_client.get_taker_volume(&symbol, Interval::Minute5, TAKER_VOLUME_PERIODS).await {
+		Ok(volumes) => TakerVolume::combined(&volumes),
+		// Bybit doesn't report taker volume
+		Err(ExchangeError::Unsupported(_)) => None,
+		Err(error) => {
+			warn!("Failed to get taker volume for {symbol}: {error:#}");
+			None
+		},
+	};
+
+	// A thin book around the liquidation makes follow-through more likely, also optional
+	let order_book_depth = match exchange_client.get_order_book(&symbol, ORDER_BOOK_LIMIT).await {
+		Ok(book) => book.depth_within(ORDER_BOOK_DEPTH_PERCENT),
+		Err(ExchangeError::Unsupported(_)) => None,
+		Err(error) => {
+			warn!("Failed to get order book for {symbol}: {error:#}");
+			None
+		},
+	};
+
+	// Trade flow is collected in the background, so the next queued alert doesn't wait out this window
+	let telegram_bot = telegram_bot.clone();
+	tokio::spawn(async move {
+		let trade_flow = collect_trade_flow(trades, trade_flow_deadline).await;
+
+		let token_alert = TokenAlert {
+			symbol: coin,
+			exchange: liquidation_info.exchange,
+			open_interest_info,
+			long_short_ratio,
+			taker_volume,
+			order_book_depth,
+			trade_flow,
+			trade_flow_window,
+			liquidation_cluster,
+			liquidation_info,
+			liquidation_heatmap_screenshot,
+		};
+
+		if let Err(error) = telegram_bot.send_alert(&token_alert).await {
+			error!("Failed to send alert for {}: {error}", token_alert.liquidation_info.symbol);
+		}
+	});
 
 	Ok(())
 }
+
+/// Taker volume until `deadline`, `None` when nothing traded
+async fn collect_trade_flow(
+	mut trades: BoxStream<'static, StreamEvent<TradeInfo>>,
+	deadline: Instant,
+) -> Option<VolumeDelta> {
+	let mut flow = VolumeDelta::default();
+
+	let _ = tokio::time::timeout_at(deadline, async {
+		while let Some(event) = trades.next().await {
+			if let StreamEvent::Data(trade) = event {
+				flow.add(&trade);
+			}
+		}
+	})
+	.await;
+
+	(flow.trades > 0).then_some(flow)
+}
@@ -1,19 +1,37 @@
-use exchanges::{MarketLiquidationsInfo, OpenInterestInfo};
+use std::time::Duration;
+
+use exchanges::{
+	ExchangeKind, LiquidatedPosition, LiquidationCluster, LongShortRatio, MarketLiquidationsInfo, OpenInterestInfo,
+	OrderBookDepth, SymbolInfo, SymbolStatus, TakerVolume, VolumeDelta,
+};
 use teloxide::{
 	prelude::*,
 	types::{InputFile, MessageId, ParseMode, ThreadId},
 };
 
-use crate::config::TelegramConfig;
+use crate::{ORDER_BOOK_DEPTH_PERCENT, config::TelegramConfig};
 
+#[derive(Clone)]
 pub struct TelegramBot {
 	bot: Bot,
 	config: TelegramConfig,
 }
 
 pub struct TokenAlert {
+	/// Canonical base asset, e.g. `PEPE` for `1000PEPEUSDT`
 	pub symbol: String,
+	pub exchange: ExchangeKind,
 	pub open_interest_info: OpenInterestInfo,
+	pub long_short_ratio: Option<LongShortRatio>,
+	/// Taker buy/sell volume over the last hour
+	pub taker_volume: Option<TakerVolume>,
+	/// Resting liquidity within `ORDER_BOOK_DEPTH_PERCENT` of the mid price
+	pub order_book_depth: Option<OrderBookDepth>,
+	/// Taker volume within `trade_flow_window` after the liquidation
+	pub trade_flow: Option<VolumeDelta>,
+	pub trade_flow_window: Duration,
+	/// Liquidations of the same symbol and side shortly before this one, across all scanned venues
+	pub liquidation_cluster: Option<LiquidationCluster>,
 	pub liquidation_info: MarketLiquidationsInfo,
 	pub liquidation_heatmap_screenshot: Vec<u8>,
 }
@@ -57,12 +75,25 @@ impl TelegramBot {
 		Ok(())
 	}
 
+	pub async fn send_listing(&self, exchange: ExchangeKind, info: &SymbolInfo) -> anyhow::Result<()> {
+		let mut request =
+			self.bot.send_message(self.config.chat_id.clone(), format_listing(exchange, info)).parse_mode(ParseMode::Html);
+
+		if let Some(thread_id) = self.config.thread_id {
+			request = request.message_thread_id(ThreadId(MessageId(thread_id)));
+		}
+
+		request.await.map_err(|error| anyhow::anyhow!("Failed to send listing: {error}"))?;
+
+		Ok(())
+	}
+
 	fn format_alert_message(&self, token: &TokenAlert) -> String {
 		let sections = [
 			self.format_header(token),
 			self.format_liquidation_info(token),
 			self.format_market_stats(token),
-			self.format_footer(&token.symbol),
+			self.format_footer(token),
 		];
 
 		sections.join("\n\n")
@@ -75,13 +106,17 @@ impl TelegramBot {
 	fn format_liquidation_info(&self, token: &TokenAlert) -> String {
 		let liquidation = &token.liquidation_info;
 
-		let side_info = match liquidation.side.as_str() {
-			"BUY" => "shorts 🔴",
-			"SELL" => "longs 🟢",
-			_ => "unknown",
+		let side_info = match liquidation.liquidated_position() {
+			LiquidatedPosition::Short => "shorts 🔴",
+			LiquidatedPosition::Long => "longs 🟢",
 		};
 
-		format!("💥 Liquidated {side_info} | <code>{:.0}$</code>", liquidation.usd_price)
+		let info = format!("💥 Liquidated {side_info} | <code>{:.0}$</code>", liquidation.usd_price);
+
+		match token.liquidation_cluster.as_ref().filter(|cluster| cluster.liquidations > 1) {
+			Some(cluster) => format!("{info}\n🌊 {}", format_cluster(cluster)),
+			None => info,
+		}
 	}
 
 	fn format_market_stats(&self, token: &TokenAlert) -> String {
@@ -93,7 +128,7 @@ impl TelegramBot {
 			format!("{emoji}  <code>{sign}{value:.2}% ({label})</code>")
 		};
 
-		format!(
+		let open_interest = format!(
 			"📈 Open Interest \n\
 			{} \n\
 			{} \n\
@@ -103,13 +138,53 @@ impl TelegramBot {
 			format("1h", oi.percent_change_1_hour),
 			format("4h", oi.percent_change_4_hours),
 			format("24h", oi.percent_change_1_day),
-		)
+		);
+
+		let mut stats = vec![open_interest];
+
+		if let Some(volume) = &token.taker_volume {
+			stats.push(format!(
+				"🧮 Taker volume 1h <code>{:.2} buys / {:.2} sells ({:+.0}%)</code>",
+				volume.buy_volume,
+				volume.sell_volume,
+				volume.imbalance() * 100.0
+			));
+		}
+
+		if let Some(ratio) = &token.long_short_ratio {
+			stats.push(format!(
+				"👥 Accounts <code>{:.1}% long / {:.1}% short</code>",
+				ratio.long_share * 100.0,
+				ratio.short_share * 100.0
+			));
+		}
+
+		if let Some(flow) = &token.trade_flow {
+			stats.push(format!(
+				"⚖️ Taker flow {}s <code>{:.0}$ buys / {:.0}$ sells ({:+.0}%)</code>",
+				token.trade_flow_window.as_secs(),
+				flow.buy_notional,
+				flow.sell_notional,
+				flow.imbalance() * 100.0
+			));
+		}
+
+		if let Some(depth) = &token.order_book_depth {
+			stats.push(format!(
+				"📚 Depth ±{ORDER_BOOK_DEPTH_PERCENT}% <code>{:.0}$ bids / {:.0}$ asks</code>",
+				depth.bid_notional, depth.ask_notional
+			));
+		}
+
+		stats.join("\n\n")
 	}
 
-	fn format_footer(&self, symbol: &str) -> String {
-		let exchange = "Binance";
-		let coinglass_symbol = format!("{symbol}USDT");
-		let tradingview_symbol = format!("{symbol}USDT.P");
+	fn format_footer(&self, token: &TokenAlert) -> String {
+		let symbol = &token.symbol;
+		let exchange = token.exchange.name();
+		// Charts are linked by the venue's own symbol, `1000PEPEUSDT` rather than `PEPEUSDT`
+		let coinglass_symbol = &token.liquidation_info.symbol;
+		let tradingview_symbol = format!("{coinglass_symbol}.P");
 
 		let coinglass_link = format!("<a href='https://www.coinglass.com/tv/{exchange}_{coinglass_symbol}'>CoinGlass</a>");
 		let tradingview_link =
@@ -120,3 +195,35 @@ impl TelegramBot {
 		format!("{coinglass_link} | {tradingview_link} | {liquidation_heatmap_link}")
 	}
 }
+
+/// E.g. "$2.1M longs liquidated across Binance+Bybit in 30s"
+fn format_cluster(cluster: &LiquidationCluster) -> String {
+	let side = match cluster.position {
+		LiquidatedPosition::Short => "shorts",
+		LiquidatedPosition::Long => "longs",
+	};
+	let venues: Vec<_> = cluster.venues.iter().map(|venue| venue.name()).collect();
+	let seconds = cluster.span_millis.div_ceil(1000).max(1);
+
+	format!(
+		"<code>{}</code> {side} liquidated across {} in {seconds}s",
+		format_usd_compact(cluster.usd_value),
+		venues.join("+")
+	)
+}
+
+fn format_usd_compact(value: f64) -> String {
+	if value >= 1_000_000.0 {
+		format!("${:.1}M", value / 1_000_000.0)
+	} else if value >= 1_000.0 {
+		format!("${:.1}K", value / 1_000.0)
+	} else {
+		format!("${value:.0}")
+	}
+}
+
+fn format_listing(exchange: ExchangeKind, info: &SymbolInfo) -> String {
+	let status = if info.status == SymbolStatus::PreTrading { "opens soon" } else { "trading" };
+
+	format!("🆕 New perpetual on {} | <code>{}</code> ({status})", exchange.name(), info.symbol)
+}
@@ -1,5 +1,13 @@
-pub fn extract_coin_from_pair(pair: &str) -> &str {
-	pair.strip_suffix("USDT").unwrap_or(pair)
+use exchanges::Instrument;
+
+/// Canonical base asset, e.g. `PEPE` for `1000PEPEUSDT`
+pub fn extract_coin_from_pair(pair: &str) -> String {
+	Instrument::parse(pair).map_or_else(|| String::from(pair), |instrument| instrument.base.to_string())
+}
+
+/// Base asset as the venue spells it, e.g. `1000PEPE` for `1000PEPEUSDT`
+pub fn extract_venue_coin_from_pair(pair: &str) -> &str {
+	Instrument::parse(pair).and_then(|instrument| pair.strip_suffix(&instrument.quote.to_string())).unwrap_or(pair)
 }
 
 #[cfg(test)]
@@ -12,4 +20,12 @@ mod tests {
 		let coin = extract_coin_from_pair(pair);
 		assert_eq!(coin, "ZBT");
 	}
+
+	#[test]
+	fn when_multiplied() {
+		assert_eq!(extract_coin_from_pair("1000PEPEUSDT"), "PEPE");
+		assert_eq!(extract_coin_from_pair("BTCUSDC"), "BTC");
+		assert_eq!(extract_venue_coin_from_pair("1000PEPEUSDT"), "1000PEPE");
+		assert_eq!(extract_venue_coin_from_pair("SHIB1000USDC"), "SHIB1000");
+	}
 }
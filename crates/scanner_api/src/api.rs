@@ -1,8 +1,10 @@
 use std::cmp::Ordering;
 
-use actix_web::{Error, HttpResponse, Responder, web};
+use actix_web::{Error, HttpResponse, Responder, http::StatusCode, http::header, web};
+use exchanges::{Exchange, ExchangeError};
 use serde::Deserialize;
 
+use crate::fetcher::{SpotMarket, refresh_pair};
 use crate::models::{PairResponse, PairSnapshot, SortDirection, SortField, SortKey};
 use crate::state::AppState;
 
@@ -11,6 +13,7 @@ pub struct PairsQuery {
 	pub sort: Option<String>,
 	pub favorite: Option<bool>,
 	pub has_comments: Option<bool>,
+	pub delisted: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,14 +61,14 @@ pub async fn favorite_pair(state: web::Data<AppState>, pair: web::Path<String>)
 }
 
 pub async fn unfavorite_pair(state: web::Data<AppState>, pair: web::Path<String>) -> Result<impl Responder, Error> {
-	match state
+	state
 		.unfavorite_pair(pair.as_str())
 		.await
 		.map_err(|err| actix_web::error::ErrorInternalServerError(err.to_string()))?
-	{
-		Some(updated) => Ok(HttpResponse::Ok().json(PairResponse::from(&updated))),
-		None => Err(actix_web::error::ErrorNotFound("Pair not found")),
-	}
+		.map_or_else(
+			|| Err(actix_web::error::ErrorNotFound("Pair not found")),
+			|updated| Ok(HttpResponse::Ok().json(PairResponse::from(&updated))),
+		)
 }
 
 pub async fn add_comment(
@@ -95,28 +98,69 @@ pub async fn remove_comment(
 		return Err(actix_web::error::ErrorBadRequest("Comment cannot be empty"));
 	}
 
-	match state
+	state
 		.remove_comment(pair.as_str(), comment)
 		.await
 		.map_err(|err| actix_web::error::ErrorInternalServerError(err.to_string()))?
-	{
-		Some(updated) => Ok(HttpResponse::Ok().json(PairResponse::from(&updated))),
-		None => Err(actix_web::error::ErrorNotFound("Comment or pair not found")),
+		.map_or_else(
+			|| Err(actix_web::error::ErrorNotFound("Comment or pair not found")),
+			|updated| Ok(HttpResponse::Ok().json(PairResponse::from(&updated))),
+		)
+}
+
+/// Refresh the MFI values and basis of `pair` right away, exchange failures are reported with a matching status code
+pub async fn refresh_pair_now(
+	state: web::Data<AppState>,
+	exchange: web::Data<dyn Exchange + Send + Sync>,
+	spot: web::Data<SpotMarket>,
+	pair: web::Path<String>,
+) -> Result<impl Responder, Error> {
+	let updated = match refresh_pair(&state, exchange.get_ref(), &spot, pair.as_str()).await {
+		Ok(updated) => updated,
+		Err(err) => return Ok(exchange_error_response(&err)),
+	};
+
+	state.persist().await.map_err(|err| actix_web::error::ErrorInternalServerError(err.to_string()))?;
+	Ok(HttpResponse::Ok().json(PairResponse::from(&updated)))
+}
+
+fn exchange_error_response(err: &ExchangeError) -> HttpResponse {
+	let mut response = HttpResponse::build(exchange_error_status(err));
+	if let ExchangeError::RateLimited { retry_after: Some(retry_after) } = err {
+		response.insert_header((header::RETRY_AFTER, retry_after.as_secs().max(1).to_string()));
 	}
+
+	response.body(err.to_string())
 }
 
-fn matches_filters(pair: &PairSnapshot, query: &PairsQuery) -> bool {
-	if let Some(is_favorite) = query.favorite {
-		if pair.is_favorite != is_favorite {
-			return false;
-		}
+const fn exchange_error_status(err: &ExchangeError) -> StatusCode {
+	match err {
+		ExchangeError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+		ExchangeError::UnknownSymbol(_) => StatusCode::NOT_FOUND,
+		ExchangeError::InsufficientData(_) => StatusCode::UNPROCESSABLE_ENTITY,
+		ExchangeError::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+		ExchangeError::Decode(_) | ExchangeError::Network(_) => StatusCode::BAD_GATEWAY,
+		ExchangeError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
 	}
+}
 
-	if let Some(has_comments) = query.has_comments {
-		let pair_has_comments = !pair.comments.is_empty();
-		if pair_has_comments != has_comments {
-			return false;
-		}
+const fn matches_filters(pair: &PairSnapshot, query: &PairsQuery) -> bool {
+	if let Some(is_favorite) = query.favorite
+		&& pair.is_favorite != is_favorite
+	{
+		return false;
+	}
+
+	if let Some(has_comments) = query.has_comments
+		&& pair.comments.is_empty() == has_comments
+	{
+		return false;
+	}
+
+	if let Some(is_delisted) = query.delisted
+		&& pair.is_delisted != is_delisted
+	{
+		return false;
 	}
 
 	true
@@ -158,8 +202,9 @@ fn parse_sort_key(value: &str) -> Result<SortKey, SortParseError> {
 		"mfi_4h" => Ok(SortKey::Mfi4h),
 		"mfi_1d" => Ok(SortKey::Mfi1d),
 		"mfi_1w" => Ok(SortKey::Mfi1w),
+		"basis" => Ok(SortKey::Basis),
 		_ => Err(SortParseError::new(format!(
-			"Unsupported sort field: {value}. Use price, mfi_1h, mfi_4h, mfi_1d, or mfi_1w.",
+			"Unsupported sort field: {value}. Use price, mfi_1h, mfi_4h, mfi_1d, mfi_1w, or basis.",
 		))),
 	}
 }
@@ -184,6 +229,8 @@ fn compare_pairs(left: &PairResponse, right: &PairResponse, sort_fields: &[SortF
 			SortKey::Mfi4h => compare_f64(left.mfi_4h, right.mfi_4h),
 			SortKey::Mfi1d => compare_f64(left.mfi_1d, right.mfi_1d),
 			SortKey::Mfi1w => compare_f64(left.mfi_1w, right.mfi_1w),
+			// Pairs without a spot market sort as if they had no premium
+			SortKey::Basis => compare_f64(left.basis_percent.unwrap_or(0.0), right.basis_percent.unwrap_or(0.0)),
 		};
 
 		if ordering != Ordering::Equal {
@@ -262,8 +309,11 @@ mod tests {
 				mfi_4h: 60.0,
 				mfi_1d: 50.0,
 				mfi_1w: 30.0,
+				basis_percent: None,
+				market_lead: None,
 				is_favorite: false,
 				comments: Vec::new(),
+				is_delisted: false,
 			},
 			PairResponse {
 				icon: "b".to_string(),
@@ -273,8 +323,11 @@ mod tests {
 				mfi_4h: 60.0,
 				mfi_1d: 80.0,
 				mfi_1w: 30.0,
+				basis_percent: None,
+				market_lead: None,
 				is_favorite: false,
 				comments: Vec::new(),
+				is_delisted: false,
 			},
 			PairResponse {
 				icon: "c".to_string(),
@@ -284,8 +337,11 @@ mod tests {
 				mfi_4h: 20.0,
 				mfi_1d: 80.0,
 				mfi_1w: 70.0,
+				basis_percent: None,
+				market_lead: None,
 				is_favorite: false,
 				comments: Vec::new(),
+				is_delisted: false,
 			},
 		];
 
@@ -324,15 +380,18 @@ mod tests {
 			mfi_4h: 0.0,
 			mfi_1d: 0.0,
 			mfi_1w: 0.0,
+			basis_percent: None,
+			market_lead: None,
 			is_favorite,
 			comments: comments.iter().map(|value| (*value).to_string()).collect(),
+			is_delisted: false,
 			updated_at: Utc::now(),
 		}
 	}
 
 	#[test]
 	fn matches_filters_defaults_to_all() {
-		let query = PairsQuery { sort: None, favorite: None, has_comments: None };
+		let query = PairsQuery { sort: None, favorite: None, has_comments: None, delisted: None };
 		let pair = sample_pair("AAAUSDT", true, &[]);
 		assert!(matches_filters(&pair, &query));
 
@@ -342,7 +401,7 @@ mod tests {
 
 	#[test]
 	fn matches_filters_comments_only() {
-		let query = PairsQuery { sort: None, favorite: None, has_comments: Some(true) };
+		let query = PairsQuery { sort: None, favorite: None, has_comments: Some(true), delisted: None };
 		let with_comments = sample_pair("AAAUSDT", false, &["note"]);
 		let without_comments = sample_pair("BBBUSD", false, &[]);
 
@@ -352,7 +411,7 @@ mod tests {
 
 	#[test]
 	fn matches_filters_favorites_and_comments() {
-		let query = PairsQuery { sort: None, favorite: Some(true), has_comments: Some(true) };
+		let query = PairsQuery { sort: None, favorite: Some(true), has_comments: Some(true), delisted: None };
 		let matching = sample_pair("AAAUSDT", true, &["note"]);
 		let missing_favorite = sample_pair("BBBUSD", false, &["note"]);
 		let missing_comment = sample_pair("CCCUSD", true, &[]);
@@ -361,4 +420,32 @@ mod tests {
 		assert!(!matches_filters(&missing_favorite, &query));
 		assert!(!matches_filters(&missing_comment, &query));
 	}
+
+	#[test]
+	fn exchange_errors_map_to_status_codes() {
+		let rate_limited = ExchangeError::RateLimited { retry_after: Some(std::time::Duration::from_secs(30)) };
+		let response = exchange_error_response(&rate_limited);
+
+		assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+		assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "30");
+		assert_eq!(exchange_error_status(&ExchangeError::UnknownSymbol("FOOUSDT".to_string())), StatusCode::NOT_FOUND);
+		assert_eq!(
+			exchange_error_status(&ExchangeError::InsufficientData("2 candles".to_string())),
+			StatusCode::UNPROCESSABLE_ENTITY
+		);
+		assert_eq!(
+			exchange_error_status(&ExchangeError::Network(anyhow::anyhow!("Connection reset"))),
+			StatusCode::BAD_GATEWAY
+		);
+	}
+
+	#[test]
+	fn matches_filters_delisted() {
+		let query = PairsQuery { sort: None, favorite: None, has_comments: None, delisted: Some(false) };
+		let listed = sample_pair("AAAUSDT", false, &[]);
+		let delisted = PairSnapshot { is_delisted: true, ..sample_pair("BBBUSDT", false, &[]) };
+
+		assert!(matches_filters(&listed, &query));
+		assert!(!matches_filters(&delisted, &query));
+	}
 }
@@ -1,31 +1,50 @@
-use std::time::Duration;
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
-use anyhow::{Context, anyhow};
+use anyhow::Context;
 use chrono::Utc;
-use exchanges::{BinanceExchange, Exchange};
+use exchanges::{
+	CandleInfo, Exchange, ExchangeError, Interval, ListingEvent, ListingTracker, SpotPerpPair, StreamEvent, market_lead,
+};
 use futures::stream::{self, StreamExt};
 
 use crate::mfi::calculate_mfi;
-use crate::models::{PairUpdate, icon_url};
+use crate::models::{PairSnapshot, PairUpdate, SpotComparison, icon_url};
 use crate::state::AppState;
 
 const KLINE_LIMIT: u32 = 100;
 const MFI_LENGTH: usize = 14;
 const PAIR_CONCURRENCY: usize = 10;
-const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const REFRESH_INTERVAL: Duration = Duration::from_mins(10);
+const MFI_INTERVALS: [Interval; 4] = [Interval::Hour1, Interval::Hour4, Interval::Day1, Interval::Week1];
+/// Attempts per klines request when the failure is transient
+const MAX_FETCH_ATTEMPTS: u32 = 3;
+/// Used when a rate limit error doesn't say how long to wait
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(30);
+const NETWORK_BACKOFF: Duration = Duration::from_secs(2);
+/// Latest hourly candles whose volume growth decides the market lead
+const LEAD_CANDLES: usize = 4;
 
 struct MfiSnapshot {
 	value: f64,
 	price: f64,
+	candles: Vec<CandleInfo>,
 }
 
-pub fn spawn_refresh_loop(state: AppState, binance: BinanceExchange) {
+/// Spot market perpetuals are compared against, `None` when the venue has none
+#[derive(Clone, Default)]
+pub struct SpotMarket(pub Option<Arc<dyn Exchange + Send + Sync>>);
+
+pub fn spawn_refresh_loop<E>(state: AppState, exchange: Arc<E>, spot: SpotMarket)
+where
+	E: Exchange + Send + Sync + ?Sized + 'static,
+{
 	tokio::spawn(async move {
 		let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+		let mut listings = ListingTracker::default();
 		interval.tick().await;
 
 		loop {
-			if let Err(err) = refresh_pairs(&state, &binance).await {
+			if let Err(err) = refresh_pairs(&state, exchange.as_ref(), &spot, &mut listings).await {
 				eprintln!("Failed to refresh pairs: {err}");
 			}
 
@@ -34,14 +53,48 @@ pub fn spawn_refresh_loop(state: AppState, binance: BinanceExchange) {
 	});
 }
 
-async fn refresh_pairs(state: &AppState, binance: &BinanceExchange) -> anyhow::Result<()> {
-	let pairs = binance.get_all_usdt_pairs().await.context("Failed to fetch USDT pairs")?;
+/// Keep prices fresh between refreshes using the exchange ticker stream
+pub fn spawn_ticker_loop<E>(state: AppState, exchange: &E)
+where
+	E: Exchange + ?Sized,
+{
+	let mut tickers = exchange.watch_tickers();
+
+	tokio::spawn(async move {
+		while let Some(event) = tickers.next().await {
+			if let StreamEvent::Data(ticker) = event {
+				state.update_price(&ticker.symbol, ticker.last_price).await;
+			}
+		}
+	});
+}
+
+/// Refresh tradable pairs only, pairs that stopped trading are marked delisted and kept as they were
+async fn refresh_pairs<E>(
+	state: &AppState,
+	exchange: &E,
+	spot: &SpotMarket,
+	listings: &mut ListingTracker,
+) -> anyhow::Result<()>
+where
+	E: Exchange + Sync + ?Sized,
+{
+	let symbols = exchange.get_symbols().await.context("Failed to fetch symbols")?;
+	for event in listings.update(symbols) {
+		log_listing_event(&event);
+	}
+
+	// Served from the same cached symbols
+	let pairs = exchange.get_all_usdt_pairs().await.context("Failed to fetch USDT pairs")?;
+
+	for pair in state.mark_delisted(&pairs.iter().cloned().collect::<HashSet<_>>()).await {
+		eprintln!("{pair} is no longer trading, refresh stopped");
+	}
 
 	stream::iter(pairs)
 		.for_each_concurrent(PAIR_CONCURRENCY, |pair| async move {
-			if let Err(err) = refresh_pair(state, binance, &pair).await {
-				eprintln!("Failed to refresh {pair}: {err}");
-			}
+			// Failures are logged per timeframe
+			refresh_pair(state, exchange, spot, &pair).await.ok();
 		})
 		.await;
 
@@ -50,50 +103,159 @@ async fn refresh_pairs(state: &AppState, binance: &BinanceExchange) -> anyhow::R
 	Ok(())
 }
 
-async fn refresh_pair(state: &AppState, binance: &BinanceExchange, pair: &str) -> anyhow::Result<()> {
+fn log_listing_event(event: &ListingEvent) {
+	match event {
+		ListingEvent::Listed(info) => println!("New listing {} ({:?})", info.symbol, info.status),
+		ListingEvent::StatusChanged { previous, info } => {
+			println!("{} status changed {previous:?} -> {:?}", info.symbol, info.status);
+		},
+		ListingEvent::Removed(info) => println!("{} removed from the exchange", info.symbol),
+	}
+}
+
+/// Fetch every MFI timeframe and the spot comparison, and store the ones that succeeded.
+/// Fails only when no MFI timeframe could be fetched, with the error of the first one.
+pub async fn refresh_pair<E>(
+	state: &AppState,
+	exchange: &E,
+	spot: &SpotMarket,
+	pair: &str,
+) -> Result<PairSnapshot, ExchangeError>
+where
+	E: Exchange + Sync + ?Sized,
+{
 	let icon = icon_url(pair);
 	let updated_at = Utc::now();
 
 	let mut update = PairUpdate::default();
+	let mut errors = Vec::new();
+	let mut hourly = None;
 
-	match fetch_mfi(binance, pair, "1h").await {
-		Ok(snapshot) => {
-			update.mfi_1h = Some(snapshot.value);
-			update.price = Some(snapshot.price);
-		},
-		Err(err) => eprintln!("MFI 1h fetch failed for {pair}: {err}"),
-	}
+	for interval in MFI_INTERVALS {
+		let snapshot = match fetch_mfi(exchange, pair, interval).await {
+			Ok(snapshot) => snapshot,
+			Err(err) => {
+				// Young pairs don't have enough weekly candles yet, which is expected
+				if !matches!(err, ExchangeError::InsufficientData(_)) {
+					eprintln!("MFI {interval} fetch failed for {pair}: {err}");
+				}
+				errors.push(err);
+				continue;
+			},
+		};
 
-	match fetch_mfi(binance, pair, "4h").await {
-		Ok(snapshot) => update.mfi_4h = Some(snapshot.value),
-		Err(err) => eprintln!("MFI 4h fetch failed for {pair}: {err}"),
+		match interval {
+			Interval::Hour1 => {
+				update.mfi_1h = Some(snapshot.value);
+				update.price = Some(snapshot.price);
+				hourly = Some(snapshot.candles);
+			},
+			Interval::Hour4 => update.mfi_4h = Some(snapshot.value),
+			Interval::Day1 => update.mfi_1d = Some(snapshot.value),
+			_ => update.mfi_1w = Some(snapshot.value),
+		}
 	}
 
-	match fetch_mfi(binance, pair, "1d").await {
-		Ok(snapshot) => update.mfi_1d = Some(snapshot.value),
-		Err(err) => eprintln!("MFI 1d fetch failed for {pair}: {err}"),
+	if errors.len() == MFI_INTERVALS.len() {
+		return Err(errors.swap_remove(0));
 	}
 
-	match fetch_mfi(binance, pair, "1w").await {
-		Ok(snapshot) => update.mfi_1w = Some(snapshot.value),
-		Err(err) => eprintln!("MFI 1w fetch failed for {pair}: {err}"),
+	if let (Some(spot), Some(candles)) = (&spot.0, hourly) {
+		match compare_with_spot(spot.as_ref(), pair, &candles).await {
+			Ok(Some(comparison)) => update.spot = Some(comparison),
+			Ok(None) => {},
+			Err(err) => eprintln!("Spot comparison failed for {pair}: {err}"),
+		}
 	}
 
-	state.apply_update(pair.to_string(), icon, update, updated_at).await;
-	Ok(())
+	Ok(state.apply_update(pair.to_string(), icon, update, updated_at).await)
 }
 
-async fn fetch_mfi(binance: &BinanceExchange, pair: &str, interval: &str) -> anyhow::Result<MfiSnapshot> {
-	let candles = binance
-		.get_klines(pair, interval, KLINE_LIMIT)
-		.await
-		.with_context(|| format!("Failed to fetch klines for {pair} ({interval})"))?;
+async fn fetch_mfi<E>(exchange: &E, pair: &str, interval: Interval) -> Result<MfiSnapshot, ExchangeError>
+where
+	E: Exchange + Sync + ?Sized,
+{
+	let candles = fetch_klines(exchange, pair, interval).await?;
 
+	// The last candle is still open, its close is the latest traded price
 	let price = candles
 		.last()
-		.map(|candle| candle.open)
-		.ok_or_else(|| anyhow!("No candle data for {pair} ({interval})"))?;
-	let value = calculate_mfi(&candles, MFI_LENGTH).ok_or_else(|| anyhow!("Insufficient candle data for {pair} ({interval})"))?;
+		.map(|candle| candle.close)
+		.ok_or_else(|| ExchangeError::InsufficientData(format!("No candle data for {pair} ({interval})")))?;
+	let value = calculate_mfi(&candles, MFI_LENGTH).ok_or_else(|| {
+		ExchangeError::InsufficientData(format!("{} candles of {pair} ({interval}) are too few for MFI", candles.len()))
+	})?;
+
+	Ok(MfiSnapshot { value, price, candles })
+}
+
+/// Basis and market lead of `pair` against its spot pair, `None` when the spot market doesn't list it
+async fn compare_with_spot<S>(
+	spot: &S,
+	pair: &str,
+	perp_candles: &[CandleInfo],
+) -> Result<Option<SpotComparison>, ExchangeError>
+where
+	S: Exchange + Sync + ?Sized,
+{
+	let Some(spot_pair) = SpotPerpPair::find(pair, &spot.get_symbols().await?) else {
+		return Ok(None);
+	};
+	let spot_candles = fetch_klines(spot, &spot_pair.spot.venue_symbol, Interval::Hour1).await?;
+
+	Ok(Some(SpotComparison {
+		basis_percent: spot_pair.closed_basis_percent(perp_candles, &spot_candles),
+		market_lead: market_lead(perp_candles, &spot_candles, LEAD_CANDLES),
+	}))
+}
+
+/// Klines with retries of transient failures, other errors are returned right away
+async fn fetch_klines<E>(exchange: &E, pair: &str, interval: Interval) -> Result<Vec<CandleInfo>, ExchangeError>
+where
+	E: Exchange + Sync + ?Sized,
+{
+	let mut attempt = 1;
+
+	loop {
+		let err = match exchange.get_klines(pair, interval, KLINE_LIMIT).await {
+			Err(err) if attempt < MAX_FETCH_ATTEMPTS => err,
+			result => return result,
+		};
+		let Some(delay) = retry_delay(&err, attempt) else {
+			return Err(err);
+		};
 
-	Ok(MfiSnapshot { value, price })
+		eprintln!("Retrying klines for {pair} ({interval}) in {}s: {err}", delay.as_secs());
+		tokio::time::sleep(delay).await;
+		attempt += 1;
+	}
+}
+
+/// Delay before retrying `err`, at most `RATE_LIMIT_BACKOFF`, network errors back off linearly.
+///
+/// `None` for errors that won't clear up or that the client already retried, a rate limit with `retry_after`
+/// comes from Binance which waits out its own 429s before giving up.
+fn retry_delay(err: &ExchangeError, attempt: u32) -> Option<Duration> {
+	match err {
+		ExchangeError::RateLimited { retry_after: None } => Some(RATE_LIMIT_BACKOFF),
+		ExchangeError::Network(_) => Some((NETWORK_BACKOFF * attempt).min(RATE_LIMIT_BACKOFF)),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn retry_delay_follows_error_kind() {
+		let rate_limited = ExchangeError::RateLimited { retry_after: Some(Duration::from_secs(7)) };
+		let network = ExchangeError::Network(anyhow::anyhow!("Connection reset"));
+
+		assert_eq!(retry_delay(&rate_limited, 1), None);
+		assert_eq!(retry_delay(&ExchangeError::RateLimited { retry_after: None }, 2), Some(RATE_LIMIT_BACKOFF));
+		assert_eq!(retry_delay(&network, 2), Some(NETWORK_BACKOFF * 2));
+		assert_eq!(retry_delay(&network, 100), Some(RATE_LIMIT_BACKOFF));
+		assert_eq!(retry_delay(&ExchangeError::UnknownSymbol(String::from("BTCUSDT")), 1), None);
+	}
 }
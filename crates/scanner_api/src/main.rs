@@ -1,9 +1,11 @@
+use std::sync::Arc;
+
 use actix_web::{App, HttpServer, web};
-use exchanges::BinanceExchange;
+use exchanges::{BinanceExchange, BinanceMarket, BybitExchange, Exchange, ExchangeKind, OkxExchange};
 
-use crate::api::{add_comment, favorite_pair, get_pairs, remove_comment, unfavorite_pair};
+use crate::api::{add_comment, favorite_pair, get_pairs, refresh_pair_now, remove_comment, unfavorite_pair};
 use crate::cors::build_cors;
-use crate::fetcher::spawn_refresh_loop;
+use crate::fetcher::{SpotMarket, spawn_refresh_loop, spawn_ticker_loop};
 use crate::state::AppState;
 
 mod api;
@@ -16,9 +18,24 @@ mod state;
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
 	let state = AppState::load("state.json").await?;
-	let binance = BinanceExchange::new();
+	let exchange_kind = match std::env::var("EXCHANGE") {
+		Ok(value) => value.parse()?,
+		Err(_) => ExchangeKind::default(),
+	};
+
+	let exchange: Arc<dyn Exchange + Send + Sync> = match exchange_kind {
+		ExchangeKind::Binance => Arc::new(BinanceExchange::new()),
+		ExchangeKind::Bybit => Arc::new(BybitExchange::new()),
+		ExchangeKind::Okx => Arc::new(OkxExchange::new()),
+	};
+	// Basis is measured against the spot market of the same venue, only Binance has one so far
+	let spot = match exchange_kind {
+		ExchangeKind::Binance => SpotMarket(Some(Arc::new(BinanceExchange::builder().market(BinanceMarket::Spot).build()))),
+		ExchangeKind::Bybit | ExchangeKind::Okx => SpotMarket::default(),
+	};
 
-	spawn_refresh_loop(state.clone(), binance);
+	spawn_ticker_loop(state.clone(), exchange.as_ref());
+	spawn_refresh_loop(state.clone(), Arc::clone(&exchange), spot.clone());
 
 	HttpServer::new(move || {
 		let cors = build_cors();
@@ -26,11 +43,14 @@ async fn main() -> anyhow::Result<()> {
 		App::new()
 			.wrap(cors)
 			.app_data(web::Data::new(state.clone()))
+			.app_data(web::Data::from(Arc::clone(&exchange)))
+			.app_data(web::Data::new(spot.clone()))
 			.route("/pairs", web::get().to(get_pairs))
 			.route("/favorites/{pair}", web::post().to(favorite_pair))
 			.route("/favorites/{pair}", web::delete().to(unfavorite_pair))
 			.route("/comments/{pair}", web::post().to(add_comment))
 			.route("/comments/{pair}", web::delete().to(remove_comment))
+			.route("/refresh/{pair}", web::post().to(refresh_pair_now))
 	})
 	.bind(("0.0.0.0", 8080))?
 	.run()
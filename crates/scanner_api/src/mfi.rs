@@ -40,33 +40,34 @@ mod tests {
 	#[test]
 	fn test_mfi_basic() {
 		let candles = vec![
-			CandleInfo { open: 100.0, high: 105.0, low: 99.0, close: 103.0, volume: 1000.0 },
-			CandleInfo { open: 103.0, high: 108.0, low: 102.0, close: 107.0, volume: 1500.0 },
-			CandleInfo { open: 107.0, high: 110.0, low: 106.0, close: 108.0, volume: 1200.0 },
-			CandleInfo { open: 108.0, high: 109.0, low: 105.0, close: 106.0, volume: 800.0 },
-			CandleInfo { open: 106.0, high: 107.0, low: 104.0, close: 105.0, volume: 900.0 },
-			CandleInfo { open: 105.0, high: 106.0, low: 103.0, close: 104.0, volume: 1100.0 },
-			CandleInfo { open: 104.0, high: 105.0, low: 102.0, close: 103.0, volume: 1000.0 },
-			CandleInfo { open: 103.0, high: 104.0, low: 101.0, close: 102.0, volume: 950.0 },
-			CandleInfo { open: 102.0, high: 103.0, low: 100.0, close: 101.0, volume: 1050.0 },
-			CandleInfo { open: 101.0, high: 102.0, low: 99.0, close: 100.0, volume: 1100.0 },
-			CandleInfo { open: 100.0, high: 101.0, low: 98.0, close: 99.0, volume: 1200.0 },
-			CandleInfo { open: 99.0, high: 100.0, low: 97.0, close: 98.0, volume: 1300.0 },
-			CandleInfo { open: 98.0, high: 99.0, low: 96.0, close: 97.0, volume: 1400.0 },
-			CandleInfo { open: 97.0, high: 98.0, low: 95.0, close: 96.0, volume: 1500.0 },
-			CandleInfo { open: 96.0, high: 97.0, low: 94.0, close: 95.0, volume: 1600.0 },
+			CandleInfo { open: 100.0, high: 105.0, low: 99.0, close: 103.0, volume: 1000.0, ..Default::default() },
+			CandleInfo { open: 103.0, high: 108.0, low: 102.0, close: 107.0, volume: 1500.0, ..Default::default() },
+			CandleInfo { open: 107.0, high: 110.0, low: 106.0, close: 108.0, volume: 1200.0, ..Default::default() },
+			CandleInfo { open: 108.0, high: 109.0, low: 105.0, close: 106.0, volume: 800.0, ..Default::default() },
+			CandleInfo { open: 106.0, high: 107.0, low: 104.0, close: 105.0, volume: 900.0, ..Default::default() },
+			CandleInfo { open: 105.0, high: 106.0, low: 103.0, close: 104.0, volume: 1100.0, ..Default::default() },
+			CandleInfo { open: 104.0, high: 105.0, low: 102.0, close: 103.0, volume: 1000.0, ..Default::default() },
+			CandleInfo { open: 103.0, high: 104.0, low: 101.0, close: 102.0, volume: 950.0, ..Default::default() },
+			CandleInfo { open: 102.0, high: 103.0, low: 100.0, close: 101.0, volume: 1050.0, ..Default::default() },
+			CandleInfo { open: 101.0, high: 102.0, low: 99.0, close: 100.0, volume: 1100.0, ..Default::default() },
+			CandleInfo { open: 100.0, high: 101.0, low: 98.0, close: 99.0, volume: 1200.0, ..Default::default() },
+			CandleInfo { open: 99.0, high: 100.0, low: 97.0, close: 98.0, volume: 1300.0, ..Default::default() },
+			CandleInfo { open: 98.0, high: 99.0, low: 96.0, close: 97.0, volume: 1400.0, ..Default::default() },
+			CandleInfo { open: 97.0, high: 98.0, low: 95.0, close: 96.0, volume: 1500.0, ..Default::default() },
+			CandleInfo { open: 96.0, high: 97.0, low: 94.0, close: 95.0, volume: 1600.0, ..Default::default() },
 		];
 
 		let mfi = calculate_mfi(&candles, 14);
 		assert!(mfi.is_some());
 
 		let mfi_value = mfi.unwrap();
-		assert!(mfi_value >= 0.0 && mfi_value <= 100.0);
+		assert!((0.0..=100.0).contains(&mfi_value));
 	}
 
 	#[test]
 	fn test_insufficient_data() {
-		let candles = vec![CandleInfo { open: 100.0, high: 105.0, low: 99.0, close: 103.0, volume: 1000.0 }];
+		let candles =
+			vec![CandleInfo { open: 100.0, high: 105.0, low: 99.0, close: 103.0, volume: 1000.0, ..Default::default() }];
 
 		let mfi = calculate_mfi(&candles, 14);
 		assert!(mfi.is_none());
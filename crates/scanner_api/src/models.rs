@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use exchanges::{Instrument, MarketLead};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -11,14 +12,23 @@ pub struct PairSnapshot {
 	pub mfi_4h: f64,
 	pub mfi_1d: f64,
 	pub mfi_1w: f64,
+	/// Perpetual premium over spot in percent, `None` without a spot pair
+	#[serde(default)]
+	pub basis_percent: Option<f64>,
+	/// Whether spot or derivatives volume grew more within the last hours
+	#[serde(default)]
+	pub market_lead: Option<MarketLead>,
 	pub is_favorite: bool,
 	pub comments: Vec<String>,
+	/// No longer trading on the exchange, MFI and price are frozen at the last refresh
+	#[serde(default)]
+	pub is_delisted: bool,
 	pub updated_at: DateTime<Utc>,
 }
 
 impl PairSnapshot {
 	#[must_use]
-	pub fn new(pair: String, icon: String, updated_at: DateTime<Utc>) -> Self {
+	pub const fn new(pair: String, icon: String, updated_at: DateTime<Utc>) -> Self {
 		Self {
 			icon,
 			pair,
@@ -27,8 +37,11 @@ impl PairSnapshot {
 			mfi_4h: 0.0,
 			mfi_1d: 0.0,
 			mfi_1w: 0.0,
+			basis_percent: None,
+			market_lead: None,
 			is_favorite: false,
 			comments: Vec::new(),
+			is_delisted: false,
 			updated_at,
 		}
 	}
@@ -41,6 +54,16 @@ pub struct PairUpdate {
 	pub mfi_4h: Option<f64>,
 	pub mfi_1d: Option<f64>,
 	pub mfi_1w: Option<f64>,
+	/// Replaces both spot fields of the snapshot, so a comparison without a result clears the previous one
+	pub spot: Option<SpotComparison>,
+}
+
+/// Perpetual compared against its spot pair
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SpotComparison {
+	/// `None` when the markets share no closed candle
+	pub basis_percent: Option<f64>,
+	pub market_lead: Option<MarketLead>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -52,8 +75,11 @@ pub struct PairResponse {
 	pub mfi_4h: f64,
 	pub mfi_1d: f64,
 	pub mfi_1w: f64,
+	pub basis_percent: Option<f64>,
+	pub market_lead: Option<MarketLead>,
 	pub is_favorite: bool,
 	pub comments: Vec<String>,
+	pub is_delisted: bool,
 }
 
 impl From<&PairSnapshot> for PairResponse {
@@ -68,8 +94,11 @@ impl From<&PairSnapshot> for PairResponse {
 			mfi_4h: format_mfi(snapshot.mfi_4h),
 			mfi_1d: format_mfi(snapshot.mfi_1d),
 			mfi_1w: format_mfi(snapshot.mfi_1w),
+			basis_percent: snapshot.basis_percent.map(|basis| (basis * 1000.0).round() / 1000.0),
+			market_lead: snapshot.market_lead,
 			is_favorite: snapshot.is_favorite,
 			comments: snapshot.comments.clone(),
+			is_delisted: snapshot.is_delisted,
 		}
 	}
 }
@@ -81,6 +110,7 @@ pub enum SortKey {
 	Mfi4h,
 	Mfi1d,
 	Mfi1w,
+	Basis,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -95,9 +125,10 @@ pub struct SortField {
 	pub direction: SortDirection,
 }
 
+/// Icon of the canonical base asset, so `1000PEPEUSDT` shows the `PEPE` icon
 #[must_use]
 pub fn icon_url(pair: &str) -> String {
-	let base = pair.strip_suffix("USDT").unwrap_or(pair);
+	let base = Instrument::parse(pair).map_or_else(|| String::from(pair), |instrument| instrument.base.to_string());
 	format!("https://static-app.bb-os.com/icon/{base}.png")
 }
 
@@ -116,13 +147,23 @@ mod tests {
 			mfi_4h: 20.0,
 			mfi_1d: 30.0,
 			mfi_1w: 40.0,
+			basis_percent: Some(0.123_456),
+			market_lead: Some(MarketLead::Spot),
 			is_favorite: false,
 			comments: Vec::new(),
+			is_delisted: false,
 			updated_at: Utc::now(),
 		};
 
 		let response = PairResponse::from(&snapshot);
 
-		assert_eq!(response.price, 123.456);
+		assert!((response.price - 123.456).abs() < f64::EPSILON);
+		assert_eq!(response.basis_percent, Some(0.123));
+	}
+
+	#[test]
+	fn icon_url_uses_canonical_asset() {
+		assert_eq!(icon_url("1000PEPEUSDT"), "https://static-app.bb-os.com/icon/PEPE.png");
+		assert_eq!(icon_url("ETHUSDC"), "https://static-app.bb-os.com/icon/ETH.png");
 	}
 }
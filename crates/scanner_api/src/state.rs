@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::ErrorKind;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -17,7 +17,6 @@ pub struct AppState {
 }
 
 impl AppState {
-	#[must_use]
 	pub async fn load(storage_path: impl Into<PathBuf>) -> Result<Self> {
 		let storage_path = storage_path.into();
 		let pairs = match fs::read_to_string(&storage_path).await {
@@ -55,7 +54,13 @@ impl AppState {
 		pairs.values().cloned().collect()
 	}
 
-	pub async fn apply_update(&self, pair: String, icon: String, update: PairUpdate, updated_at: DateTime<Utc>) {
+	pub async fn apply_update(
+		&self,
+		pair: String,
+		icon: String,
+		update: PairUpdate,
+		updated_at: DateTime<Utc>,
+	) -> PairSnapshot {
 		let mut pairs = self.pairs.write().await;
 		let entry = pairs.entry(pair.clone()).or_insert_with(|| PairSnapshot::new(pair, icon.clone(), updated_at));
 
@@ -75,7 +80,40 @@ impl AppState {
 		if let Some(value) = update.mfi_1w {
 			entry.mfi_1w = value;
 		}
+		if let Some(spot) = update.spot {
+			entry.basis_percent = spot.basis_percent;
+			entry.market_lead = spot.market_lead;
+		}
 		entry.updated_at = updated_at;
+		let snapshot = entry.clone();
+		drop(pairs);
+
+		snapshot
+	}
+
+	/// Update the price of a known pair, unknown pairs are left for the next refresh
+	pub async fn update_price(&self, pair: &str, price: f64) {
+		let mut pairs = self.pairs.write().await;
+		if let Some(entry) = pairs.get_mut(pair) {
+			entry.price = price;
+		}
+	}
+
+	/// Flag pairs missing from `tradable` as delisted and clear the flag on the rest, returns the newly delisted pairs
+	pub async fn mark_delisted(&self, tradable: &HashSet<String>) -> Vec<String> {
+		let mut pairs = self.pairs.write().await;
+		let mut delisted = Vec::new();
+
+		for (pair, entry) in pairs.iter_mut() {
+			let is_delisted = !tradable.contains(pair);
+			if is_delisted && !entry.is_delisted {
+				delisted.push(pair.clone());
+			}
+			entry.is_delisted = is_delisted;
+		}
+		drop(pairs);
+
+		delisted
 	}
 
 	pub async fn favorite_pair(&self, pair: &str) -> Result<PairSnapshot> {
@@ -85,7 +123,9 @@ impl AppState {
 				.entry(pair.to_string())
 				.or_insert_with(|| PairSnapshot::new(pair.to_string(), icon_url(pair), Utc::now()));
 			entry.is_favorite = true;
-			entry.clone()
+			let snapshot = entry.clone();
+			drop(pairs);
+			snapshot
 		};
 
 		self.persist().await?;
@@ -100,7 +140,9 @@ impl AppState {
 				return Ok(None);
 			};
 			entry.is_favorite = false;
-			Some(entry.clone())
+			let snapshot = entry.clone();
+			drop(pairs);
+			Some(snapshot)
 		};
 
 		if snapshot.is_some() {
@@ -117,7 +159,9 @@ impl AppState {
 				.entry(pair.to_string())
 				.or_insert_with(|| PairSnapshot::new(pair.to_string(), icon_url(pair), Utc::now()));
 			entry.comments.push(comment);
-			entry.clone()
+			let snapshot = entry.clone();
+			drop(pairs);
+			snapshot
 		};
 
 		self.persist().await?;
@@ -131,12 +175,12 @@ impl AppState {
 			let Some(entry) = pairs.get_mut(pair) else {
 				return Ok(None);
 			};
-			if let Some(index) = entry.comments.iter().position(|value| value == comment) {
+			let snapshot = entry.comments.iter().position(|value| value == comment).map(|index| {
 				entry.comments.remove(index);
-				Some(entry.clone())
-			} else {
-				None
-			}
+				entry.clone()
+			});
+			drop(pairs);
+			snapshot
 		};
 
 		if snapshot.is_some() {
@@ -154,7 +198,7 @@ mod tests {
 
 	fn temp_state_path() -> PathBuf {
 		let mut path = std::env::temp_dir();
-		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_nanos()).unwrap_or(0);
+		let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos());
 		path.push(format!("scanner_api_state_{nanos}.json"));
 		path
 	}
@@ -189,6 +233,40 @@ mod tests {
 		let _ = std::fs::remove_file(path);
 	}
 
+	#[tokio::test]
+	async fn updates_price_of_known_pairs_only() {
+		let path = temp_state_path();
+		let state = AppState::load(&path).await.unwrap();
+
+		state.favorite_pair("ETHUSDT").await.unwrap();
+		state.update_price("ETHUSDT", 3_500.0).await;
+		state.update_price("BTCUSDT", 97_000.0).await;
+
+		let pairs = state.list_pairs().await;
+		assert_eq!(pairs.len(), 1);
+		assert!((pairs[0].price - 3_500.0).abs() < f64::EPSILON);
+
+		let _ = std::fs::remove_file(path);
+	}
+
+	#[tokio::test]
+	async fn marks_pairs_missing_from_exchange_as_delisted() {
+		let path = temp_state_path();
+		let state = AppState::load(&path).await.unwrap();
+
+		state.favorite_pair("ETHUSDT").await.unwrap();
+		state.favorite_pair("OLDUSDT").await.unwrap();
+
+		let tradable = HashSet::from([String::from("ETHUSDT")]);
+		assert_eq!(state.mark_delisted(&tradable).await, vec!["OLDUSDT"]);
+		assert!(state.mark_delisted(&tradable).await.is_empty());
+
+		let pairs = state.list_pairs().await;
+		assert!(pairs.iter().all(|pair| pair.is_delisted == (pair.pair == "OLDUSDT")));
+
+		let _ = std::fs::remove_file(path);
+	}
+
 	#[tokio::test]
 	async fn persists_state_to_disk() {
 		let path = temp_state_path();